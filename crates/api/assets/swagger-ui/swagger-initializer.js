@@ -0,0 +1,12 @@
+// Renders the API document served by the service. Tokens entered with "Authorize" are
+// kept in memory only, so they are gone once the page is closed.
+window.onload = function () {
+  window.ui = SwaggerUIBundle({
+    url: "/openapi.json",
+    dom_id: "#swagger-ui",
+    deepLinking: true,
+    persistAuthorization: false,
+    presets: [SwaggerUIBundle.presets.apis],
+    layout: "BaseLayout",
+  });
+};
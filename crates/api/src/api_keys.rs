@@ -0,0 +1,89 @@
+//! API keys used by game servers and other services instead of a user token.
+
+use economy_service_entity::api_key;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+use crate::responses::{ApiError, ErrorCode};
+
+/// Beginning of every key, so that leaked keys are easy to recognize
+const KEY_PREFIX: &str = "esk_";
+
+/// Length of the beginning of a key stored in plain text to tell keys apart
+const DISPLAYED_PREFIX_LEN: usize = 12;
+
+/// Operation an API key is allowed to perform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Scope {
+    /// Read economy states, including freezes shown to bankers
+    ReadBalances,
+    /// Pay from the shared account
+    PayFrom(i32),
+    /// Mint and burn money
+    Mint,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read:balances" => Ok(Scope::ReadBalances),
+            "mint" => Ok(Scope::Mint),
+            _ => s
+                .strip_prefix("pay:from:")
+                .and_then(|id| id.parse().ok())
+                .map(Scope::PayFrom)
+                .ok_or_else(|| format!("Unknown scope {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::ReadBalances => write!(f, "read:balances"),
+            Scope::PayFrom(account_id) => write!(f, "pay:from:{}", account_id),
+            Scope::Mint => write!(f, "mint"),
+        }
+    }
+}
+
+/// Key generated for a service along with what is stored of it
+pub(crate) struct GeneratedKey {
+    /// The key itself, shown only once
+    pub key: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+/// Generates a random key
+pub(crate) fn generate_key() -> GeneratedKey {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    let key = format!("{}{}", KEY_PREFIX, hex::encode(bytes));
+    GeneratedKey {
+        prefix: key[..DISPLAYED_PREFIX_LEN].to_string(),
+        hash: hash_key(&key),
+        key,
+    }
+}
+
+/// Hex-encoded SHA-256 hash of the key, the only form keys are stored in
+pub(crate) fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Fails unless the key is granted the scope
+pub(crate) fn require_scope(key: &api_key::Model, scope: Scope) -> Result<(), ApiError> {
+    if key.scopes.0.iter().any(|s| s.parse() == Ok(scope)) {
+        Ok(())
+    } else {
+        Err(ApiError::new(
+            ErrorCode::MissingScope,
+            format!("API key is missing the {} scope", scope),
+        ))
+    }
+}
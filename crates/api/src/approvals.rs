@@ -0,0 +1,52 @@
+//! Approval of large mints and treasury payments by several users before they're executed.
+
+use chrono::Duration;
+use economy_service_core::ApprovalPolicy;
+use serde::Deserialize;
+
+/// Approvals configuration, read from `APPROVALS_*` environment variables.
+/// Operations are executed right away unless their threshold is set.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApprovalsConfig {
+    /// Absolute amount of mints and burns a banker can make within the window without approval
+    mint_threshold: Option<i32>,
+
+    /// Amount a member can pay from an organization account within the window without approval
+    treasury_payment_threshold: Option<i32>,
+
+    /// Amount of distinct approvers needed, not counting the proposer
+    #[serde(default = "default_required")]
+    required: i32,
+
+    /// Hours after which unapproved proposals expire
+    #[serde(default = "default_expiry_hours")]
+    expiry_hours: i64,
+
+    /// Hours over which amounts of a user add up towards the thresholds
+    #[serde(default = "default_window_hours")]
+    window_hours: i64,
+}
+
+fn default_required() -> i32 {
+    2
+}
+
+fn default_expiry_hours() -> i64 {
+    72
+}
+
+fn default_window_hours() -> i64 {
+    24
+}
+
+impl From<ApprovalsConfig> for ApprovalPolicy {
+    fn from(config: ApprovalsConfig) -> Self {
+        ApprovalPolicy {
+            mint_threshold: config.mint_threshold,
+            treasury_payment_threshold: config.treasury_payment_threshold,
+            required: config.required.max(1),
+            expiry: Duration::hours(config.expiry_hours),
+            window: Duration::hours(config.window_hours),
+        }
+    }
+}
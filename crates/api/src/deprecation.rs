@@ -0,0 +1,32 @@
+//! Deprecation of the unversioned routes, which predate `/v1`.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+/// Marks responses of unversioned routes as deprecated and links them to their successors,
+/// found by prepending the given prefix to the path
+pub(crate) async fn deprecate_unversioned<B>(
+    State(prefix): State<&'static str>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let successor = request
+        .uri()
+        .path_and_query()
+        .map(|path| format!("<{}{}>; rel=\"successor-version\"", prefix, path))
+        .and_then(|link| HeaderValue::from_str(&link).ok());
+
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    if let Some(successor) = successor {
+        headers.insert(header::LINK, successor);
+    }
+
+    response
+}
@@ -0,0 +1,213 @@
+use economy_service_core::{get_last_transaction_id, get_transactions_after};
+use economy_service_entity::transaction;
+use sea_orm::{DbConn, DbErr};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, OnceCell};
+
+/// Amount of balance changes buffered for slow subscribers.
+/// Subscribers that fall further behind catch up from the database.
+const BUFFER_SIZE: usize = 1024;
+
+/// Delay between two looks for new transactions
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Amount of transactions fetched from the database at once
+const BATCH_SIZE: u64 = 500;
+
+/// How long a missing transaction ID is waited for before it's assumed to be rolled back.
+/// IDs are taken when transactions are recorded, so they can be committed out of order.
+const GAP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Broadcast of committed balance changes, fed by following the transactions table.
+/// Every change is published no matter what recorded it, be it a request handler, a background
+/// task, the CLI or another replica. Following starts with the first subscriber.
+#[derive(Clone, Debug)]
+pub(crate) struct BalanceEvents {
+    sender: broadcast::Sender<transaction::Model>,
+    conn: DbConn,
+    started: Arc<OnceCell<()>>,
+}
+impl BalanceEvents {
+    pub(crate) fn new(conn: DbConn) -> Self {
+        BalanceEvents {
+            sender: broadcast::channel(BUFFER_SIZE).0,
+            conn,
+            started: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Subscribes to transactions committed from now on
+    pub(crate) async fn subscribe(&self) -> Result<broadcast::Receiver<transaction::Model>, DbErr> {
+        // subscribe before following starts, so the receiver gets everything it publishes
+        let receiver = self.sender.subscribe();
+
+        self.started
+            .get_or_try_init(|| async {
+                let cursor = get_last_transaction_id(&self.conn).await?;
+                tokio::spawn(follow_transactions(
+                    self.conn.clone(),
+                    self.sender.clone(),
+                    cursor,
+                ));
+                Ok::<_, DbErr>(())
+            })
+            .await?;
+
+        Ok(receiver)
+    }
+}
+
+/// Publishes transactions as they are committed until the process exits
+async fn follow_transactions(
+    conn: DbConn,
+    sender: broadcast::Sender<transaction::Model>,
+    cursor: i32,
+) {
+    let mut feed = Feed::new(cursor);
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        match get_transactions_after(feed.cursor, BATCH_SIZE, &conn).await {
+            Ok(records) => {
+                for record in feed.receive(records, Instant::now()) {
+                    // sending only fails when nobody is subscribed
+                    let _ = sender.send(record);
+                }
+            }
+            Err(err) => tracing::error!("failed to follow transactions: {}", err),
+        }
+    }
+}
+
+/// Position in the transactions table. Transactions are published as soon as they are seen,
+/// while the cursor stays before the lowest missing ID until it shows up or times out.
+struct Feed {
+    /// ID up to which every transaction was published or given up on
+    cursor: i32,
+
+    /// Published transactions past the cursor
+    seen: BTreeSet<i32>,
+
+    /// Missing IDs right past the cursor, along with when they were first missed
+    gaps: HashMap<i32, Instant>,
+}
+impl Feed {
+    fn new(cursor: i32) -> Self {
+        Feed {
+            cursor,
+            seen: BTreeSet::new(),
+            gaps: HashMap::new(),
+        }
+    }
+
+    /// Returns the transactions that weren't published yet and moves the cursor
+    fn receive(
+        &mut self,
+        records: Vec<transaction::Model>,
+        now: Instant,
+    ) -> Vec<transaction::Model> {
+        let fresh: Vec<_> = records
+            .into_iter()
+            .filter(|record| self.seen.insert(record.id))
+            .collect();
+
+        while let Some(&next_seen) = self.seen.iter().next() {
+            let next = self.cursor + 1;
+            if next_seen == next {
+                self.seen.remove(&next);
+                self.gaps.remove(&next);
+                self.cursor = next;
+                continue;
+            }
+
+            let missed_at = *self.gaps.entry(next).or_insert(now);
+            if now.duration_since(missed_at) < GAP_TIMEOUT {
+                break;
+            }
+            self.gaps.remove(&next);
+            self.cursor = next;
+        }
+
+        fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use economy_service_entity::transaction::TransactionKind;
+
+    fn record(id: i32) -> transaction::Model {
+        transaction::Model {
+            id,
+            kind: TransactionKind::Payment,
+            payer_id: Some(1),
+            payee_id: Some(2),
+            payer_account_id: None,
+            payee_account_id: None,
+            initiator_id: None,
+            api_key_id: None,
+            amount: 1,
+            comment: None,
+            created_at: Utc::now(),
+            reverses_id: None,
+            reversed_amount: 0,
+            loan_id: None,
+            payer_balance: None,
+            payee_balance: None,
+        }
+    }
+
+    fn ids(records: Vec<transaction::Model>) -> Vec<i32> {
+        records.into_iter().map(|record| record.id).collect()
+    }
+
+    #[test]
+    fn publishes_each_transaction_once() {
+        let mut feed = Feed::new(0);
+        let now = Instant::now();
+
+        assert_eq!(ids(feed.receive(vec![record(1), record(2)], now)), [1, 2]);
+        assert_eq!(feed.cursor, 2);
+        assert_eq!(ids(feed.receive(vec![], now)), Vec::<i32>::new());
+        assert_eq!(ids(feed.receive(vec![record(3)], now)), [3]);
+        assert_eq!(feed.cursor, 3);
+    }
+
+    #[test]
+    fn waits_for_transactions_committed_out_of_order() {
+        let mut feed = Feed::new(0);
+        let now = Instant::now();
+
+        // 2 is recorded but not committed yet, while 3 is
+        assert_eq!(ids(feed.receive(vec![record(1), record(3)], now)), [1, 3]);
+        assert_eq!(feed.cursor, 1);
+
+        // 3 is fetched again, but published only once
+        let later = now + Duration::from_secs(1);
+        assert_eq!(ids(feed.receive(vec![record(2), record(3)], later)), [2]);
+        assert_eq!(feed.cursor, 3);
+    }
+
+    #[test]
+    fn gives_up_on_rolled_back_transactions() {
+        let mut feed = Feed::new(0);
+        let now = Instant::now();
+
+        feed.receive(vec![record(2)], now);
+        assert_eq!(feed.cursor, 0);
+
+        feed.receive(vec![record(2)], now + GAP_TIMEOUT / 2);
+        assert_eq!(feed.cursor, 0);
+
+        feed.receive(vec![record(2)], now + GAP_TIMEOUT);
+        assert_eq!(feed.cursor, 2);
+        assert!(feed.seen.is_empty());
+        assert!(feed.gaps.is_empty());
+    }
+}
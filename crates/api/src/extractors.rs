@@ -1,45 +1,286 @@
-use crate::{responses::AppError, AppState};
+use crate::{
+    api_keys::hash_key,
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
 use axum::{
-    extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
-    Json,
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        ConnectInfo, FromRequest, FromRequestParts,
+    },
+    http::{request::Parts, Request},
+    response::{IntoResponse, Response},
 };
+pub(crate) use economy_service_core::Actor;
+use economy_service_core::{get_active_api_key, touch_api_key, AuditContext, AuditEntryForm};
+use economy_service_entity::{api_key, audit_log_entry::AuditAction};
+use serde::{de::DeserializeOwned, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use users_service_client::{GetSelfResponse, User};
 
 pub(crate) struct AuthenticatedUser(pub User);
 
 #[axum::async_trait]
 impl FromRequestParts<AppState> for AuthenticatedUser {
-    type Rejection = (StatusCode, Json<AppError>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
+        // Reuse the user if they were already authenticated for this request
+        if let Some(user) = parts.extensions.get::<User>() {
+            return Ok(AuthenticatedUser(user.clone()));
+        }
+
+        if !parts.headers.contains_key("x-token") && parts.headers.contains_key("x-api-key") {
+            return Err(ApiError::new(
+                ErrorCode::NotAllowed,
+                "API keys can't be used for this operation",
+            ));
+        }
+
         // Extract token from header
         let token = parts
             .headers
             .get("x-token")
             .and_then(|t| t.to_str().ok())
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                Json(AppError::new("No token provided")),
+            .ok_or(ApiError::new(
+                ErrorCode::Unauthenticated,
+                "No token provided",
             ))?;
 
         // Get user
-        let res = state.users_client.get_self(token).await.map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })?;
+        let res = state
+            .users_client
+            .get_self(token)
+            .await
+            .map_err(ApiError::upstream)?;
 
         match res {
-            GetSelfResponse::Ok(user) => Ok(AuthenticatedUser(user)),
-            GetSelfResponse::Unauthenticated => Err((
-                StatusCode::UNAUTHORIZED,
-                Json(AppError::new("Authentication failed")),
+            GetSelfResponse::Ok(user) => {
+                parts.extensions.insert(user.clone());
+                Ok(AuthenticatedUser(user))
+            }
+            GetSelfResponse::Unauthenticated => Err(ApiError::new(
+                ErrorCode::Unauthenticated,
+                "Authentication failed",
+            )),
+        }
+    }
+}
+
+/// Caller authenticated either with a user token in `x-token` or with an API key in `x-api-key`
+pub(crate) enum Caller {
+    User(User),
+    ApiKey(api_key::Model),
+}
+impl Caller {
+    /// Performer of the operations requested by the caller
+    pub(crate) fn actor(&self) -> Actor {
+        match self {
+            Caller::User(user) => Actor::User(user.id),
+            Caller::ApiKey(key) => Actor::ApiKey {
+                id: key.id,
+                created_by: key.created_by,
+            },
+        }
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Caller {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // Reuse the key if it was already checked for this request
+        if let Some(key) = parts.extensions.get::<api_key::Model>() {
+            return Ok(Caller::ApiKey(key.clone()));
+        }
+
+        let key = match parts.headers.get("x-api-key") {
+            Some(key) => key.to_str().unwrap_or_default(),
+            None => {
+                return AuthenticatedUser::from_request_parts(parts, state)
+                    .await
+                    .map(|AuthenticatedUser(user)| Caller::User(user))
+            }
+        };
+
+        let key = get_active_api_key(&hash_key(key), &state.conn)
+            .await?
+            .ok_or(ApiError::new(ErrorCode::Unauthenticated, "Invalid API key"))?;
+
+        touch_api_key(key.id, &state.conn).await?;
+
+        parts.extensions.insert(key.clone());
+        Ok(Caller::ApiKey(key))
+    }
+}
+
+/// IP address of the client. Taken from `x-forwarded-for` when the service is configured to trust it.
+pub(crate) struct ClientIp(pub IpAddr);
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if state.trust_forwarded_for {
+            let forwarded = parts
+                .headers
+                .get("x-forwarded-for")
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.split(',').next())
+                .and_then(|ip| ip.trim().parse().ok());
+
+            if let Some(ip) = forwarded {
+                return Ok(ClientIp(ip));
+            }
+        }
+
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| ClientIp(addr.ip()))
+            .ok_or(ApiError::new(
+                ErrorCode::Internal,
+                "Client address is unavailable",
+            ))
+    }
+}
+
+/// Request details recorded along with privileged operations
+#[derive(Clone)]
+pub(crate) struct RequestContext {
+    pub request_id: Option<String>,
+    pub client_ip: Option<IpAddr>,
+}
+impl RequestContext {
+    /// Details of this request recorded in the audit log
+    pub(crate) fn audit(&self) -> AuditContext {
+        AuditContext {
+            request_id: self.request_id.clone(),
+            client_ip: self.client_ip.map(|ip| ip.to_string()),
+        }
+    }
+
+    /// Starts an audit log entry of an operation performed within this request
+    pub(crate) fn audit_entry(
+        &self,
+        actor_id: i32,
+        action: AuditAction,
+        target: String,
+    ) -> AuditEntryForm {
+        self.audit().entry(actor_id, action, target)
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RequestContext {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .headers
+            .get("x-request-id")
+            .and_then(|id| id.to_str().ok())
+            .map(String::from);
+
+        let client_ip = ClientIp::from_request_parts(parts, state)
+            .await
+            .ok()
+            .map(|ClientIp(ip)| ip);
+
+        Ok(RequestContext {
+            request_id,
+            client_ip,
+        })
+    }
+}
+
+/// JSON request or response body. A body that can't be read is rejected with `validation_failed`,
+/// or with `unsupported_media_type` when it isn't sent as JSON.
+pub(crate) struct Json<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S, B> FromRequest<S, B> for Json<T>
+where
+    T: DeserializeOwned,
+    axum::Json<T>: FromRequest<S, B, Rejection = JsonRejection>,
+    S: Send + Sync,
+    B: Send + 'static,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(request, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            Err(JsonRejection::MissingJsonContentType(rejection)) => Err(ApiError::new(
+                ErrorCode::UnsupportedMediaType,
+                rejection.to_string(),
+            )),
+            Err(rejection) => Err(ApiError::new(
+                ErrorCode::ValidationFailed,
+                rejection.to_string(),
             )),
         }
     }
 }
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Path parameters, rejected with `validation_failed` when they can't be parsed
+pub(crate) struct Path<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Path::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Path(value)| Path(value))
+            .map_err(|rejection: PathRejection| {
+                ApiError::new(ErrorCode::ValidationFailed, rejection.to_string())
+            })
+    }
+}
+
+/// Query string, rejected with `validation_failed` when it can't be parsed
+pub(crate) struct Query<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Query(value))
+            .map_err(|rejection: QueryRejection| {
+                ApiError::new(ErrorCode::ValidationFailed, rejection.to_string())
+            })
+    }
+}
@@ -0,0 +1,305 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{self, request::Parts, StatusCode},
+};
+use economy_service_client::models::{AccountTier, EconomyState};
+use economy_service_core::{
+    get_or_create_economy_state, get_outstanding_debt, Approval, LedgerParty,
+};
+use economy_service_entity::{account_freeze, transaction};
+use std::time::SystemTime;
+use tonic::{Code, Request, Response, Status};
+use tower_http::request_id::{MakeRequestId, MakeRequestUuid};
+
+use crate::{
+    api_keys::{require_scope, Scope},
+    extractors::{AuthenticatedUser, Caller, ClientIp, RequestContext},
+    rate_limit::{caller_key, RateLimitKey, RateLimiter},
+    responses::{economy_state, ApiError},
+    routes::{
+        find_economy_state_details, make_payment, mint_money, DataAddMoney, DataPay,
+        EconomyStateDetails,
+    },
+    AppState,
+};
+
+pub(crate) mod proto {
+    tonic::include_proto!("economy");
+}
+
+use proto::economy_server::{Economy, EconomyServer};
+
+/// gRPC counterpart of the REST API, sharing its state, authentication and rate limits
+pub(crate) struct EconomyService {
+    state: AppState,
+}
+impl EconomyService {
+    pub(crate) fn server(state: AppState) -> EconomyServer<Self> {
+        EconomyServer::new(EconomyService { state })
+    }
+
+    /// Turns gRPC request metadata into HTTP request parts, so the REST extractors can be reused.
+    /// Like HTTP requests, every call gets a request ID generated by us, whatever the client sent.
+    fn request_parts<T>(request: &Request<T>) -> Parts {
+        let (mut parts, _) = http::Request::new(()).into_parts();
+        parts.headers = request.metadata().clone().into_headers();
+        parts.headers.remove("x-request-id");
+        if let Some(request_id) = MakeRequestUuid.make_request_id(&http::Request::new(())) {
+            parts
+                .headers
+                .insert("x-request-id", request_id.header_value().clone());
+        }
+        if let Some(addr) = request.remote_addr() {
+            parts.extensions.insert(ConnectInfo(addr));
+        }
+        parts
+    }
+
+    async fn limit(&self, limiter: &RateLimiter, parts: &mut Parts) -> Result<(), Status> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, &self.state)
+            .await
+            .map_err(|err| status(parts, err))?;
+        let too_many_requests = |_| Status::resource_exhausted("Too many requests");
+        limiter
+            .check(&[RateLimitKey::Ip(ip)])
+            .map_err(too_many_requests)?;
+
+        if let Some(key) = caller_key(parts, &self.state).await {
+            limiter.check(&[key]).map_err(too_many_requests)?;
+        }
+        Ok(())
+    }
+
+    async fn authenticate(&self, parts: &mut Parts) -> Result<AuthenticatedUser, Status> {
+        AuthenticatedUser::from_request_parts(parts, &self.state)
+            .await
+            .map_err(|err| status(parts, err))
+    }
+
+    async fn caller(&self, parts: &mut Parts) -> Result<Caller, Status> {
+        Caller::from_request_parts(parts, &self.state)
+            .await
+            .map_err(|err| status(parts, err))
+    }
+}
+
+#[tonic::async_trait]
+impl Economy for EconomyService {
+    async fn get_balance(
+        &self,
+        request: Request<proto::GetBalanceRequest>,
+    ) -> Result<Response<proto::EconomyState>, Status> {
+        let mut parts = Self::request_parts(&request);
+        self.limit(&self.state.rate_limits.reads, &mut parts)
+            .await?;
+
+        let caller = match parts.headers.contains_key("x-token")
+            || parts.headers.contains_key("x-api-key")
+        {
+            true => self.caller(&mut parts).await.ok(),
+            false => None,
+        };
+
+        find_economy_state_details(&self.state, request.get_ref().user_id, caller.as_ref())
+            .await
+            .map(|details| Response::new(details.into()))
+            .map_err(|err| status(&parts, err))
+    }
+
+    async fn get_self(
+        &self,
+        request: Request<proto::GetSelfRequest>,
+    ) -> Result<Response<proto::EconomyState>, Status> {
+        let mut parts = Self::request_parts(&request);
+        self.limit(&self.state.rate_limits.reads, &mut parts)
+            .await?;
+        let AuthenticatedUser(user) = self.authenticate(&mut parts).await?;
+
+        let user_state = get_or_create_economy_state(user.id, &self.state.conn)
+            .await
+            .map_err(|err| status(&parts, err.into()))?;
+        let debt = get_outstanding_debt(user.id, &self.state.conn)
+            .await
+            .map_err(|err| status(&parts, err.into()))?;
+
+        Ok(Response::new(economy_state(user_state, debt).into()))
+    }
+
+    async fn pay(
+        &self,
+        request: Request<proto::PayRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        let mut parts = Self::request_parts(&request);
+        self.limit(&self.state.rate_limits.writes, &mut parts)
+            .await?;
+        let AuthenticatedUser(payer) = self.authenticate(&mut parts).await?;
+
+        let request = request.into_inner();
+        let data = DataPay {
+            amount: request.amount,
+            comment: request.comment,
+        };
+
+        make_payment(
+            &self.state,
+            &payer,
+            LedgerParty::User(request.payee_id),
+            data,
+        )
+        .await
+        .map(|record| Response::new(record.into()))
+        .map_err(|err| status(&parts, err))
+    }
+
+    async fn mint(
+        &self,
+        request: Request<proto::MintRequest>,
+    ) -> Result<Response<proto::MintResponse>, Status> {
+        let mut parts = Self::request_parts(&request);
+        self.limit(&self.state.rate_limits.writes, &mut parts)
+            .await?;
+        let caller = self.caller(&mut parts).await?;
+        if let Caller::ApiKey(key) = &caller {
+            require_scope(key, Scope::Mint).map_err(|err| status(&parts, err))?;
+        }
+        let ctx = RequestContext::from_request_parts(&mut parts, &self.state)
+            .await
+            .map_err(|err| status(&parts, err))?;
+
+        let request = request.into_inner();
+        let data = DataAddMoney {
+            amount: request.amount,
+            reason: request.reason,
+        };
+
+        let result = match mint_money(
+            &self.state,
+            caller.actor(),
+            request.user_id,
+            data,
+            ctx,
+            None,
+        )
+        .await
+        .map_err(|err| status(&parts, err))?
+        {
+            Approval::Executed(record) => proto::mint_response::Result::Transaction(record.into()),
+            Approval::Proposed(proposal) => proto::mint_response::Result::ProposalId(proposal.id),
+        };
+        Ok(Response::new(proto::MintResponse {
+            result: Some(result),
+        }))
+    }
+}
+
+/// Converts REST API error into gRPC status, passing its code in `error-code` metadata.
+/// Like HTTP responses, the status carries the ID of the call that internal errors are logged with.
+fn status(parts: &Parts, error: ApiError) -> Status {
+    let code = match error.status() {
+        StatusCode::BAD_REQUEST => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::CONFLICT => Code::FailedPrecondition,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::UNPROCESSABLE_ENTITY => {
+            Code::InvalidArgument
+        }
+        StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+        _ => Code::Internal,
+    };
+
+    let request_id = parts.headers.get("x-request-id");
+    if let Some(cause) = error.cause() {
+        let request_id = request_id
+            .and_then(|id| id.to_str().ok())
+            .unwrap_or_default();
+        tracing::error!(request_id = %request_id, "gRPC call failed: {}", cause);
+    }
+
+    let error = *error.error;
+    let mut status = Status::new(code, error.detail);
+    if let Ok(serde_json::Value::String(code)) = serde_json::to_value(error.code) {
+        // codes are snake_case ASCII, so they are valid metadata values
+        status
+            .metadata_mut()
+            .insert("error-code", code.parse().unwrap());
+    }
+    if let Some(request_id) = request_id.and_then(|id| id.to_str().ok()?.parse().ok()) {
+        status.metadata_mut().insert("x-request-id", request_id);
+    }
+    status
+}
+
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    SystemTime::from(time).into()
+}
+
+impl From<EconomyState> for proto::EconomyState {
+    fn from(state: EconomyState) -> Self {
+        let tier = match state.tier {
+            AccountTier::Standard => proto::AccountTier::Standard,
+            AccountTier::Verified => proto::AccountTier::Verified,
+            AccountTier::Merchant => proto::AccountTier::Merchant,
+        };
+
+        proto::EconomyState {
+            user_id: state.user_id,
+            balance: state.balance,
+            banker: state.banker,
+            tier: tier.into(),
+            freeze: None,
+            debt: state.debt,
+        }
+    }
+}
+
+impl From<EconomyStateDetails> for proto::EconomyState {
+    fn from(details: EconomyStateDetails) -> Self {
+        proto::EconomyState {
+            freeze: details.freeze.map(Into::into),
+            ..details.state.into()
+        }
+    }
+}
+
+impl From<account_freeze::Model> for proto::AccountFreeze {
+    fn from(freeze: account_freeze::Model) -> Self {
+        proto::AccountFreeze {
+            reason: freeze.reason,
+            actor_id: freeze.actor_id,
+            block_incoming: freeze.block_incoming,
+            created_at: Some(timestamp(freeze.created_at)),
+            expires_at: freeze.expires_at.map(timestamp),
+        }
+    }
+}
+
+impl From<transaction::Model> for proto::Transaction {
+    fn from(record: transaction::Model) -> Self {
+        let kind = match record.kind {
+            transaction::TransactionKind::Payment => proto::TransactionKind::Payment,
+            transaction::TransactionKind::Mint => proto::TransactionKind::Mint,
+            transaction::TransactionKind::Burn => proto::TransactionKind::Burn,
+            transaction::TransactionKind::Import => proto::TransactionKind::Import,
+            transaction::TransactionKind::Reversal => proto::TransactionKind::Reversal,
+            transaction::TransactionKind::Loan => proto::TransactionKind::Loan,
+            transaction::TransactionKind::Repayment => proto::TransactionKind::Repayment,
+        };
+
+        proto::Transaction {
+            id: record.id,
+            kind: kind.into(),
+            payer_id: record.payer_id,
+            payee_id: record.payee_id,
+            amount: record.amount,
+            comment: record.comment,
+            created_at: Some(timestamp(record.created_at)),
+            payer_account_id: record.payer_account_id,
+            payee_account_id: record.payee_account_id,
+            initiator_id: record.initiator_id,
+            reverses_id: record.reverses_id,
+            loan_id: record.loan_id,
+        }
+    }
+}
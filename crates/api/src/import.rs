@@ -0,0 +1,251 @@
+//! Bulk import of opening balances, shared by the import route and the command line.
+
+use economy_service_core::{
+    get_funded_economy_states, import_balances, record_audit_entry, AuditEntryForm, EconomyError,
+    ImportEntry,
+};
+use economy_service_entity::audit_log_entry::AuditAction;
+use futures::{stream, StreamExt, TryStreamExt};
+use sea_orm::{DbConn, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    str::FromStr,
+};
+use users_service_client::{GetUserResponse, UsersServiceClient};
+use utoipa::ToSchema;
+
+/// Amount of concurrent user lookups
+const LOOKUP_CONCURRENCY: usize = 16;
+
+pub type ImportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Format of imported data
+#[derive(Clone, Copy, Debug)]
+pub enum ImportFormat {
+    /// Comma-separated values with `user_id`, `balance` and optional `banker` columns
+    Csv,
+
+    /// JSON array of objects with `user_id`, `balance` and optional `banker` fields
+    Json,
+}
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ImportFormat::Csv),
+            "json" => Ok(ImportFormat::Json),
+            _ => Err(format!("unknown format `{}`, expected `csv` or `json`", s)),
+        }
+    }
+}
+
+/// Row of imported data
+#[derive(Deserialize)]
+struct ImportRecord {
+    user_id: i32,
+    balance: i32,
+    #[serde(default)]
+    banker: Option<bool>,
+}
+
+/// Problem that prevents the import from being applied
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportProblem {
+    /// Number of the row, starting from 1 for the first record
+    pub row: usize,
+
+    /// User ID of the row, if it could be read
+    pub user_id: Option<i32>,
+
+    /// Problem description
+    pub detail: String,
+}
+
+/// Outcome of an import
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportReport {
+    /// Amount of rows in the imported data
+    pub rows: usize,
+
+    /// Sum of imported balances
+    pub total: i64,
+
+    /// Amount of rows granting or revoking banker role
+    pub banker_changes: usize,
+
+    /// Problems found in the imported data. Nothing is applied if there are any.
+    pub problems: Vec<ImportProblem>,
+
+    /// Whether the import was applied
+    pub applied: bool,
+}
+
+fn parse(
+    data: &[u8],
+    format: ImportFormat,
+) -> Result<Vec<Result<ImportRecord, String>>, ImportError> {
+    match format {
+        ImportFormat::Csv => Ok(csv::Reader::from_reader(data)
+            .deserialize()
+            .map(|record| record.map_err(|err: csv::Error| err.to_string()))
+            .collect()),
+        ImportFormat::Json => Ok(serde_json::from_slice::<Vec<serde_json::Value>>(data)?
+            .into_iter()
+            .map(|value| serde_json::from_value(value).map_err(|err| err.to_string()))
+            .collect()),
+    }
+}
+
+/// Validates the data against the users service and the database, and applies it atomically
+/// unless it's a dry run or there are problems.
+/// Errors are only returned for malformed JSON and failures of the database or users service.
+pub async fn run_import(
+    data: &[u8],
+    format: ImportFormat,
+    dry_run: bool,
+    audit_entry: AuditEntryForm,
+    users_client: &UsersServiceClient,
+    conn: &DbConn,
+) -> Result<ImportReport, ImportError> {
+    let records = parse(data, format)?;
+
+    let mut report = ImportReport {
+        rows: records.len(),
+        total: 0,
+        banker_changes: 0,
+        problems: vec![],
+        applied: false,
+    };
+
+    // check rows on their own
+    let mut entries = Vec::with_capacity(records.len());
+    let mut rows = HashMap::with_capacity(records.len());
+    for (i, record) in records.into_iter().enumerate() {
+        let row = i + 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                report.problems.push(ImportProblem {
+                    row,
+                    user_id: None,
+                    detail: format!("Invalid row: {}", err),
+                });
+                continue;
+            }
+        };
+
+        // rows repeating a user are rejected, while the user is reported at their first row
+        let problem = match rows.entry(record.user_id) {
+            Entry::Occupied(first) => Some(format!("Duplicate of row {}", first.get())),
+            Entry::Vacant(vacant) => {
+                vacant.insert(row);
+                (record.balance < 0).then(|| String::from("Balance should not be negative"))
+            }
+        };
+        if let Some(detail) = problem {
+            report.problems.push(ImportProblem {
+                row,
+                user_id: Some(record.user_id),
+                detail,
+            });
+            continue;
+        }
+
+        report.total += record.balance as i64;
+        report.banker_changes += record.banker.is_some() as usize;
+        entries.push(ImportEntry {
+            user_id: record.user_id,
+            balance: record.balance,
+            banker: record.banker,
+        });
+    }
+
+    // check whether users exist
+    let user_ids: Vec<i32> = entries.iter().map(|entry| entry.user_id).collect();
+    let missing: Vec<i32> = stream::iter(user_ids.clone())
+        .map(|id| async move {
+            match users_client.get_user(id).await? {
+                GetUserResponse::Ok(_) => Ok(None),
+                _ => Ok::<_, reqwest::Error>(Some(id)),
+            }
+        })
+        .buffer_unordered(LOOKUP_CONCURRENCY)
+        .try_filter_map(|id| async move { Ok(id) })
+        .try_collect()
+        .await?;
+    for id in missing {
+        report.problems.push(ImportProblem {
+            row: rows[&id],
+            user_id: Some(id),
+            detail: String::from("User not found"),
+        });
+    }
+
+    // opening balances can't be imported into accounts that already hold money,
+    // which is checked again while importing in case they're funded in the meantime
+    for state in get_funded_economy_states(&user_ids, conn).await? {
+        report.problems.push(ImportProblem {
+            row: rows[&state.user_id],
+            user_id: Some(state.user_id),
+            detail: format!("Account already has a balance of {}", state.balance),
+        });
+    }
+
+    report.problems.sort_by_key(|problem| problem.row);
+    if dry_run || !report.problems.is_empty() {
+        return Ok(report);
+    }
+
+    // import and record it in the audit log atomically
+    let txn = conn.begin().await?;
+    let banker_changes = match import_balances(&entries, &txn).await {
+        Ok(banker_changes) => banker_changes,
+        Err(EconomyError::AlreadyFunded { user_id }) => {
+            report.problems.push(ImportProblem {
+                row: rows[&user_id],
+                user_id: Some(user_id),
+                detail: String::from("Account already has a balance"),
+            });
+            return Ok(report);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    // banker roles are granted and revoked like any other, so each change is audited on its own
+    for change in banker_changes {
+        let action = match change.after {
+            true => AuditAction::GrantBanker,
+            false => AuditAction::RevokeBanker,
+        };
+        record_audit_entry(
+            AuditEntryForm {
+                action,
+                target: format!("user:{}", change.user_id),
+                before: Some(json!({ "banker": change.before })),
+                after: Some(json!({ "banker": change.after })),
+                ..audit_entry.clone()
+            },
+            &txn,
+        )
+        .await?;
+    }
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(json!({
+                "rows": report.rows,
+                "total": report.total,
+                "banker_changes": report.banker_changes,
+            })),
+            ..audit_entry
+        },
+        &txn,
+    )
+    .await?;
+    txn.commit().await?;
+
+    report.applied = true;
+    Ok(report)
+}
@@ -1,38 +1,157 @@
+pub(crate) mod api_keys;
+pub(crate) mod approvals;
+pub(crate) mod deprecation;
+pub(crate) mod events;
 pub(crate) mod extractors;
+pub(crate) mod grpc;
+pub mod import;
+pub(crate) mod limits;
+pub(crate) mod loans;
 pub(crate) mod openapi;
+pub(crate) mod rate_limit;
+pub(crate) mod reconciliation;
 pub(crate) mod responses;
 pub(crate) mod routes;
+pub(crate) mod webhooks;
 
 use axum::{
-    routing::{get, patch, put},
+    extract::DefaultBodyLimit,
+    http::Request,
+    middleware,
+    routing::{delete, get, patch, post, put},
     Router,
 };
+use economy_service_core::{ApprovalPolicy, TransferLimitsPolicy};
 use economy_service_migration::{
     sea_orm::{Database, DbConn},
     Migrator, MigratorTrait,
 };
 use serde::Deserialize;
 use std::net::SocketAddr;
-use tower_http::trace::TraceLayer;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use users_service_client::UsersServiceClient;
 
-use crate::routes::{add_money, get_by_id, get_self, pay};
+use crate::{
+    approvals::ApprovalsConfig,
+    events::BalanceEvents,
+    grpc::EconomyService,
+    limits::TransferLimitsConfig,
+    loans::LoansConfig,
+    rate_limit::{RateLimitConfig, RateLimits},
+    reconciliation::ReconciliationConfig,
+    routes::{
+        accept_loan, add_money, approve_proposal, batch_pay, create_account, create_api_key,
+        create_webhook, decline_loan, delete_webhook, freeze, get_account, get_account_limits,
+        get_account_members, get_account_transactions, get_api_keys, get_audit_log,
+        get_balances_export, get_by_id, get_by_username, get_limits, get_loan, get_proposal,
+        get_proposals, get_reconciliation, get_self, get_self_accounts, get_self_events,
+        get_self_loans, get_transaction, get_transactions_export, get_webhook_deliveries,
+        get_webhooks, import_balances, offer_loan, pay, pay_account, pay_by_username,
+        refund_payment, reject_proposal, remove_account_member, reverse_payment, revoke_api_key,
+        send_from_account, set_account_limit, set_account_member, set_limits, unfreeze,
+    },
+    webhooks::WebhooksConfig,
+};
+
+/// Maximum size of imported data
+const IMPORT_BODY_LIMIT: usize = 32 * 1024 * 1024;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Config {
     database_url: String,
     users_service_url: String,
+    #[serde(default = "default_grpc_port")]
+    grpc_port: u16,
+    #[serde(default)]
+    migrations: MigrationMode,
+}
+
+fn default_grpc_port() -> u16 {
+    8021
+}
+
+/// What the server does about pending migrations when it starts
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MigrationMode {
+    /// Apply them
+    #[default]
+    Auto,
+
+    /// Refuse to start until they are applied with `migrate up`
+    Verify,
+
+    /// Start without checking the schema
+    Off,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProxyConfig {
+    #[serde(default)]
+    trust_forwarded_for: bool,
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct AppState {
     users_client: UsersServiceClient,
     conn: DbConn,
+    rate_limits: RateLimits,
+    transfer_limits: TransferLimitsPolicy,
+    approvals: ApprovalPolicy,
+    balance_events: BalanceEvents,
+    trust_forwarded_for: bool,
+}
+impl AppState {
+    /// Builds the state with the rest of its configuration read from the environment
+    fn from_env(conn: DbConn, users_client: UsersServiceClient) -> Self {
+        let proxy_config = envy::from_env::<ProxyConfig>().unwrap();
+        let rate_limit_config = envy::prefixed("RATE_LIMIT_")
+            .from_env::<RateLimitConfig>()
+            .unwrap();
+        let transfer_limits_config = envy::prefixed("TRANSFER_LIMITS_")
+            .from_env::<TransferLimitsConfig>()
+            .unwrap();
+        let approvals_config = envy::prefixed("APPROVALS_")
+            .from_env::<ApprovalsConfig>()
+            .unwrap();
+
+        AppState {
+            users_client,
+            conn: conn.clone(),
+            rate_limits: RateLimits::new(&rate_limit_config),
+            transfer_limits: transfer_limits_config.into(),
+            approvals: approvals_config.into(),
+            balance_events: BalanceEvents::new(conn),
+            trust_forwarded_for: proxy_config.trust_forwarded_for,
+        }
+    }
 }
 
-#[tokio::main]
-pub async fn main() {
+/// Builds the HTTP API on top of the database and users service, reading the rest of its
+/// configuration from the environment. Neither runs migrations nor starts background tasks,
+/// besides following balance changes once somebody subscribes to them.
+pub fn router(conn: DbConn, users_client: UsersServiceClient) -> Router {
+    app_router(AppState::from_env(conn, users_client))
+}
+
+/// Builds the gRPC API like [`router`] builds the HTTP one
+pub fn grpc_router(
+    conn: DbConn,
+    users_client: UsersServiceClient,
+) -> tonic::transport::server::Router {
+    tonic::transport::Server::builder().add_service(EconomyService::server(AppState::from_env(
+        conn,
+        users_client,
+    )))
+}
+
+/// Runs HTTP and gRPC servers until either of them fails.
+/// Fails right away if the configuration is invalid or the schema isn't ready.
+pub async fn serve() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG")
@@ -41,31 +160,191 @@ pub async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let config = envy::from_env::<Config>().unwrap();
+    let config = envy::from_env::<Config>()?;
+    let webhooks_config = envy::prefixed("WEBHOOKS_").from_env::<WebhooksConfig>()?;
+    let reconciliation_config =
+        envy::prefixed("RECONCILIATION_").from_env::<ReconciliationConfig>()?;
+    let loans_config = envy::prefixed("LOANS_").from_env::<LoansConfig>()?;
+    loans_config.validate()?;
 
-    let conn = Database::connect(&config.database_url).await.unwrap();
+    let conn = Database::connect(&config.database_url).await?;
     let users_client = UsersServiceClient::new(&config.users_service_url);
 
-    let state = AppState { users_client, conn };
+    let state = AppState::from_env(conn, users_client);
 
-    Migrator::up(&state.conn, None).await.unwrap();
+    prepare_schema(&state.conn, config.migrations).await?;
 
-    let app = Router::new()
-        .merge(
-            Router::new()
-                .route("/:id", get(get_by_id))
-                .route("/:id", patch(add_money))
-                .route("/me", get(get_self))
-                .route("/:id/pay", put(pay))
-                .with_state(state),
-        )
-        .merge(openapi::ApiDoc::router().with_state(()))
-        .layer(TraceLayer::new_for_http());
+    tokio::spawn(webhooks::run_delivery_worker(
+        state.conn.clone(),
+        webhooks_config,
+    ));
+    tokio::spawn(reconciliation::run_reconciliation_task(
+        state.conn.clone(),
+        reconciliation_config,
+    ));
+    tokio::spawn(loans::run_loan_collector(state.conn.clone(), loans_config));
+
+    let grpc_state = state.clone();
+    let app = app_router(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 8020));
-    tracing::debug!("listening on {}", addr);
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let grpc_addr = SocketAddr::from(([0, 0, 0, 0], config.grpc_port));
+
+    let http_server =
+        axum::Server::bind(&addr).serve(app.into_make_service_with_connect_info::<SocketAddr>());
+    let grpc_server = tonic::transport::Server::builder()
+        .add_service(EconomyService::server(grpc_state))
+        .serve(grpc_addr);
+
+    tracing::debug!("listening on {} (HTTP) and {} (gRPC)", addr, grpc_addr);
+    tokio::select! {
+        res = http_server => res?,
+        res = grpc_server => res?,
+    }
+    Ok(())
+}
+
+/// Applies pending migrations or checks that there are none, depending on the mode.
+/// Replicas started together should use `verify` and have `migrate up` run once before them.
+async fn prepare_schema(
+    conn: &DbConn,
+    mode: MigrationMode,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match mode {
+        MigrationMode::Auto => Migrator::up(conn, None).await?,
+        MigrationMode::Verify => {
+            let pending = Migrator::get_pending_migrations(conn).await?.len();
+            if pending > 0 {
+                return Err(format!(
+                    "schema is {} migration(s) behind, apply them with `migrate up`",
+                    pending
+                )
+                .into());
+            }
+        }
+        MigrationMode::Off => tracing::debug!("skipping schema checks"),
+    }
+    Ok(())
+}
+
+/// Versioned API along with the deprecated unversioned routes and the API docs
+fn app_router(state: AppState) -> Router {
+    let (shared, users) = (shared_routes(&state), user_routes(&state));
+    Router::new()
+        .nest("/v1", v1_routes(shared.clone(), users.clone()))
+        // unversioned routes are kept until clients move to `/v1`, with routes of users at the root
+        .merge(shared.layer(middleware::from_fn_with_state(
+            "/v1",
+            deprecation::deprecate_unversioned,
+        )))
+        .merge(users.layer(middleware::from_fn_with_state(
+            "/v1/users",
+            deprecation::deprecate_unversioned,
+        )))
+        .with_state(state)
+        .merge(openapi::ApiDoc::router().with_state(()))
+        .layer(middleware::from_fn(responses::log_internal_errors))
+        .layer(TraceLayer::new_for_http())
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .layer(middleware::map_request(drop_client_request_id))
+}
+
+/// Removes the request ID sent by the client, so that every request gets one generated by us.
+/// The ID ends up in the audit log and error logs, where clients shouldn't be able to choose it.
+async fn drop_client_request_id<B>(mut request: Request<B>) -> Request<B> {
+    request.headers_mut().remove("x-request-id");
+    request
+}
+
+/// Routes of the first version of the API, mounted under `/v1`. A later version can be built
+/// from the same handlers, replacing or adding only the routes that differ.
+fn v1_routes(shared: Router<AppState>, users: Router<AppState>) -> Router<AppState> {
+    shared.nest("/users", users)
+}
+
+/// Routes addressing a single user by ID or username, under `/users` since `/v1`
+fn user_routes(state: &AppState) -> Router<AppState> {
+    let reads = Router::new()
+        .route("/:id", get(get_by_id))
+        .route("/by-username/:username", get(get_by_username))
+        .route("/:id/limits", get(get_limits))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_reads,
+        ));
+
+    let writes = Router::new()
+        .route("/:id", patch(add_money))
+        .route("/:id/pay", put(pay))
+        .route("/by-username/:username/pay", put(pay_by_username))
+        .route("/:id/limits", put(set_limits))
+        .route("/:id/freeze", put(freeze))
+        .route("/:id/freeze", delete(unfreeze))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_writes,
+        ));
+
+    reads.merge(writes)
+}
+
+/// Routes served at the same paths by every version
+fn shared_routes(state: &AppState) -> Router<AppState> {
+    let reads = Router::new()
+        .route("/me", get(get_self))
+        .route("/me/events", get(get_self_events))
+        .route("/me/accounts", get(get_self_accounts))
+        .route("/me/loans", get(get_self_loans))
+        .route("/loans/:id", get(get_loan))
+        .route("/accounts/:id", get(get_account))
+        .route("/accounts/:id/members", get(get_account_members))
+        .route("/accounts/:id/limits", get(get_account_limits))
+        .route("/accounts/:id/transactions", get(get_account_transactions))
+        .route("/transactions/:id", get(get_transaction))
+        .route("/proposals", get(get_proposals))
+        .route("/proposals/:id", get(get_proposal))
+        .route("/audit", get(get_audit_log))
+        .route("/reconciliation", get(get_reconciliation))
+        .route("/export/balances", get(get_balances_export))
+        .route("/export/transactions", get(get_transactions_export))
+        .route("/webhooks", get(get_webhooks))
+        .route("/api-keys", get(get_api_keys))
+        .route("/webhooks/:id/deliveries", get(get_webhook_deliveries))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_reads,
+        ));
+
+    let writes = Router::new()
+        .route("/payouts", post(batch_pay))
+        .route("/accounts", post(create_account))
+        .route("/accounts/:id/pay", put(pay_account))
+        .route("/accounts/:id/send", put(send_from_account))
+        .route("/accounts/:id/limits/:role", put(set_account_limit))
+        .route(
+            "/accounts/:id/members/:user_id",
+            put(set_account_member).delete(remove_account_member),
+        )
+        .route("/loans", post(offer_loan))
+        .route("/loans/:id/accept", post(accept_loan))
+        .route("/loans/:id/decline", post(decline_loan))
+        .route("/transactions/:id/refund", post(refund_payment))
+        .route("/transactions/:id/reverse", post(reverse_payment))
+        .route("/proposals/:id/approve", post(approve_proposal))
+        .route("/proposals/:id/reject", post(reject_proposal))
+        .route("/webhooks", post(create_webhook))
+        .route(
+            "/import",
+            post(import_balances).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route("/webhooks/:id", delete(delete_webhook))
+        .route("/api-keys", post(create_api_key))
+        .route("/api-keys/:id", delete(revoke_api_key))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_writes,
+        ));
+
+    reads.merge(writes)
 }
@@ -0,0 +1,50 @@
+use economy_service_core::{TransferLimits, TransferLimitsPolicy};
+use serde::Deserialize;
+
+/// Transfer limits configuration, read from `TRANSFER_LIMITS_*` environment variables.
+/// Unset limits are unlimited.
+#[derive(Debug, Deserialize)]
+pub(crate) struct TransferLimitsConfig {
+    /// Length of the rolling window in hours
+    #[serde(default = "default_window_hours")]
+    window_hours: i64,
+
+    standard_per_transaction: Option<i32>,
+    standard_daily: Option<i32>,
+    standard_window: Option<i32>,
+
+    verified_per_transaction: Option<i32>,
+    verified_daily: Option<i32>,
+    verified_window: Option<i32>,
+
+    merchant_per_transaction: Option<i32>,
+    merchant_daily: Option<i32>,
+    merchant_window: Option<i32>,
+}
+
+fn default_window_hours() -> i64 {
+    24 * 7
+}
+
+impl From<TransferLimitsConfig> for TransferLimitsPolicy {
+    fn from(config: TransferLimitsConfig) -> Self {
+        TransferLimitsPolicy {
+            standard: TransferLimits {
+                per_transaction: config.standard_per_transaction,
+                daily: config.standard_daily,
+                window: config.standard_window,
+            },
+            verified: TransferLimits {
+                per_transaction: config.verified_per_transaction,
+                daily: config.verified_daily,
+                window: config.verified_window,
+            },
+            merchant: TransferLimits {
+                per_transaction: config.merchant_per_transaction,
+                daily: config.merchant_daily,
+                window: config.merchant_window,
+            },
+            window: chrono::Duration::hours(config.window_hours),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Scheduled collection of loan installments from borrowers' balances.
+
+use chrono::Utc;
+use economy_service_core::{collect_installment, get_due_loans, Collection, LatePaymentPolicy};
+use economy_service_entity::loan::LoanStatus;
+use sea_orm::{DbConn, DbErr};
+use serde::Deserialize;
+
+/// Amount of due loans collected per poll
+const BATCH_SIZE: u64 = 100;
+
+/// Loan collection configuration, read from `LOANS_*` environment variables
+#[derive(Debug, Deserialize)]
+pub(crate) struct LoansConfig {
+    /// Seconds between polls for due installments
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+
+    /// Fee charged on missed amounts in basis points
+    #[serde(default = "default_late_fee_bps")]
+    late_fee_bps: i32,
+
+    /// Installments missed in a row after which a loan is defaulted
+    #[serde(default = "default_default_after")]
+    default_after: i32,
+}
+
+impl LoansConfig {
+    /// Fails with a message naming the variable whose value can't be used
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.poll_interval_secs == 0 {
+            return Err(String::from(
+                "LOANS_POLL_INTERVAL_SECS should be more than 0",
+            ));
+        }
+        if self.late_fee_bps < 0 {
+            return Err(String::from("LOANS_LATE_FEE_BPS should not be negative"));
+        }
+        if self.default_after <= 0 {
+            return Err(String::from("LOANS_DEFAULT_AFTER should be more than 0"));
+        }
+        Ok(())
+    }
+}
+
+fn default_poll_interval_secs() -> u64 {
+    60
+}
+
+fn default_late_fee_bps() -> i32 {
+    500
+}
+
+fn default_default_after() -> i32 {
+    3
+}
+
+/// Collects due installments until the process exits
+pub(crate) async fn run_loan_collector(conn: DbConn, config: LoansConfig) {
+    let policy = LatePaymentPolicy {
+        late_fee_bps: config.late_fee_bps,
+        default_after: config.default_after,
+    };
+
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(config.poll_interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(err) = collect_due(&conn, policy).await {
+            tracing::error!("failed to collect loan installments: {}", err);
+        }
+    }
+}
+
+async fn collect_due(conn: &DbConn, policy: LatePaymentPolicy) -> Result<(), DbErr> {
+    for loan in get_due_loans(Utc::now(), BATCH_SIZE, conn).await? {
+        // a failed installment is retried on the next poll, as its due date stays unchanged
+        let collection = match collect_installment(&loan, policy, conn).await {
+            Ok(Some(collection)) => collection,
+            Ok(None) => continue,
+            Err(err) => {
+                tracing::warn!("failed to collect installment of loan {}: {}", loan.id, err);
+                continue;
+            }
+        };
+
+        let Collection { loan, missed, .. } = collection;
+
+        if missed > 0 {
+            tracing::debug!(
+                "borrower {} missed {} of loan {} installment",
+                loan.borrower_id,
+                missed,
+                loan.id
+            );
+        }
+        match loan.status {
+            LoanStatus::Repaid => tracing::debug!("loan {} is repaid", loan.id),
+            LoanStatus::Defaulted if missed > 0 => tracing::warn!(
+                "loan {} is defaulted after {} missed installments",
+                loan.id,
+                loan.missed_installments
+            ),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> LoansConfig {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        envy::prefixed("LOANS_").from_iter(vars).unwrap()
+    }
+
+    #[test]
+    fn rejects_unusable_policies() {
+        assert!(config(&[]).validate().is_ok());
+        assert!(config(&[("LOANS_LATE_FEE_BPS", "0")]).validate().is_ok());
+
+        for vars in [
+            [("LOANS_DEFAULT_AFTER", "0")],
+            [("LOANS_DEFAULT_AFTER", "-1")],
+            [("LOANS_LATE_FEE_BPS", "-1")],
+            [("LOANS_POLL_INTERVAL_SECS", "0")],
+        ] {
+            assert!(config(&vars).validate().is_err(), "{:?}", vars);
+        }
+    }
+}
@@ -1,59 +1,187 @@
-use economy_service_entity::economy_state::Model as EconomyState;
+use axum::{http::header, response::Html, routing::get};
+use economy_service_client::models::{AccountTier, EconomyState};
+use economy_service_entity::{
+    account::{AccountKind, Model as Account},
+    account_freeze::Model as AccountFreeze,
+    account_member::{AccountRole, Model as AccountMember},
+    account_role_limit::Model as AccountRoleLimit,
+    api_key::Model as ApiKey,
+    audit_log_entry::{AuditAction, Model as AuditLogEntry},
+    loan::{LoanStatus, Model as Loan},
+    proposal::{Model as Proposal, ProposalKind, ProposalStatus},
+    proposal_approval::Model as ProposalApproval,
+    transaction::{Model as Transaction, TransactionKind},
+    webhook::{Model as Webhook, WebhookEvent},
+    webhook_delivery::{DeliveryStatus, Model as WebhookDelivery},
+};
 use utoipa::{
-    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    openapi::security::{self, ApiKeyValue, SecurityScheme},
     Modify, OpenApi,
 };
 
-use routes::{DataAddMoney, DataPay};
+use routes::{
+    AccountMembership, BalanceUpdate, CreatedApiKey, DataAddMoney, DataBatchPay, DataCreateAccount,
+    DataCreateApiKey, DataCreateWebhook, DataFreeze, DataOfferLoan, DataPay, DataPayoutItem,
+    DataRejectProposal, DataReverse, DataSendFromAccount, DataSetAccountLimit,
+    DataSetAccountMember, DataSetLimits, DriftedAccount, EconomyStateDetails, PayoutMode,
+    PayoutReport, PayoutResult, ProposalDetails, ReconciliationReport, TransactionDetails,
+    TransferLimitsInfo,
+};
 
-use crate::responses::AppError;
+use crate::import::{ImportProblem, ImportReport};
+use crate::responses::{AppError, ErrorCode};
 use crate::routes;
 
-const DOCS_TEMPLATE: &str = r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-  <meta charset="utf-8" />
-  <meta name="viewport" content="width=device-width, initial-scale=1" />
-  <title>Documentation</title>
-  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@4.5.0/swagger-ui.css" />
-</head>
-<body>
-<div id="ui"></div>
-<script src="https://unpkg.com/swagger-ui-dist@4.5.0/swagger-ui-bundle.js" crossorigin></script>
-<script>
-  window.onload = () => {
-    window.ui = SwaggerUIBundle({
-      spec: %SPEC%,
-      dom_id: '#ui',
-    });
-  };
-</script>
-</body>
-</html>"#;
+/// Page of the API docs, rendered from `/openapi.json` by the vendored swagger-ui
+const DOCS_HTML: &str = include_str!("../assets/swagger-ui/index.html");
+const DOCS_INITIALIZER: &str = include_str!("../assets/swagger-ui/swagger-initializer.js");
+const SWAGGER_UI_JS: &str = include_str!("../assets/swagger-ui/swagger-ui-bundle.js");
+const SWAGGER_UI_CSS: &str = include_str!("../assets/swagger-ui/swagger-ui.css");
+const SWAGGER_UI_FAVICON: &[u8] = include_bytes!("../assets/swagger-ui/favicon-32x32.png");
 
 #[derive(OpenApi, Debug)]
 #[openapi(
-    paths(routes::get_by_id, routes::get_self, routes::pay, routes::add_money),
-    components(schemas(EconomyState, AppError, DataPay, DataAddMoney)),
-    modifiers(&SecurityAddon, &InfoAddon),
+    paths(
+        routes::get_by_id,
+        routes::get_by_username,
+        routes::get_self,
+        routes::get_self_events,
+        routes::get_self_accounts,
+        routes::pay,
+        routes::pay_by_username,
+        routes::add_money,
+        routes::batch_pay,
+        routes::create_account,
+        routes::get_account,
+        routes::pay_account,
+        routes::send_from_account,
+        routes::get_account_members,
+        routes::set_account_member,
+        routes::remove_account_member,
+        routes::get_account_limits,
+        routes::set_account_limit,
+        routes::get_account_transactions,
+        routes::get_limits,
+        routes::set_limits,
+        routes::offer_loan,
+        routes::get_loan,
+        routes::accept_loan,
+        routes::decline_loan,
+        routes::get_self_loans,
+        routes::freeze,
+        routes::unfreeze,
+        routes::get_transaction,
+        routes::refund_payment,
+        routes::reverse_payment,
+        routes::get_proposals,
+        routes::get_proposal,
+        routes::approve_proposal,
+        routes::reject_proposal,
+        routes::get_audit_log,
+        routes::get_reconciliation,
+        routes::get_balances_export,
+        routes::get_transactions_export,
+        routes::import_balances,
+        routes::create_webhook,
+        routes::get_webhooks,
+        routes::delete_webhook,
+        routes::get_webhook_deliveries,
+        routes::create_api_key,
+        routes::get_api_keys,
+        routes::revoke_api_key,
+    ),
+    components(schemas(
+        EconomyState,
+        EconomyStateDetails,
+        BalanceUpdate,
+        Transaction,
+        TransactionKind,
+        TransactionDetails,
+        AccountTier,
+        AccountFreeze,
+        Account,
+        AccountKind,
+        AccountMember,
+        AccountRole,
+        AccountMembership,
+        AccountRoleLimit,
+        Proposal,
+        ProposalKind,
+        ProposalStatus,
+        ProposalApproval,
+        ProposalDetails,
+        Loan,
+        LoanStatus,
+        AuditLogEntry,
+        AuditAction,
+        Webhook,
+        WebhookEvent,
+        WebhookDelivery,
+        DeliveryStatus,
+        ApiKey,
+        CreatedApiKey,
+        AppError,
+        ErrorCode,
+        ImportReport,
+        ImportProblem,
+        ReconciliationReport,
+        DriftedAccount,
+        DataPay,
+        DataAddMoney,
+        DataBatchPay,
+        DataPayoutItem,
+        PayoutMode,
+        PayoutReport,
+        PayoutResult,
+        DataCreateAccount,
+        DataSendFromAccount,
+        DataSetAccountLimit,
+        DataSetAccountMember,
+        DataSetLimits,
+        DataFreeze,
+        DataCreateWebhook,
+        DataCreateApiKey,
+        DataOfferLoan,
+        DataRejectProposal,
+        DataReverse,
+        TransferLimitsInfo,
+    )),
+    modifiers(&SecurityAddon, &InfoAddon, &ServersAddon),
 )]
 pub(crate) struct ApiDoc;
 
 impl ApiDoc {
     pub(crate) fn router() -> axum::Router {
-        async fn schema_handler() -> impl axum::response::IntoResponse {
-            axum::Json(ApiDoc::openapi())
-        }
-
-        async fn docs_handler() -> impl axum::response::IntoResponse {
-            axum::response::Html(
-                DOCS_TEMPLATE.replace("%SPEC%", &ApiDoc::openapi().to_json().unwrap()),
-            )
-        }
+        // the document doesn't change at runtime, so it is only serialized once
+        let spec = ApiDoc::openapi().to_json().unwrap();
 
         axum::Router::new()
-            .route("/openapi.json", axum::routing::get(schema_handler))
-            .route("/docs", axum::routing::get(docs_handler))
+            .route(
+                "/openapi.json",
+                get(|| async move { ([(header::CONTENT_TYPE, "application/json")], spec) }),
+            )
+            .route("/docs", get(|| async { Html(DOCS_HTML) }))
+            .route(
+                "/docs/swagger-initializer.js",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "text/javascript")],
+                        DOCS_INITIALIZER,
+                    )
+                }),
+            )
+            .route(
+                "/docs/swagger-ui-bundle.js",
+                get(|| async { ([(header::CONTENT_TYPE, "text/javascript")], SWAGGER_UI_JS) }),
+            )
+            .route(
+                "/docs/swagger-ui.css",
+                get(|| async { ([(header::CONTENT_TYPE, "text/css")], SWAGGER_UI_CSS) }),
+            )
+            .route(
+                "/docs/favicon-32x32.png",
+                get(|| async { ([(header::CONTENT_TYPE, "image/png")], SWAGGER_UI_FAVICON) }),
+            )
     }
 }
 
@@ -68,14 +196,30 @@ impl Modify for InfoAddon {
     }
 }
 
+struct ServersAddon;
+impl Modify for ServersAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.servers = Some(vec![utoipa::openapi::ServerBuilder::new()
+            .url("/v1")
+            .description(Some(
+                "Version 1. Unversioned paths still work, but are deprecated.",
+            ))
+            .build()]);
+    }
+}
+
 struct SecurityAddon;
 impl Modify for SecurityAddon {
     fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
         if let Some(components) = openapi.components.as_mut() {
             components.add_security_scheme(
                 "api_key",
-                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-token"))),
-            )
+                SecurityScheme::ApiKey(security::ApiKey::Header(ApiKeyValue::new("x-token"))),
+            );
+            components.add_security_scheme(
+                "service_key",
+                SecurityScheme::ApiKey(security::ApiKey::Header(ApiKeyValue::new("x-api-key"))),
+            );
         }
     }
 }
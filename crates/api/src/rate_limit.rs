@@ -0,0 +1,317 @@
+use crate::{
+    extractors::{AuthenticatedUser, Caller, ClientIp},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+use axum::{
+    extract::{FromRequestParts, State},
+    http::{header, request::Parts, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Amount of tracked buckets after which fully refilled ones are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Rate limiting configuration, read from `RATE_LIMIT_*` environment variables
+#[derive(Debug, Deserialize)]
+pub(crate) struct RateLimitConfig {
+    /// Read requests allowed per minute for a single user, API key or IP. 0 disables the limit.
+    #[serde(default = "default_reads_per_minute")]
+    reads_per_minute: u32,
+
+    /// Money-moving requests allowed per minute for a single user, API key or IP. 0 disables the limit.
+    #[serde(default = "default_writes_per_minute")]
+    writes_per_minute: u32,
+}
+
+fn default_reads_per_minute() -> u32 {
+    120
+}
+
+fn default_writes_per_minute() -> u32 {
+    20
+}
+
+/// Separate request budgets for read and money-moving routes
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimits {
+    pub(crate) reads: Arc<RateLimiter>,
+    pub(crate) writes: Arc<RateLimiter>,
+}
+impl RateLimits {
+    pub(crate) fn new(config: &RateLimitConfig) -> Self {
+        RateLimits {
+            reads: Arc::new(RateLimiter::new(config.reads_per_minute)),
+            writes: Arc::new(RateLimiter::new(config.writes_per_minute)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum RateLimitKey {
+    User(i32),
+    ApiKey(i32),
+    Ip(IpAddr),
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// In-process token bucket limiter keyed by user, API key and client IP
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<RateLimitKey, Bucket>>,
+}
+impl RateLimiter {
+    pub(crate) fn new(per_minute: u32) -> Self {
+        RateLimiter {
+            capacity: per_minute as f64,
+            refill_per_sec: per_minute as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of every key, or from none of them if any bucket is empty.
+    /// On rejection returns how long to wait until a token is available for every key.
+    pub(crate) fn check(&self, keys: &[RateLimitKey]) -> Result<(), Duration> {
+        if self.capacity == 0.0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            let refill_time = Duration::from_secs_f64(self.capacity / self.refill_per_sec);
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < refill_time);
+        }
+
+        let mut wait = Duration::ZERO;
+        for key in keys {
+            let bucket = buckets.entry(*key).or_insert(Bucket {
+                tokens: self.capacity,
+                updated: now,
+            });
+
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+            bucket.updated = now;
+
+            if bucket.tokens < 1.0 {
+                let missing = 1.0 - bucket.tokens;
+                wait = wait.max(Duration::from_secs_f64(missing / self.refill_per_sec));
+            }
+        }
+
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        for key in keys {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Middleware applying the read budget
+pub(crate) async fn limit_reads<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let limiter = state.rate_limits.reads.clone();
+    limit(&limiter, state, req, next).await
+}
+
+/// Middleware applying the money-moving budget
+pub(crate) async fn limit_writes<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let limiter = state.rate_limits.writes.clone();
+    limit(&limiter, state, req, next).await
+}
+
+/// Returns the bucket of the authenticated user or API key, if the request carries valid credentials.
+/// Only called once the client IP has passed its own bucket, so rejected clients don't cost a
+/// users service lookup.
+pub(crate) async fn caller_key(parts: &mut Parts, state: &AppState) -> Option<RateLimitKey> {
+    // The authenticated user is cached in request extensions, so handlers don't fetch it twice.
+    // Failed authentication is left for the handler to reject.
+    // Like `Caller`, a key takes precedence over a token sent along with it.
+    if parts.headers.contains_key("x-api-key") {
+        if let Ok(Caller::ApiKey(key)) = Caller::from_request_parts(parts, state).await {
+            return Some(RateLimitKey::ApiKey(key.id));
+        }
+    } else if parts.headers.contains_key("x-token") {
+        if let Ok(AuthenticatedUser(user)) =
+            AuthenticatedUser::from_request_parts(parts, state).await
+        {
+            return Some(RateLimitKey::User(user.id));
+        }
+    }
+
+    None
+}
+
+fn too_many_requests(wait: Duration) -> Response {
+    let retry_after = wait.as_secs_f64().ceil() as u64;
+    (
+        [(header::RETRY_AFTER, retry_after.max(1).to_string())],
+        ApiError::new(ErrorCode::RateLimited, "Too many requests"),
+    )
+        .into_response()
+}
+
+async fn limit<B>(
+    limiter: &RateLimiter,
+    state: AppState,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let (mut parts, body) = req.into_parts();
+
+    let ClientIp(ip) = match ClientIp::from_request_parts(&mut parts, &state).await {
+        Ok(ip) => ip,
+        Err(rejection) => return rejection.into_response(),
+    };
+    if let Err(wait) = limiter.check(&[RateLimitKey::Ip(ip)]) {
+        return too_many_requests(wait);
+    }
+
+    if let Some(key) = caller_key(&mut parts, &state).await {
+        if let Err(wait) = limiter.check(&[key]) {
+            return too_many_requests(wait);
+        }
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_keys::hash_key;
+    use economy_service_core::{create_api_key, CreateApiKeyForm};
+    use economy_service_migration::{Migrator, MigratorTrait};
+    use sea_orm::Database;
+    use users_service_client::UsersServiceClient;
+
+    const USER: RateLimitKey = RateLimitKey::User(1);
+    const IP: RateLimitKey = RateLimitKey::Ip(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST));
+
+    #[test]
+    fn rejects_after_capacity_is_used() {
+        let limiter = RateLimiter::new(3);
+        for _ in 0..3 {
+            assert!(limiter.check(&[USER]).is_ok());
+        }
+
+        let wait = limiter.check(&[USER]).unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(20));
+
+        // Other keys have their own buckets
+        assert!(limiter.check(&[IP]).is_ok());
+    }
+
+    #[test]
+    fn takes_tokens_from_all_keys_or_none() {
+        let limiter = RateLimiter::new(2);
+        limiter.check(&[USER]).unwrap();
+        limiter.check(&[USER]).unwrap();
+
+        assert!(limiter.check(&[IP, USER]).is_err());
+
+        // The rejected check above didn't take a token from the IP bucket
+        limiter.check(&[IP]).unwrap();
+        limiter.check(&[IP]).unwrap();
+        assert!(limiter.check(&[IP]).is_err());
+    }
+
+    #[test]
+    fn refills_over_time() {
+        let limiter = RateLimiter::new(60);
+        for _ in 0..60 {
+            limiter.check(&[USER]).unwrap();
+        }
+        assert!(limiter.check(&[USER]).is_err());
+
+        // Pretend two seconds have passed, which refills two tokens at 60 per minute
+        limiter
+            .buckets
+            .lock()
+            .unwrap()
+            .get_mut(&USER)
+            .unwrap()
+            .updated -= Duration::from_secs(2);
+        limiter.check(&[USER]).unwrap();
+        limiter.check(&[USER]).unwrap();
+        assert!(limiter.check(&[USER]).is_err());
+    }
+
+    #[test]
+    fn zero_disables_the_limit() {
+        let limiter = RateLimiter::new(0);
+        for _ in 0..1000 {
+            assert!(limiter.check(&[USER, IP]).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn key_takes_precedence_over_token() {
+        let db_path =
+            std::env::temp_dir().join(format!("economy-rate-limit-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+            .await
+            .unwrap();
+        Migrator::up(&conn, None).await.unwrap();
+
+        let key = create_api_key(
+            CreateApiKeyForm {
+                name: String::from("Shop"),
+                prefix: String::from("shop"),
+                key_hash: hash_key("secret"),
+                scopes: vec![],
+                created_by: 1,
+                expires_at: None,
+            },
+            &conn,
+        )
+        .await
+        .unwrap();
+
+        // nothing listens on the users service, so the token can't be what's counted
+        let state = AppState::from_env(conn, UsersServiceClient::new("http://127.0.0.1:1"));
+        let (mut parts, _) = Request::builder()
+            .header("x-token", "user-1")
+            .header("x-api-key", "secret")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert_eq!(
+            caller_key(&mut parts, &state).await,
+            Some(RateLimitKey::ApiKey(key.id))
+        );
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
@@ -0,0 +1,57 @@
+use economy_service_core::reconcile;
+use sea_orm::DbConn;
+use serde::Deserialize;
+
+/// Reconciliation configuration, read from `RECONCILIATION_*` environment variables
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReconciliationConfig {
+    /// Seconds between reconciliations. 0 disables the background task.
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+}
+
+fn default_interval_secs() -> u64 {
+    60 * 60
+}
+
+/// Periodically compares balances with the ledger and reports drift until the process exits
+pub(crate) async fn run_reconciliation_task(conn: DbConn, config: ReconciliationConfig) {
+    if config.interval_secs == 0 {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.interval_secs));
+    loop {
+        interval.tick().await;
+
+        let reconciliation = match reconcile(&conn).await {
+            Ok(reconciliation) => reconciliation,
+            Err(err) => {
+                tracing::error!("failed to reconcile balances: {}", err);
+                continue;
+            }
+        };
+
+        if reconciliation.drift != 0 || !reconciliation.drifted_accounts.is_empty() {
+            tracing::warn!(
+                "balances drifted from the ledger: total drift {}, {} of {} accounts drifted",
+                reconciliation.drift,
+                reconciliation.drifted_accounts.len(),
+                reconciliation.accounts,
+            );
+            for account in &reconciliation.drifted_accounts {
+                tracing::debug!(
+                    "{:?}: balance {}, expected {}",
+                    account.holder,
+                    account.balance,
+                    account.expected
+                );
+            }
+        } else {
+            tracing::debug!(
+                "balances of {} accounts match the ledger",
+                reconciliation.accounts
+            );
+        }
+    }
+}
@@ -1,16 +1,222 @@
-use serde::Serialize;
-use utoipa::ToSchema;
-
-/// Service error data
-#[derive(Debug, Serialize, ToSchema)]
-pub(crate) struct AppError {
-    /// Error detail message
-    detail: String,
-}
-impl AppError {
-    pub(crate) fn new(detail: impl Into<String>) -> Self {
-        AppError {
-            detail: detail.into(),
+use axum::{
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use economy_service_client::models::{AccountTier, EconomyState};
+use economy_service_core::{EconomyError, TransferHeadroom, TransferLimitKind};
+use economy_service_entity::economy_state;
+use sea_orm::DbErr;
+
+pub(crate) use economy_service_client::models::{AppError, ErrorCode};
+
+/// Media type of error responses
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Error response of the API, sent as `application/problem+json` with the status of its code
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    pub(crate) error: Box<AppError>,
+
+    /// Cause of an internal or upstream error, logged instead of being sent to the client
+    cause: Option<String>,
+}
+impl ApiError {
+    pub(crate) fn new(code: ErrorCode, detail: impl Into<String>) -> Self {
+        AppError::new(code, detail).into()
+    }
+
+    /// Failure of the service itself, e.g. of the database.
+    /// The client only gets a generic message, while the cause is logged with the request ID.
+    pub(crate) fn internal(err: impl ToString) -> Self {
+        ApiError {
+            error: Box::new(AppError::new(ErrorCode::Internal, "Internal server error")),
+            cause: Some(err.to_string()),
+        }
+    }
+
+    /// Failure to reach the users service. Like internal errors, the cause is only logged.
+    pub(crate) fn upstream(err: reqwest::Error) -> Self {
+        ApiError {
+            error: Box::new(AppError::new(
+                ErrorCode::UpstreamUnavailable,
+                "Users service is unavailable",
+            )),
+            cause: Some(err.to_string()),
+        }
+    }
+
+    /// Payment exceeding the tightest transfer or spending limit of the payer
+    pub(crate) fn transfer_limit_exceeded(
+        headroom: TransferHeadroom,
+        detail: impl Into<String>,
+    ) -> Self {
+        let limit = match headroom.kind {
+            TransferLimitKind::PerTransaction => "per_transaction",
+            TransferLimitKind::Daily => "daily",
+            TransferLimitKind::Window => "window",
+        };
+        AppError::new(ErrorCode::TransferLimitExceeded, detail)
+            .with_remaining(headroom.remaining)
+            .with_limit(limit)
+            .into()
+    }
+
+    pub(crate) fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.error.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Cause of an internal or upstream error, if it is one
+    pub(crate) fn cause(&self) -> Option<&str> {
+        self.cause.as_deref()
+    }
+}
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        ApiError {
+            error: Box::new(error),
+            cause: None,
+        }
+    }
+}
+impl From<DbErr> for ApiError {
+    fn from(err: DbErr) -> Self {
+        ApiError::internal(err)
+    }
+}
+impl From<EconomyError> for ApiError {
+    fn from(err: EconomyError) -> Self {
+        match err {
+            EconomyError::InvalidAmount => {
+                ApiError::new(ErrorCode::InvalidAmount, "Amount should be more than 0")
+            }
+            EconomyError::SelfPayment => {
+                ApiError::new(ErrorCode::SelfPayment, "Cannot pay to yourself")
+            }
+            EconomyError::AccountNotFound => {
+                ApiError::new(ErrorCode::AccountNotFound, "Account not found")
+            }
+            EconomyError::MissingBankerRole => {
+                ApiError::new(ErrorCode::MissingBankerRole, "Missing banker role")
+            }
+            EconomyError::MissingAccountRole => {
+                ApiError::new(ErrorCode::MissingAccountRole, "Missing account role")
+            }
+            EconomyError::LastOwner => ApiError::new(
+                ErrorCode::ValidationFailed,
+                "Account should have at least one owner",
+            ),
+            EconomyError::ApprovalRequired => ApiError::new(
+                ErrorCode::ApprovalRequired,
+                "Operation requires approval, which can't be proposed with an API key",
+            ),
+            EconomyError::InsufficientFunds { available } => {
+                AppError::new(ErrorCode::InsufficientFunds, "Insufficient funds")
+                    .with_available(available)
+                    .into()
+            }
+            EconomyError::AccountFrozen => {
+                ApiError::new(ErrorCode::AccountFrozen, "Your account is frozen")
+            }
+            EconomyError::PayeeFrozen => {
+                ApiError::new(ErrorCode::PayeeFrozen, "Payee account is frozen")
+            }
+            EconomyError::TransferLimitExceeded(headroom) => ApiError::transfer_limit_exceeded(
+                headroom,
+                format!(
+                    "Transfer exceeds {} limit, {} remaining",
+                    headroom.kind, headroom.remaining
+                ),
+            ),
+            EconomyError::RoleLimitExceeded(headroom) => ApiError::transfer_limit_exceeded(
+                headroom,
+                format!(
+                    "Transfer exceeds {} limit of your role, {} remaining",
+                    headroom.kind, headroom.remaining
+                ),
+            ),
+            EconomyError::ReversalExceedsRemaining { remaining } => AppError::new(
+                ErrorCode::ReversalExceedsRemaining,
+                format!(
+                    "Amount exceeds the unreversed part of the payment, {} remaining",
+                    remaining
+                ),
+            )
+            .with_remaining(remaining)
+            .into(),
+            EconomyError::AlreadyReversed => ApiError::new(
+                ErrorCode::AlreadyReversed,
+                "Payment is already fully reversed",
+            ),
+            EconomyError::NotReversible => {
+                ApiError::new(ErrorCode::NotReversible, "Only payments can be reversed")
+            }
+            EconomyError::AlreadyFunded { user_id } => ApiError::new(
+                ErrorCode::ValidationFailed,
+                format!("Account of user {} already has a balance", user_id),
+            ),
+            EconomyError::Database(err) => ApiError::internal(err),
+        }
+    }
+}
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (
+            self.status(),
+            [(header::CONTENT_TYPE, PROBLEM_JSON)],
+            Json(*self.error),
+        )
+            .into_response();
+        if let Some(cause) = self.cause {
+            response.extensions_mut().insert(InternalErrorCause(cause));
         }
+        response
+    }
+}
+
+/// Cause of an internal or upstream error, passed from the response to [`log_internal_errors`]
+#[derive(Clone)]
+struct InternalErrorCause(String);
+
+/// Logs causes of internal and upstream errors along with the ID of the request, which the client
+/// gets in the `X-Request-Id` header and can refer to when reporting the error
+pub(crate) async fn log_internal_errors<B>(request: Request<B>, next: Next<B>) -> Response {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|id| id.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+
+    let mut response = next.run(request).await;
+    if let Some(InternalErrorCause(cause)) = response.extensions_mut().remove() {
+        tracing::error!(
+            request_id = %request_id,
+            "{} {} failed: {}",
+            method,
+            path,
+            cause
+        );
+    }
+    response
+}
+
+/// Converts stored economy state into its API representation
+pub(crate) fn economy_state(state: economy_state::Model, debt: i64) -> EconomyState {
+    let tier = match state.tier {
+        economy_state::AccountTier::Standard => AccountTier::Standard,
+        economy_state::AccountTier::Verified => AccountTier::Verified,
+        economy_state::AccountTier::Merchant => AccountTier::Merchant,
+    };
+
+    EconomyState {
+        user_id: state.user_id,
+        balance: state.balance,
+        banker: state.banker,
+        tier,
+        debt,
     }
 }
@@ -0,0 +1,90 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{accept_loan as accept, EconomyError};
+use economy_service_entity::loan::LoanStatus;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    routes::find_loan,
+    AppState,
+};
+
+/// Accept a loan offered to you. Its principal is moved from the lender's balance to yours,
+/// and the first installment is due one interval later. Borrower only.
+#[utoipa::path(
+    post, path = "/loans/{id}/accept", tag = "Loans",
+    params(
+        ("id" = String, Path, description = "Loan ID")
+    ),
+    responses(
+        (status = 200, body = Loan, description = "Successful acceptance"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Lender has insufficient funds or loan exceeds their transfer limit"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Caller isn't the borrower or either account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Loan not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Loan was already accepted or declined"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn accept_loan(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let loan = find_loan(&state, id).await?;
+
+    if user.id != loan.borrower_id {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Only the borrower can accept the loan",
+        ));
+    }
+
+    if loan.status != LoanStatus::Offered {
+        return Err(ApiError::new(
+            ErrorCode::LoanNotPending,
+            "Loan was already accepted or declined",
+        ));
+    }
+
+    let (loan, _) = match accept(id, Some(state.transfer_limits), &state.conn).await {
+        Ok(Some(accepted)) => accepted,
+        Ok(None) => {
+            return Err(ApiError::new(
+                ErrorCode::LoanNotPending,
+                "Loan was already accepted or declined",
+            ))
+        }
+        Err(EconomyError::InsufficientFunds { .. }) => {
+            return Err(ApiError::new(
+                ErrorCode::InsufficientFunds,
+                "Lender doesn't have enough money anymore",
+            ))
+        }
+        Err(EconomyError::TransferLimitExceeded(headroom)) => {
+            return Err(ApiError::transfer_limit_exceeded(
+                headroom,
+                format!(
+                    "Loan exceeds {} limit of the lender, {} remaining",
+                    headroom.kind, headroom.remaining
+                ),
+            ))
+        }
+        Err(EconomyError::AccountFrozen) => {
+            return Err(ApiError::new(
+                ErrorCode::AccountFrozen,
+                "Lender account is frozen",
+            ))
+        }
+        Err(EconomyError::PayeeFrozen) => {
+            return Err(ApiError::new(
+                ErrorCode::PayeeFrozen,
+                "Your account is frozen",
+            ))
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Json(loan))
+}
@@ -1,101 +1,84 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
-    response::IntoResponse,
-    Json,
+    response::{IntoResponse, Response},
 };
-use economy_service_core::{
-    get_or_create_economy_state, update_economy_state, UpdateEconomyStateForm,
-};
-use serde::Deserialize;
-use utoipa::ToSchema;
-
-use crate::{extractors::AuthenticatedUser, responses::AppError, AppState};
+pub(crate) use economy_service_client::models::DataAddMoney;
+use economy_service_core::{Approval, MintMoneyForm};
+use economy_service_entity::transaction;
 
-/// Data used in add money operation
-#[derive(Deserialize, ToSchema)]
-pub(crate) struct DataAddMoney {
-    /// Amount of money to add
-    amount: i32,
-}
+use crate::{
+    api_keys::{require_scope, Scope},
+    extractors::{Actor, Caller, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
 
 /// Add money to target user. Bankers only.
+/// Amounts over the configured threshold are proposed for approval by other bankers instead.
+/// API keys with the `mint` scope mint on behalf of the admin who created them, but can't propose mints.
 #[utoipa::path(
-    patch, path = "/{id}", tag = "Economy state", request_body = DataAddMoney,
+    patch, path = "/users/{id}", tag = "Economy state", request_body = DataAddMoney,
     params(
         ("id" = String, Path, description = "Target user ID")
     ),
     responses(
-        (status = 204, description = "Successful fetch"),
-        (status = 401, body = AppError, description = "Authentication failed"),
-        (status = 403, body = AppError, description = "Missing banker role"),
-        (status = 404, body = AppError, description = "User not found"),
+        (status = 200, description = "Successful minting"),
+        (status = 202, body = Proposal, description = "Minting awaits approval"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing banker role or API key scope or target account is frozen or minting requires approval"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
     ),
-    security(("api_key" = []))
+    security(("api_key" = []), ("service_key" = []))
 )]
 pub(crate) async fn add_money(
     Path(id): Path<i32>,
-    AuthenticatedUser(user): AuthenticatedUser,
+    caller: Caller,
+    ctx: RequestContext,
     State(state): State<AppState>,
     Json(data): Json<DataAddMoney>,
-) -> Result<(), impl IntoResponse> {
-    let is_banker = get_or_create_economy_state(user.id, &state.conn)
-        .await
-        .map(|state| state.banker)
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })?;
+) -> Result<Response, ApiError> {
+    if let Caller::ApiKey(key) = &caller {
+        require_scope(key, Scope::Mint)?;
+    }
 
-    if !is_banker {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(AppError::new("Missing banker role")),
-        ));
+    match mint_money(&state, caller.actor(), id, data, ctx, None).await? {
+        Approval::Executed(_) => Ok(StatusCode::OK.into_response()),
+        Approval::Proposed(proposal) => Ok((StatusCode::ACCEPTED, Json(proposal)).into_response()),
     }
+}
 
-    let res = state.users_client.get_user(id).await.map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })?;
+/// Mints (or burns, if the amount is negative) money of the target user once they're found.
+/// API keys are checked for their scope beforehand.
+pub(crate) async fn mint_money(
+    state: &AppState,
+    actor: Actor,
+    id: i32,
+    data: DataAddMoney,
+    ctx: RequestContext,
+    proposal_id: Option<i32>,
+) -> Result<Approval<transaction::Model>, ApiError> {
+    let res = state
+        .users_client
+        .get_user(id)
+        .await
+        .map_err(ApiError::upstream)?;
 
     let user = match res {
         users_service_client::GetUserResponse::Ok(user) => user,
         users_service_client::GetUserResponse::NotFound => {
-            return Err((StatusCode::NOT_FOUND, Json(AppError::new("User not found"))))
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
         }
         _ => unreachable!(),
     };
 
-    let user_state = get_or_create_economy_state(user.id, &state.conn)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })?;
-    let balance = user_state.balance;
-
-    update_economy_state(
-        user_state.into(),
-        UpdateEconomyStateForm {
-            balance: Some(balance + data.amount),
-            ..Default::default()
-        },
-        &state.conn,
-    )
-    .await
-    .map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })?;
-
-    Ok(())
+    let form = MintMoneyForm {
+        user_id: user.id,
+        amount: data.amount,
+        reason: data.reason,
+        actor,
+        proposal_id,
+    };
+    Ok(economy_service_core::mint_money(form, &state.approvals, &ctx.audit(), &state.conn).await?)
 }
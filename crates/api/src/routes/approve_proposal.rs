@@ -0,0 +1,230 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    approve_proposal as approve, claim_proposal, finish_proposal, record_audit_entry, Approval,
+    AuditEntryForm,
+};
+use economy_service_entity::{
+    audit_log_entry::AuditAction,
+    proposal::{self, ProposalKind, ProposalStatus},
+    transaction,
+};
+use sea_orm::{ActiveEnum, TransactionTrait};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    extractors::{Actor, AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{
+        can_approve, find_proposal, mint_money, proposal_details, spend_from_account, DataAddMoney,
+        DataSendFromAccount,
+    },
+    AppState,
+};
+
+/// Parameters of a proposed mint
+#[derive(Deserialize)]
+struct MintPayload {
+    user_id: i32,
+    amount: i32,
+    reason: Option<String>,
+}
+
+/// Approve proposal, executing it once it has enough approvals.
+/// Bankers approve mints, while owners and treasurers of the account approve its payments.
+#[utoipa::path(
+    post, path = "/proposals/{id}/approve", tag = "Proposals",
+    params(
+        ("id" = String, Path, description = "Proposal ID")
+    ),
+    responses(
+        (status = 200, body = ProposalDetails, description = "Successful approval"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not allowed to approve the proposal or proposal is own"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Proposal not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Proposal isn't pending or is already approved by the caller"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn approve_proposal(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let proposal = find_proposal(&state, id).await?;
+    check_pending(&proposal)?;
+
+    if user.id == proposal.proposer_id {
+        return Err(ApiError::new(
+            ErrorCode::OwnProposal,
+            "Cannot approve your own proposal",
+        ));
+    }
+    if !can_approve(&state, &proposal, &user).await? {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Not allowed to approve the proposal",
+        ));
+    }
+
+    // approve and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let approvals = approve(id, user.id, &txn).await?;
+    let approvals = match approvals {
+        Some(approvals) => approvals,
+        None => {
+            return Err(ApiError::new(
+                ErrorCode::AlreadyApproved,
+                "Proposal is already approved by you",
+            ))
+        }
+    };
+
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(json!({
+                "approvals": approvals,
+                "required_approvals": proposal.required_approvals,
+            })),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::ApproveProposal,
+                format!("proposal:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    let mut proposal = proposal;
+    if approvals >= proposal.required_approvals as u64 {
+        let claimed = claim_proposal(id, ProposalStatus::Approved, None, &state.conn).await?;
+
+        // another approver could have got to the execution first
+        if claimed {
+            proposal = execute_proposal(&state, proposal, user.id, ctx).await?;
+        }
+    }
+
+    let proposal = find_proposal(&state, proposal.id).await?;
+    proposal_details(&state, proposal).await.map(Json)
+}
+
+/// Fails with 409 unless the proposal is pending
+pub(crate) fn check_pending(proposal: &proposal::Model) -> Result<(), ApiError> {
+    if proposal.status != ProposalStatus::Pending {
+        return Err(ApiError::new(
+            ErrorCode::ProposalNotPending,
+            format!("Proposal is {}", proposal.status.to_value()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Executes the claimed proposal on behalf of its proposer and records the outcome.
+/// Any failure of the execution fails the proposal, so that it isn't left approved.
+async fn execute_proposal(
+    state: &AppState,
+    proposal: proposal::Model,
+    approver_id: i32,
+    ctx: RequestContext,
+) -> Result<proposal::Model, ApiError> {
+    let (transaction_id, error) = match run_proposal(state, &proposal, ctx.clone()).await {
+        Ok(record) => (Some(record.id), None),
+        Err(err) => {
+            if let Some(cause) = err.cause() {
+                tracing::error!("proposal {} failed: {}", proposal.id, cause);
+            }
+            (None, Some(err.error.detail))
+        }
+    };
+
+    // finish and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let proposal = finish_proposal(proposal.id, transaction_id, error, &txn)
+        .await?
+        .ok_or(ApiError::new(
+            ErrorCode::ProposalNotFound,
+            "Proposal not found",
+        ))?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(json!({
+                "status": proposal.status,
+                "transaction_id": proposal.transaction_id,
+                "error": proposal.error,
+            })),
+            ..ctx.audit_entry(
+                approver_id,
+                AuditAction::ExecuteProposal,
+                format!("proposal:{}", proposal.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(proposal)
+}
+
+/// Runs the operation of the approved proposal on behalf of its proposer
+async fn run_proposal(
+    state: &AppState,
+    proposal: &proposal::Model,
+    ctx: RequestContext,
+) -> Result<transaction::Model, ApiError> {
+    let res = match (proposal.kind, proposal.account_id) {
+        (ProposalKind::Mint, _) => {
+            let payload: MintPayload =
+                serde_json::from_value(proposal.payload.clone()).map_err(ApiError::internal)?;
+            let data = DataAddMoney {
+                amount: payload.amount,
+                reason: payload.reason,
+            };
+            mint_money(
+                state,
+                Actor::User(proposal.proposer_id),
+                payload.user_id,
+                data,
+                ctx,
+                Some(proposal.id),
+            )
+            .await?
+        }
+        (ProposalKind::TreasuryPayment, Some(account_id)) => {
+            let data: DataSendFromAccount =
+                serde_json::from_value(proposal.payload.clone()).map_err(ApiError::internal)?;
+            spend_from_account(
+                state,
+                Actor::User(proposal.proposer_id),
+                account_id,
+                data,
+                ctx,
+                Some(proposal.id),
+            )
+            .await?
+        }
+        (ProposalKind::TreasuryPayment, None) => {
+            return Err(ApiError::internal(
+                "treasury payment proposal has no account",
+            ))
+        }
+    };
+
+    match res {
+        Approval::Executed(record) => Ok(record),
+        Approval::Proposed(_) => Err(ApiError::internal(
+            "execution of the approved proposal was proposed again",
+        )),
+    }
+}
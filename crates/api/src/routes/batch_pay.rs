@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use economy_service_core::{
+    get_active_freeze, get_approval_usage, get_or_create_economy_state, get_transfer_usage,
+    mint_payout as mint_items, mint_payout_each as mint_each, pay_out, pay_out_each,
+    record_audit_entry, transfer_headroom, AuditEntryForm, EconomyError, PayoutItem, TransferUsage,
+};
+use economy_service_entity::{audit_log_entry::AuditAction, proposal::ProposalKind, transaction};
+use futures::{stream, StreamExt, TryStreamExt};
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use users_service_client::GetUserResponse;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{Actor, AuthenticatedUser, Json, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Maximum amount of items in a payout
+const MAX_PAYOUT_ITEMS: usize = 1000;
+
+/// Amount of concurrent payee lookups
+const LOOKUP_CONCURRENCY: usize = 16;
+
+/// How a payout deals with items that can't be paid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PayoutMode {
+    /// Pay all of the items or none of them
+    #[default]
+    Atomic,
+
+    /// Pay every item that can be paid and report the others
+    BestEffort,
+}
+
+/// Single payment of a payout
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataPayoutItem {
+    /// ID of the payee user
+    payee_id: i32,
+
+    /// Amount of money to pay
+    amount: i32,
+
+    /// Comment that will be shown to payee
+    comment: Option<String>,
+}
+
+/// Data used in batch pay operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataBatchPay {
+    /// Payments to make, 1000 at most
+    items: Vec<DataPayoutItem>,
+
+    #[serde(default)]
+    mode: PayoutMode,
+
+    /// Mint the money instead of paying it from own balance. Bankers only.
+    #[serde(default)]
+    mint: bool,
+
+    /// Reason recorded in the audit log when minting
+    reason: Option<String>,
+}
+
+/// Outcome of a payout item
+#[derive(Serialize, ToSchema)]
+pub(crate) struct PayoutResult {
+    /// Position of the item in the payout, starting from 0
+    index: usize,
+
+    payee_id: i32,
+    amount: i32,
+
+    /// ID of the transaction, if the item was paid
+    transaction_id: Option<i32>,
+
+    /// Reason the item can't be paid
+    error: Option<String>,
+
+    /// Machine-readable code of the error
+    code: Option<ErrorCode>,
+}
+impl PayoutResult {
+    fn fail(&mut self, code: ErrorCode, error: impl Into<String>) {
+        self.code = Some(code);
+        self.error = Some(error.into());
+    }
+}
+
+/// Outcome of a payout
+#[derive(Serialize, ToSchema)]
+pub(crate) struct PayoutReport {
+    /// Amount of items in the payout
+    items: usize,
+
+    /// Amount of paid items
+    paid: usize,
+
+    /// Sum of paid amounts
+    total: i64,
+
+    /// Outcomes of the items, in the order they were given
+    results: Vec<PayoutResult>,
+}
+
+/// Pay money to many players at once, e.g. salaries or event rewards.
+///
+/// All items are validated first. Atomic payouts are made only if every item can be paid,
+/// while best-effort payouts pay what they can and report the rest.
+/// Bankers can mint the paid money instead of spending their balance.
+#[utoipa::path(
+    post, path = "/payouts", tag = "Economy state",
+    request_body = DataBatchPay,
+    responses(
+        (status = 200, body = PayoutReport, description = "Payout made"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: no or too many items or insufficient funds"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing banker role or mint requires approval or payer account is frozen"),
+        (status = 422, body = PayoutReport, description = "Some items of atomic payout can't be paid, nothing paid"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn batch_pay(
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataBatchPay>,
+) -> Result<Response, ApiError> {
+    if data.items.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Payout should have at least one item",
+        ));
+    }
+    if data.items.len() > MAX_PAYOUT_ITEMS {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            format!("Payout should have at most {} items", MAX_PAYOUT_ITEMS),
+        ));
+    }
+
+    let payer_state = get_or_create_economy_state(user.id, &state.conn).await?;
+
+    if data.mint {
+        if !payer_state.banker {
+            return Err(ApiError::new(
+                ErrorCode::MissingBankerRole,
+                "Missing banker role",
+            ));
+        }
+    } else {
+        // check whether payer can send money
+        let freeze = get_active_freeze(user.id, &state.conn).await?;
+        if freeze.is_some() {
+            return Err(ApiError::new(
+                ErrorCode::AccountFrozen,
+                "Your account is frozen",
+            ));
+        }
+    }
+
+    // check items on their own
+    let mut results: Vec<PayoutResult> = data
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let mut result = PayoutResult {
+                index,
+                payee_id: item.payee_id,
+                amount: item.amount,
+                transaction_id: None,
+                error: None,
+                code: None,
+            };
+            if item.amount <= 0 {
+                result.fail(ErrorCode::InvalidAmount, "Amount should be more than 0");
+            } else if !data.mint && item.payee_id == user.id {
+                result.fail(ErrorCode::SelfPayment, "Cannot pay to yourself");
+            }
+            result
+        })
+        .collect();
+
+    // check whether payees exist and can receive money, looking each of them up once
+    let mut payee_ids: Vec<i32> = results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .map(|result| result.payee_id)
+        .collect();
+    payee_ids.sort_unstable();
+    payee_ids.dedup();
+
+    let client = &state.users_client;
+    let missing: HashSet<i32> = stream::iter(payee_ids.clone())
+        .map(|id| async move {
+            match client.get_user(id).await? {
+                GetUserResponse::Ok(_) => Ok(None),
+                _ => Ok::<_, reqwest::Error>(Some(id)),
+            }
+        })
+        .buffer_unordered(LOOKUP_CONCURRENCY)
+        .try_filter_map(|id| async move { Ok(id) })
+        .try_collect()
+        .await
+        .map_err(ApiError::upstream)?;
+
+    for result in results.iter_mut().filter(|result| result.error.is_none()) {
+        if missing.contains(&result.payee_id) {
+            result.fail(ErrorCode::UserNotFound, "User not found");
+        }
+    }
+
+    if data.mint {
+        let total: i64 = results
+            .iter()
+            .filter(|result| result.error.is_none())
+            .map(|result| result.amount as i64)
+            .sum();
+        let used = get_approval_usage(
+            &state.approvals,
+            ProposalKind::Mint,
+            user.id,
+            None,
+            &state.conn,
+        )
+        .await?;
+        if state
+            .approvals
+            .requires_approval(ProposalKind::Mint, used, total)
+        {
+            return Err(approval_required());
+        }
+    } else {
+        // check funds and limits once for the whole payout, taking items in order
+        let limits = state.transfer_limits.limits_for(&payer_state);
+        let usage = get_transfer_usage(user.id, state.transfer_limits.window, &state.conn).await?;
+
+        let mut spent = 0;
+        for result in results.iter_mut().filter(|result| result.error.is_none()) {
+            let amount = result.amount as i64;
+            let headroom = transfer_headroom(
+                &limits,
+                &TransferUsage {
+                    daily: usage.daily + spent,
+                    window: usage.window + spent,
+                },
+            );
+
+            if amount > payer_state.balance as i64 - spent {
+                result.fail(ErrorCode::InsufficientFunds, "Insufficient funds");
+            } else if let Some(headroom) = headroom.filter(|headroom| amount > headroom.remaining) {
+                result.fail(
+                    ErrorCode::TransferLimitExceeded,
+                    format!(
+                        "Transfer exceeds {} limit, {} remaining",
+                        headroom.kind, headroom.remaining
+                    ),
+                );
+            } else {
+                spent += amount;
+            }
+        }
+    }
+
+    let mut report = PayoutReport {
+        items: results.len(),
+        paid: 0,
+        total: 0,
+        results: vec![],
+    };
+
+    if data.mode == PayoutMode::Atomic && results.iter().any(|result| result.error.is_some()) {
+        report.results = results;
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(report)).into_response());
+    }
+
+    let (indices, items): (Vec<usize>, Vec<PayoutItem>) = results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .map(|result| {
+            (
+                result.index,
+                PayoutItem {
+                    payee_id: result.payee_id,
+                    amount: result.amount,
+                    comment: data.items[result.index].comment.clone(),
+                },
+            )
+        })
+        .unzip();
+
+    let records: Vec<Result<transaction::Model, ApiError>> = if items.is_empty() {
+        vec![]
+    } else if data.mint {
+        mint_payout(&state, user.id, &items, data.mode, data.reason, ctx).await?
+    } else if data.mode == PayoutMode::Atomic {
+        // payer could have spent their money while this request was processed
+        pay_out(user.id, &items, Some(state.transfer_limits), &state.conn)
+            .await?
+            .into_iter()
+            .map(Ok)
+            .collect()
+    } else {
+        // items that can't be paid anymore are reported like the invalid ones,
+        // as the ones before them are paid already
+        pay_out_each(user.id, &items, Some(state.transfer_limits), &state.conn)
+            .await
+            .into_iter()
+            .map(|record| record.map_err(ApiError::from))
+            .collect()
+    };
+
+    for (index, record) in indices.into_iter().zip(records) {
+        let result = &mut results[index];
+        match record {
+            Ok(record) => {
+                result.transaction_id = Some(record.id);
+                report.paid += 1;
+                report.total += record.amount as i64;
+            }
+            Err(err) => {
+                if let Some(cause) = err.cause() {
+                    tracing::error!("payout item {} failed: {}", index, cause);
+                }
+                result.fail(err.error.code, err.error.detail);
+            }
+        }
+    }
+
+    report.results = results;
+    Ok((StatusCode::OK, Json(report)).into_response())
+}
+
+/// Minted payouts can't be proposed, so their amount is refused instead
+fn approval_required() -> ApiError {
+    ApiError::new(
+        ErrorCode::ApprovalRequired,
+        "Minting this much requires approval, mint to users one by one instead",
+    )
+}
+
+/// Mints the items and records the minted ones in the audit log atomically.
+/// In atomic mode, an item that can't be minted, such as one to a frozen payee, fails the payout.
+async fn mint_payout(
+    state: &AppState,
+    banker_id: i32,
+    items: &[PayoutItem],
+    mode: PayoutMode,
+    reason: Option<String>,
+    ctx: RequestContext,
+) -> Result<Vec<Result<transaction::Model, ApiError>>, ApiError> {
+    let txn = state.conn.begin().await?;
+
+    // the threshold is checked again, as the banker could have minted in the meantime
+    let actor = Actor::User(banker_id);
+    let records = match mode {
+        PayoutMode::Atomic => mint_items(actor, items, &state.approvals, &txn)
+            .await
+            .map(|records| records.into_iter().map(Ok).collect()),
+        PayoutMode::BestEffort => mint_each(actor, items, &state.approvals, &txn).await,
+    }
+    .map_err(|err| match err {
+        EconomyError::ApprovalRequired => approval_required(),
+        err => err.into(),
+    })?;
+
+    let minted: Vec<&transaction::Model> = records.iter().flatten().collect();
+    let total: i64 = minted.iter().map(|record| record.amount as i64).sum();
+    let transaction_ids: Vec<i32> = minted.iter().map(|record| record.id).collect();
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(json!({
+                "items": minted.len(),
+                "total": total,
+                "transaction_ids": transaction_ids,
+            })),
+            reason,
+            ..ctx.audit_entry(banker_id, AuditAction::MintPayout, String::from("payout"))
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.map_err(ApiError::from))
+        .collect())
+}
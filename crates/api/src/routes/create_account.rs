@@ -0,0 +1,57 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{create_account as create, CreateAccountForm};
+use economy_service_entity::account::AccountKind;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Data used in create account operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataCreateAccount {
+    /// Kind of the account
+    kind: AccountKind,
+
+    /// Name of the account, 64 characters at most
+    name: String,
+}
+
+/// Create a shop, company or organization account owned by the caller
+#[utoipa::path(
+    post, path = "/accounts", tag = "Accounts", request_body = DataCreateAccount,
+    responses(
+        (status = 201, body = Account, description = "Successful creation"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid name"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn create_account(
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(data): Json<DataCreateAccount>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let name = data.name.trim();
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Name should be 1 to 64 characters long",
+        ));
+    }
+
+    let form = CreateAccountForm {
+        kind: data.kind,
+        name: name.to_owned(),
+        owner_id: user.id,
+    };
+
+    create(form, &state.conn)
+        .await
+        .map(|account| (StatusCode::CREATED, Json(account)))
+        .map_err(ApiError::internal)
+}
@@ -0,0 +1,161 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use economy_service_core::{
+    create_api_key as create, get_account, get_or_create_economy_state, record_audit_entry,
+    AuditEntryForm, CreateApiKeyForm,
+};
+use economy_service_entity::{
+    account_member::AccountRole, api_key::Model as ApiKey, audit_log_entry::AuditAction,
+};
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api_keys::{generate_key, Scope},
+    extractors::{AuthenticatedUser, Json, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::require_account_role,
+    AppState,
+};
+
+/// Data used in create API key operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataCreateApiKey {
+    /// Name describing who uses the key
+    name: String,
+
+    /// Scopes like `read:balances`, `pay:from:<account ID>` or `mint`
+    scopes: Vec<String>,
+
+    /// Time after which the key is rejected
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// API key along with the key itself, which is only shown once
+#[derive(Serialize, ToSchema)]
+pub(crate) struct CreatedApiKey {
+    #[serde(flatten)]
+    api_key: ApiKey,
+
+    /// Key to send in the `x-api-key` header
+    key: String,
+}
+
+/// Create an API key for a game server or another service. Admins only.
+#[utoipa::path(
+    post, path = "/api-keys", tag = "API keys", request_body = DataCreateApiKey,
+    responses(
+        (status = 201, body = CreatedApiKey, description = "Successful creation"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: empty name or no scopes or unknown scope or expiry in the past"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role, or banker role for the mint scope, or account owner role for a pay:from scope"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account of a scope not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn create_api_key(
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataCreateApiKey>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    if data.name.trim().is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Name should not be empty",
+        ));
+    }
+
+    if data.scopes.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "API key should be granted at least one scope",
+        ));
+    }
+
+    for scope in &data.scopes {
+        match scope.parse() {
+            Ok(Scope::PayFrom(account_id)) => {
+                if get_account(account_id, &state.conn).await?.is_none() {
+                    return Err(ApiError::new(
+                        ErrorCode::AccountNotFound,
+                        format!("Account {} not found", account_id),
+                    ));
+                }
+                // keys spend on behalf of their creator, who has to own the account
+                require_account_role(&state, account_id, user.id, &[AccountRole::Owner]).await?;
+            }
+            // keys mint on behalf of their creator
+            Ok(Scope::Mint) => {
+                if !get_or_create_economy_state(user.id, &state.conn)
+                    .await?
+                    .banker
+                {
+                    return Err(ApiError::new(
+                        ErrorCode::MissingBankerRole,
+                        "Only bankers can create keys with the mint scope",
+                    ));
+                }
+            }
+            Ok(Scope::ReadBalances) => {}
+            Err(err) => return Err(ApiError::new(ErrorCode::ValidationFailed, err)),
+        }
+    }
+
+    if matches!(data.expires_at, Some(expires_at) if expires_at <= Utc::now()) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Expiry should be in the future",
+        ));
+    }
+
+    let key = generate_key();
+
+    // create and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let api_key = create(
+        CreateApiKeyForm {
+            name: data.name,
+            prefix: key.prefix,
+            key_hash: key.hash,
+            scopes: data.scopes,
+            created_by: user.id,
+            expires_at: data.expires_at,
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(serde_json::to_value(&api_key).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::CreateApiKey,
+                format!("api_key:{}", api_key.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiKey {
+            api_key,
+            key: key.key,
+        }),
+    ))
+}
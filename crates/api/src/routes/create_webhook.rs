@@ -0,0 +1,106 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{
+    create_webhook as create, record_audit_entry, AuditEntryForm, CreateWebhookForm,
+};
+use economy_service_entity::{audit_log_entry::AuditAction, webhook::WebhookEvent};
+use reqwest::Url;
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Data used in create webhook operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataCreateWebhook {
+    /// HTTP(S) URL the events are sent to
+    url: String,
+
+    /// Secret used to sign the payloads with HMAC-SHA256
+    secret: String,
+
+    /// Events to subscribe to
+    events: Vec<WebhookEvent>,
+}
+
+/// Subscribe a webhook to balance events. Admins only.
+#[utoipa::path(
+    post, path = "/webhooks", tag = "Webhooks", request_body = DataCreateWebhook,
+    responses(
+        (status = 201, body = Webhook, description = "Successful creation"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid URL or empty secret or no events"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn create_webhook(
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataCreateWebhook>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    if !matches!(Url::parse(&data.url), Ok(url) if ["http", "https"].contains(&url.scheme())) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "URL should be a valid HTTP(S) URL",
+        ));
+    }
+
+    if data.secret.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Secret should not be empty",
+        ));
+    }
+
+    if data.events.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Webhook should subscribe to at least one event",
+        ));
+    }
+
+    // create and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let webhook = create(
+        CreateWebhookForm {
+            url: data.url,
+            secret: data.secret,
+            events: data.events,
+            created_by: user.id,
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(serde_json::to_value(&webhook).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::CreateWebhook,
+                format!("webhook:{}", webhook.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
@@ -0,0 +1,48 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::decline_loan as decline;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    routes::find_loan,
+    AppState,
+};
+
+/// Decline a loan offered to you, or withdraw a loan you offered. Borrower and lender only.
+#[utoipa::path(
+    post, path = "/loans/{id}/decline", tag = "Loans",
+    params(
+        ("id" = String, Path, description = "Loan ID")
+    ),
+    responses(
+        (status = 200, body = Loan, description = "Successful decline"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Caller isn't the borrower or lender"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Loan not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Loan was already accepted or declined"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn decline_loan(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let loan = find_loan(&state, id).await?;
+
+    if user.id != loan.borrower_id && user.id != loan.lender_id {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Only the borrower and the lender can decline the loan",
+        ));
+    }
+
+    decline(id, &state.conn)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::new(
+            ErrorCode::LoanNotPending,
+            "Loan was already accepted or declined",
+        ))
+}
@@ -0,0 +1,71 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{delete_webhook as delete, record_audit_entry, AuditEntryForm};
+use economy_service_entity::audit_log_entry::AuditAction;
+use sea_orm::TransactionTrait;
+
+use crate::{
+    extractors::{AuthenticatedUser, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Delete webhook along with its delivery log. Admins only.
+#[utoipa::path(
+    delete, path = "/webhooks/{id}", tag = "Webhooks",
+    params(
+        ("id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 204, description = "Successful deletion"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Webhook not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn delete_webhook(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    // delete and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let webhook = delete(id, &txn).await?;
+
+    let webhook = match webhook {
+        Some(webhook) => webhook,
+        None => {
+            return Err(ApiError::new(
+                ErrorCode::WebhookNotFound,
+                "Webhook not found",
+            ))
+        }
+    };
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(serde_json::to_value(&webhook).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::DeleteWebhook,
+                format!("webhook:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,123 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use economy_service_core::{
+    freeze_account, get_active_freeze, record_audit_entry, AuditEntryForm, FreezeAccountForm,
+};
+use economy_service_entity::audit_log_entry::AuditAction;
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Data used in freeze operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataFreeze {
+    /// Reason of the freeze
+    reason: String,
+
+    /// Whether the account should be unable to receive money as well
+    #[serde(default)]
+    block_incoming: bool,
+
+    /// Time the freeze expires at. Never expires if omitted.
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// Freeze account of target user so it cannot send money. Admins only.
+#[utoipa::path(
+    put, path = "/users/{id}/freeze", tag = "Account freezes", request_body = DataFreeze,
+    params(
+        ("id" = String, Path, description = "Target user ID")
+    ),
+    responses(
+        (status = 200, body = AccountFreeze, description = "Successful freeze"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: empty reason or expiry in the past"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn freeze(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataFreeze>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    if data.reason.trim().is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Reason should not be empty",
+        ));
+    }
+
+    if matches!(data.expires_at, Some(expires_at) if expires_at <= Utc::now()) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Expiry should be in the future",
+        ));
+    }
+
+    let res = state
+        .users_client
+        .get_user(id)
+        .await
+        .map_err(ApiError::upstream)?;
+
+    let target = match res {
+        users_service_client::GetUserResponse::Ok(user) => user,
+        users_service_client::GetUserResponse::NotFound => {
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
+        }
+        _ => unreachable!(),
+    };
+
+    // freeze and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let before = get_active_freeze(target.id, &txn).await?;
+
+    let freeze = freeze_account(
+        target.id,
+        FreezeAccountForm {
+            reason: data.reason.clone(),
+            actor_id: user.id,
+            block_incoming: data.block_incoming,
+            expires_at: data.expires_at,
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: before
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(ApiError::internal)?,
+            after: Some(serde_json::to_value(&freeze).map_err(ApiError::internal)?),
+            reason: Some(data.reason),
+            ..ctx.audit_entry(user.id, AuditAction::Freeze, format!("user:{}", target.id))
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(freeze))
+}
@@ -0,0 +1,79 @@
+use axum::extract::State;
+use economy_service_core::{get_account as find, get_account_member};
+use economy_service_entity::{
+    account,
+    account_member::{self, AccountRole},
+};
+use sea_orm::ActiveEnum;
+
+use crate::{
+    extractors::{Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Fetch shared account by ID
+#[utoipa::path(
+    get, path = "/accounts/{id}", tag = "Accounts",
+    params(
+        ("id" = String, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, body = Account, description = "Successful fetch"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    )
+)]
+pub(crate) async fn get_account(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<account::Model>, ApiError> {
+    find_account(&state, id).await.map(Json)
+}
+
+/// Fetches the account or fails with 404 if there is none
+pub(crate) async fn find_account(state: &AppState, id: i32) -> Result<account::Model, ApiError> {
+    find(id, &state.conn).await?.ok_or(ApiError::new(
+        ErrorCode::AccountNotFound,
+        "Account not found",
+    ))
+}
+
+/// Fetches membership of the user in the account, failing with 403 if they aren't a member
+pub(crate) async fn require_account_member(
+    state: &AppState,
+    account_id: i32,
+    user_id: i32,
+) -> Result<account_member::Model, ApiError> {
+    get_account_member(account_id, user_id, &state.conn)
+        .await?
+        .ok_or(ApiError::new(
+            ErrorCode::MissingAccountRole,
+            "Not a member of the account",
+        ))
+}
+
+/// Fetches membership of the user in the account, failing with 403
+/// if they aren't a member with one of the roles
+pub(crate) async fn require_account_role(
+    state: &AppState,
+    account_id: i32,
+    user_id: i32,
+    roles: &[AccountRole],
+) -> Result<account_member::Model, ApiError> {
+    match require_account_member(state, account_id, user_id).await {
+        Ok(member) if roles.contains(&member.role) => Ok(member),
+        _ => {
+            let mut roles: Vec<String> = roles.iter().map(|role| role.to_value()).collect();
+            let last = roles.pop().unwrap_or_default();
+            let roles = match roles.is_empty() {
+                true => last,
+                false => format!("{} or {}", roles.join(", "), last),
+            };
+            Err(ApiError::new(
+                ErrorCode::MissingAccountRole,
+                format!("Missing account {} role", roles),
+            ))
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_account_members as find;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::ApiError,
+    routes::{find_account, require_account_member},
+    AppState,
+};
+
+/// Fetch members of shared account. Members and admins only.
+#[utoipa::path(
+    get, path = "/accounts/{id}/members", tag = "Accounts",
+    params(
+        ("id" = String, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, body = [AccountMember], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not a member of the account"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_account_members(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    find_account(&state, id).await?;
+
+    if !user.admin {
+        require_account_member(&state, id, user.id).await?;
+    }
+
+    find(id, &state.conn)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
@@ -0,0 +1,62 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_account_transactions as find;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, Query},
+    responses::ApiError,
+    routes::{find_account, require_account_member},
+    AppState,
+};
+
+/// Query parameters of account transactions fetch
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AccountTransactionsQuery {
+    /// Only transactions with IDs less than this one
+    before: Option<i32>,
+
+    /// Maximum amount of transactions to return, 100 by default and 1000 at most
+    limit: Option<u64>,
+}
+
+/// Fetch transactions of shared account, newest first. Spending shows the initiating member.
+/// Members and admins only.
+#[utoipa::path(
+    get, path = "/accounts/{id}/transactions", tag = "Accounts",
+    params(
+        ("id" = String, Path, description = "Account ID"),
+        AccountTransactionsQuery,
+    ),
+    responses(
+        (status = 200, body = [Transaction], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not a member of the account"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_account_transactions(
+    Path(id): Path<i32>,
+    Query(query): Query<AccountTransactionsQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    find_account(&state, id).await?;
+
+    if !user.admin {
+        require_account_member(&state, id, user.id).await?;
+    }
+
+    find(
+        id,
+        query.before,
+        query.limit.unwrap_or(100).min(1000),
+        &state.conn,
+    )
+    .await
+    .map(Json)
+    .map_err(ApiError::internal)
+}
@@ -0,0 +1,36 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_api_keys as find;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Fetch all API keys, including revoked and expired ones. Admins only.
+#[utoipa::path(
+    get, path = "/api-keys", tag = "API keys",
+    responses(
+        (status = 200, body = [ApiKey], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_api_keys(
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    find(&state.conn)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
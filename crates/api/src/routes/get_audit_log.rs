@@ -0,0 +1,74 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use economy_service_core::{find_audit_entries, AuditLogFilter};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Query},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of audit log fetch
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AuditLogQuery {
+    /// Only entries of operations performed by this user
+    actor_id: Option<i32>,
+
+    /// Only entries of operations on this target, e.g. `user:42`
+    target: Option<String>,
+
+    /// Only entries created at or after this time
+    from: Option<DateTime<Utc>>,
+
+    /// Only entries created before this time
+    to: Option<DateTime<Utc>>,
+
+    /// Maximum amount of entries to return, 100 by default and 1000 at most
+    limit: Option<u64>,
+
+    /// Amount of entries to skip
+    offset: Option<u64>,
+}
+
+/// Fetch audit log of privileged operations, newest first. Admins only.
+#[utoipa::path(
+    get, path = "/audit", tag = "Audit log",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, body = [AuditLogEntry], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_audit_log(
+    Query(query): Query<AuditLogQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    find_audit_entries(
+        AuditLogFilter {
+            actor_id: query.actor_id,
+            target: query.target,
+            from: query.from,
+            to: query.to,
+        },
+        query.limit.unwrap_or(100).min(1000),
+        query.offset.unwrap_or(0),
+        &state.conn,
+    )
+    .await
+    .map(Json)
+    .map_err(ApiError::internal)
+}
@@ -0,0 +1,76 @@
+use axum::{body::StreamBody, extract::State, http::header, response::IntoResponse};
+use economy_service_core::{
+    export_balances, get_balances_export_user_ids, get_or_create_economy_state, ExportFormat,
+};
+use futures::TryStreamExt;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Query},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of balances export
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct BalancesExportQuery {
+    /// Format of the export, `csv` by default
+    #[param(inline)]
+    format: Option<ExportFormat>,
+}
+
+/// Export balances of all users, ordered by user ID. Bankers and admins only.
+#[utoipa::path(
+    get, path = "/export/balances", tag = "Export",
+    params(BalancesExportQuery),
+    responses(
+        (status = 200, body = String, content_type = ["text/csv", "application/x-ndjson"],
+            description = "Balances with user IDs, usernames, banker roles and tiers"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing banker or admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_balances_export(
+    Query(query): Query<BalancesExportQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let is_banker = get_or_create_economy_state(user.id, &state.conn)
+        .await
+        .map(|state| state.banker)?;
+
+    if !user.admin && !is_banker {
+        return Err(ApiError::new(
+            ErrorCode::MissingBankerRole,
+            "Missing banker or admin role",
+        ));
+    }
+
+    // resolved before the response starts, so that the users service failing
+    // doesn't cut the export short
+    let user_ids = get_balances_export_user_ids(&state.conn).await?;
+    let usernames = state
+        .users_client
+        .get_usernames(user_ids)
+        .await
+        .map_err(ApiError::upstream)?;
+
+    let format = query.format.unwrap_or_default();
+    let export = export_balances(state.conn, usernames, format)
+        .inspect_err(|err| tracing::error!("balances export failed: {}", err));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"balances.{}\"", format.extension()),
+            ),
+        ],
+        StreamBody::new(export),
+    ))
+}
@@ -1,50 +1,98 @@
-use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+use axum::extract::State;
+use economy_service_client::models::EconomyState;
+use economy_service_core::{get_economy_state_details, get_or_create_economy_state};
+use economy_service_entity::account_freeze::Model as AccountFreeze;
+use serde::Serialize;
+use users_service_client::User;
+use utoipa::ToSchema;
+
+use crate::{
+    api_keys::{require_scope, Scope},
+    extractors::{Caller, Json, Path},
+    responses::{economy_state, ApiError, ErrorCode},
+    AppState,
 };
-use economy_service_core::get_or_create_economy_state;
 
-use crate::{responses::AppError, AppState};
+/// Economy state of user with details visible to bankers
+#[derive(Serialize, ToSchema)]
+pub(crate) struct EconomyStateDetails {
+    #[serde(flatten)]
+    pub(crate) state: EconomyState,
+
+    /// Active freeze of the account. Only shown to bankers and API keys with the `read:balances` scope.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) freeze: Option<AccountFreeze>,
+}
 
 /// Fetch economy state of user by their ID
 #[utoipa::path(
-    get, path = "/{id}", tag = "Economy state",
+    get, path = "/users/{id}", tag = "Economy state",
     params(
         ("id" = String, Path, description = "Target user ID")
     ),
     responses(
-        (status = 200, body = EconomyState, description = "Successful fetch"),
-        (status = 404, body = AppError, description = "User not found"),
+        (status = 200, body = EconomyStateDetails, description = "Successful fetch"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing API key scope"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
     ),
+    security((), ("api_key" = []), ("service_key" = []))
 )]
 pub(crate) async fn get_by_id(
     Path(id): Path<i32>,
+    caller: Option<Caller>,
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, impl IntoResponse> {
-    let res = state.users_client.get_user(id).await.map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })?;
+) -> Result<Json<EconomyStateDetails>, ApiError> {
+    find_economy_state_details(&state, id, caller.as_ref())
+        .await
+        .map(Json)
+}
+
+/// Fetches economy state of the user, along with details visible to the caller
+pub(crate) async fn find_economy_state_details(
+    state: &AppState,
+    id: i32,
+    caller: Option<&Caller>,
+) -> Result<EconomyStateDetails, ApiError> {
+    let res = state
+        .users_client
+        .get_user(id)
+        .await
+        .map_err(ApiError::upstream)?;
 
     let user = match res {
         users_service_client::GetUserResponse::Ok(user) => user,
         users_service_client::GetUserResponse::NotFound => {
-            return Err((StatusCode::NOT_FOUND, Json(AppError::new("User not found"))))
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
         }
         _ => unreachable!(),
     };
 
-    get_or_create_economy_state(user.id, &state.conn)
-        .await
-        .map(Json)
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })
+    economy_state_details(state, &user, caller).await
+}
+
+/// Fetches economy state of the resolved user, along with details visible to the caller
+pub(crate) async fn economy_state_details(
+    state: &AppState,
+    user: &User,
+    caller: Option<&Caller>,
+) -> Result<EconomyStateDetails, ApiError> {
+    // freezes are only disclosed to bankers and keys allowed to read balances
+    let show_freeze = match caller {
+        Some(Caller::User(caller)) => get_or_create_economy_state(caller.id, &state.conn)
+            .await
+            .map(|state| state.banker)?,
+        Some(Caller::ApiKey(key)) => {
+            require_scope(key, Scope::ReadBalances)?;
+            true
+        }
+        None => false,
+    };
+
+    let details = get_economy_state_details(user.id, show_freeze, &state.conn).await?;
+
+    Ok(EconomyStateDetails {
+        state: economy_state(details.state, details.debt),
+        freeze: details.freeze,
+    })
 }
@@ -0,0 +1,79 @@
+use axum::extract::State;
+use users_service_client::{GetUserResponse, User};
+
+use crate::{
+    extractors::{Caller, Json, Path},
+    responses::{ApiError, ErrorCode},
+    routes::{economy_state_details, EconomyStateDetails},
+    AppState,
+};
+
+/// Maximum length of Minecraft usernames
+const MAX_USERNAME_LENGTH: usize = 16;
+
+/// Fetch economy state of user by their username, ignoring case
+#[utoipa::path(
+    get, path = "/users/by-username/{username}", tag = "Economy state",
+    params(
+        ("username" = String, Path, description = "Target username")
+    ),
+    responses(
+        (status = 200, body = EconomyStateDetails, description = "Successful fetch"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Invalid username"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing API key scope"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security((), ("api_key" = []), ("service_key" = []))
+)]
+pub(crate) async fn get_by_username(
+    Path(username): Path<String>,
+    caller: Option<Caller>,
+    State(state): State<AppState>,
+) -> Result<Json<EconomyStateDetails>, ApiError> {
+    let user = find_user_by_username(&state, &username).await?;
+    economy_state_details(&state, &user, caller.as_ref())
+        .await
+        .map(Json)
+}
+
+/// Resolves the username to a user, ignoring case, or fails with 404 if there is none
+pub(crate) async fn find_user_by_username(
+    state: &AppState,
+    username: &str,
+) -> Result<User, ApiError> {
+    // Minecraft usernames only consist of letters, digits and underscores
+    let valid = !username.is_empty()
+        && username.len() <= MAX_USERNAME_LENGTH
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(ApiError::new(
+            ErrorCode::InvalidUsername,
+            format!(
+                "Invalid username, expected at most {} letters, digits or underscores",
+                MAX_USERNAME_LENGTH
+            ),
+        ));
+    }
+
+    // the users service looks up lowercase usernames
+    let res = state
+        .users_client
+        .get_user_by_username(&username.to_ascii_lowercase())
+        .await
+        .map_err(ApiError::upstream)?;
+
+    match res {
+        GetUserResponse::Ok(user) => Ok(user),
+        GetUserResponse::NotFound => Err(ApiError::new(
+            ErrorCode::UserNotFound,
+            format!("No user named {}", username),
+        )),
+        GetUserResponse::BadRequest => Err(ApiError::new(
+            ErrorCode::InvalidUsername,
+            "Invalid username",
+        )),
+    }
+}
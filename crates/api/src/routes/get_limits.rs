@@ -0,0 +1,83 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{get_or_create_economy_state, get_transfer_usage, transfer_headroom};
+use economy_service_entity::economy_state::AccountTier;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Effective transfer limits of an account and their usage
+#[derive(Serialize, ToSchema)]
+pub(crate) struct TransferLimitsInfo {
+    /// Limits tier of the account
+    tier: AccountTier,
+
+    /// Maximum amount of a single payment
+    per_transaction: Option<i32>,
+
+    /// Maximum amount sent per day (UTC)
+    daily: Option<i32>,
+
+    /// Maximum amount sent within the rolling window
+    window: Option<i32>,
+
+    /// Length of the rolling window in hours
+    window_hours: i64,
+
+    /// Money sent today
+    daily_used: i64,
+
+    /// Money sent within the rolling window
+    window_used: i64,
+
+    /// Maximum amount that can be sent right now, if limited
+    headroom: Option<i64>,
+}
+
+/// Fetch transfer limits of user. Account owner or admins only.
+#[utoipa::path(
+    get, path = "/users/{id}/limits", tag = "Transfer limits",
+    params(
+        ("id" = String, Path, description = "Target user ID")
+    ),
+    responses(
+        (status = 200, body = TransferLimitsInfo, description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not the account owner or admin"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_limits(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if user.id != id && !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAccountRole,
+            "Not the account owner or admin",
+        ));
+    }
+
+    let user_state = get_or_create_economy_state(id, &state.conn).await?;
+
+    let usage = get_transfer_usage(id, state.transfer_limits.window, &state.conn).await?;
+
+    let limits = state.transfer_limits.limits_for(&user_state);
+
+    Ok(Json(TransferLimitsInfo {
+        tier: user_state.tier,
+        per_transaction: limits.per_transaction,
+        daily: limits.daily,
+        window: limits.window,
+        window_hours: state.transfer_limits.window.num_hours(),
+        daily_used: usage.daily,
+        window_used: usage.window,
+        headroom: transfer_headroom(&limits, &usage).map(|headroom| headroom.remaining),
+    }))
+}
@@ -0,0 +1,54 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{get_loan as find, get_or_create_economy_state};
+use economy_service_entity::loan;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Fetch loan by ID. Lender, borrower, bankers and admins only.
+#[utoipa::path(
+    get, path = "/loans/{id}", tag = "Loans",
+    params(
+        ("id" = String, Path, description = "Loan ID")
+    ),
+    responses(
+        (status = 200, body = Loan, description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not allowed to see the loan"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Loan not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_loan(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let loan = find_loan(&state, id).await?;
+
+    if !user.admin && user.id != loan.lender_id && user.id != loan.borrower_id {
+        let is_banker = get_or_create_economy_state(user.id, &state.conn)
+            .await
+            .map(|state| state.banker)?;
+
+        if !is_banker {
+            return Err(ApiError::new(
+                ErrorCode::NotAllowed,
+                "Not allowed to see the loan",
+            ));
+        }
+    }
+
+    Ok(Json(loan))
+}
+
+/// Fetches the loan or fails with 404 if there is none
+pub(crate) async fn find_loan(state: &AppState, id: i32) -> Result<loan::Model, ApiError> {
+    find(id, &state.conn)
+        .await?
+        .ok_or(ApiError::new(ErrorCode::LoanNotFound, "Loan not found"))
+}
@@ -0,0 +1,110 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    expire_proposals, get_account_member, get_or_create_economy_state, get_proposal as find,
+    get_proposal_approvals,
+};
+use economy_service_entity::{
+    account_member::AccountRole,
+    proposal::{self, ProposalKind},
+    proposal_approval,
+};
+use serde::Serialize;
+use users_service_client::User;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Proposal along with its approvals
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ProposalDetails {
+    #[serde(flatten)]
+    pub(crate) proposal: proposal::Model,
+
+    /// Approvals given so far
+    pub(crate) approvals: Vec<proposal_approval::Model>,
+}
+
+/// Fetch proposal by ID. Proposer, approvers and admins only.
+#[utoipa::path(
+    get, path = "/proposals/{id}", tag = "Proposals",
+    params(
+        ("id" = String, Path, description = "Proposal ID")
+    ),
+    responses(
+        (status = 200, body = ProposalDetails, description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not allowed to see the proposal"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Proposal not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_proposal(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let proposal = find_proposal(&state, id).await?;
+
+    if !user.admin
+        && user.id != proposal.proposer_id
+        && !can_approve(&state, &proposal, &user).await?
+    {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Not allowed to see the proposal",
+        ));
+    }
+
+    proposal_details(&state, proposal).await.map(Json)
+}
+
+/// Fetches the proposal, expiring it first if its time is up, or fails with 404 if there is none
+pub(crate) async fn find_proposal(state: &AppState, id: i32) -> Result<proposal::Model, ApiError> {
+    expire_proposals(&state.conn).await?;
+
+    find(id, &state.conn).await?.ok_or(ApiError::new(
+        ErrorCode::ProposalNotFound,
+        "Proposal not found",
+    ))
+}
+
+pub(crate) async fn proposal_details(
+    state: &AppState,
+    proposal: proposal::Model,
+) -> Result<ProposalDetails, ApiError> {
+    let approvals = get_proposal_approvals(proposal.id, &state.conn).await?;
+
+    Ok(ProposalDetails {
+        proposal,
+        approvals,
+    })
+}
+
+/// Whether the user may approve or reject the proposal: bankers approve mints,
+/// while owners and treasurers of the account approve its payments
+pub(crate) async fn can_approve(
+    state: &AppState,
+    proposal: &proposal::Model,
+    user: &User,
+) -> Result<bool, ApiError> {
+    let res = match (proposal.kind, proposal.account_id) {
+        (ProposalKind::Mint, _) => get_or_create_economy_state(user.id, &state.conn)
+            .await
+            .map(|state| state.banker),
+        (ProposalKind::TreasuryPayment, Some(account_id)) => {
+            get_account_member(account_id, user.id, &state.conn)
+                .await
+                .map(|member| {
+                    matches!(member, Some(member) if [AccountRole::Owner, AccountRole::Treasurer].contains(&member.role))
+                })
+        }
+        (ProposalKind::TreasuryPayment, None) => Ok(false),
+    };
+
+    res.map_err(ApiError::internal)
+}
@@ -0,0 +1,75 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    expire_proposals, find_proposals, get_or_create_economy_state, get_user_accounts,
+    ProposalVisibility,
+};
+use economy_service_entity::{account_member::AccountRole, proposal::ProposalStatus};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Query},
+    responses::ApiError,
+    AppState,
+};
+
+/// Query parameters of proposals fetch
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ProposalsQuery {
+    /// Only proposals with this status
+    status: Option<ProposalStatus>,
+
+    /// Maximum amount of proposals to return, 100 by default and 1000 at most
+    limit: Option<u64>,
+}
+
+/// Fetch proposals the caller made or may approve, newest first. Admins see all of them.
+#[utoipa::path(
+    get, path = "/proposals", tag = "Proposals",
+    params(ProposalsQuery),
+    responses(
+        (status = 200, body = [Proposal], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_proposals(
+    Query(query): Query<ProposalsQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    expire_proposals(&state.conn).await?;
+
+    let visible_to = if user.admin {
+        None
+    } else {
+        let banker = get_or_create_economy_state(user.id, &state.conn)
+            .await?
+            .banker;
+        let accounts = get_user_accounts(user.id, &state.conn).await?;
+
+        Some(ProposalVisibility {
+            user_id: user.id,
+            mints: banker,
+            account_ids: accounts
+                .into_iter()
+                .filter(|(member, _)| {
+                    [AccountRole::Owner, AccountRole::Treasurer].contains(&member.role)
+                })
+                .map(|(_, account)| account.id)
+                .collect(),
+        })
+    };
+
+    find_proposals(
+        query.status,
+        visible_to,
+        query.limit.unwrap_or(100).min(1000),
+        &state.conn,
+    )
+    .await
+    .map(Json)
+    .map_err(ApiError::internal)
+}
@@ -0,0 +1,111 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use economy_service_core::reconcile;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Query},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of reconciliation
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ReconciliationQuery {
+    /// Maximum amount of drifted accounts to return, 100 by default and 1000 at most
+    limit: Option<usize>,
+}
+
+/// Account whose balance doesn't match its recorded movements
+#[derive(Serialize, ToSchema)]
+pub(crate) struct DriftedAccount {
+    /// ID of the user, if the account is theirs
+    user_id: Option<i32>,
+
+    /// ID of the account, if it's a shared account
+    account_id: Option<i32>,
+
+    /// Stored balance
+    balance: i32,
+
+    /// Balance computed from the ledger
+    expected: i64,
+
+    /// Stored balance minus the expected one
+    drift: i64,
+}
+
+/// Comparison of balances with the money movements recorded in the ledger
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ReconciliationReport {
+    checked_at: DateTime<Utc>,
+
+    /// Amount of checked accounts
+    accounts: usize,
+
+    /// Sum of all balances
+    total_balance: i64,
+
+    /// Minted and imported money minus burned money
+    expected_total: i64,
+
+    /// Total balance minus the expected one. Positive if money was created outside of the ledger,
+    /// negative if it was destroyed.
+    drift: i64,
+
+    /// Amount of accounts with drift
+    drifted_account_count: usize,
+
+    /// Accounts with drift, ordered by user ID
+    drifted_accounts: Vec<DriftedAccount>,
+}
+
+/// Compare balances with the ledger and report drift. Admins only.
+#[utoipa::path(
+    get, path = "/reconciliation", tag = "Reconciliation",
+    params(ReconciliationQuery),
+    responses(
+        (status = 200, body = ReconciliationReport, description = "Successful reconciliation"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_reconciliation(
+    Query(query): Query<ReconciliationQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    let reconciliation = reconcile(&state.conn).await?;
+
+    Ok(Json(ReconciliationReport {
+        checked_at: reconciliation.checked_at,
+        accounts: reconciliation.accounts,
+        total_balance: reconciliation.total_balance,
+        expected_total: reconciliation.expected_total,
+        drift: reconciliation.drift,
+        drifted_account_count: reconciliation.drifted_accounts.len(),
+        drifted_accounts: reconciliation
+            .drifted_accounts
+            .into_iter()
+            .take(query.limit.unwrap_or(100).min(1000))
+            .map(|account| DriftedAccount {
+                user_id: account.holder.user_id(),
+                account_id: account.holder.account_id(),
+                balance: account.balance,
+                expected: account.expected,
+                drift: account.drift,
+            })
+            .collect(),
+    }))
+}
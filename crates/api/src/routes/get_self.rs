@@ -1,27 +1,28 @@
-use crate::{extractors::AuthenticatedUser, responses::AppError, AppState};
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use economy_service_core::get_or_create_economy_state;
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::{economy_state, ApiError},
+    AppState,
+};
+use axum::extract::State;
+use economy_service_client::models::EconomyState;
+use economy_service_core::{get_or_create_economy_state, get_outstanding_debt};
 
 /// Fetch your economy state data
 #[utoipa::path(
     get, path = "/me", tag = "Economy state",
     responses(
         (status = 200, body = EconomyState, description = "Successful fetch"),
-        (status = 401, body = AppError, description = "Authentication failed"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
     ),
     security(("api_key" = []))
 )]
 pub(crate) async fn get_self(
     AuthenticatedUser(user): AuthenticatedUser,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    get_or_create_economy_state(user.id, &state.conn)
-        .await
-        .map(Json)
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })
+) -> Result<Json<EconomyState>, ApiError> {
+    let user_state = get_or_create_economy_state(user.id, &state.conn).await?;
+    let debt = get_outstanding_debt(user.id, &state.conn).await?;
+
+    Ok(Json(economy_state(user_state, debt)))
 }
@@ -0,0 +1,51 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_user_accounts;
+use economy_service_entity::{account, account_member::AccountRole};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::ApiError,
+    AppState,
+};
+
+/// Shared account along with the caller's role in it
+#[derive(Serialize, ToSchema)]
+pub(crate) struct AccountMembership {
+    #[serde(flatten)]
+    account: account::Model,
+
+    /// Role of the caller in the account
+    role: AccountRole,
+}
+
+/// Fetch shared accounts the caller is a member of
+#[utoipa::path(
+    get, path = "/me/accounts", tag = "Accounts",
+    responses(
+        (status = 200, body = [AccountMembership], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_self_accounts(
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    get_user_accounts(user.id, &state.conn)
+        .await
+        .map(|accounts| {
+            Json(
+                accounts
+                    .into_iter()
+                    .map(|(member, account)| AccountMembership {
+                        account,
+                        role: member.role,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .map_err(ApiError::internal)
+}
@@ -0,0 +1,138 @@
+use async_stream::AsyncStream;
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Sse,
+    },
+};
+use economy_service_core::{
+    get_last_user_transaction_id, get_or_create_economy_state, get_user_transactions_after,
+};
+use economy_service_entity::transaction;
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    extractors::{AuthenticatedUser, Query},
+    responses::ApiError,
+    AppState,
+};
+
+/// Amount of missed transactions fetched from the database at once
+const REPLAY_BATCH_SIZE: u64 = 100;
+
+/// Query parameters of balance events stream
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct EventsQuery {
+    /// ID of the last received event. `Last-Event-ID` header takes precedence.
+    cursor: Option<i32>,
+}
+
+/// Transaction the user took part in, sent as `transaction` event
+#[derive(Serialize, ToSchema)]
+pub(crate) struct BalanceUpdate {
+    #[serde(flatten)]
+    transaction: transaction::Model,
+
+    /// Balance of the user after the transaction
+    balance: Option<i32>,
+}
+
+fn transaction_event(user_id: i32, transaction: transaction::Model) -> Event {
+    let balance = if transaction.payee_id == Some(user_id) {
+        transaction.payee_balance
+    } else {
+        transaction.payer_balance
+    };
+
+    Event::default()
+        .id(transaction.id.to_string())
+        .event("transaction")
+        .json_data(BalanceUpdate {
+            transaction,
+            balance,
+        })
+        .unwrap()
+}
+
+/// Stream your balance changes as Server-Sent Events.
+///
+/// Every transaction you take part in is sent as a `transaction` event with its ID as the event ID.
+/// Reconnecting with `Last-Event-ID` header (or `cursor` parameter) replays the missed transactions.
+/// Fresh connections start with a `balance` event holding your current balance.
+#[utoipa::path(
+    get, path = "/me/events", tag = "Economy state",
+    params(EventsQuery),
+    responses(
+        (status = 200, body = BalanceUpdate, content_type = "text/event-stream", description = "Stream of balance updates"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_self_events(
+    AuthenticatedUser(user): AuthenticatedUser,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cursor = headers
+        .get("last-event-id")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse().ok())
+        .or(query.cursor);
+
+    // subscribe before looking into the database, so nothing is committed unnoticed in between
+    let mut receiver = state.balance_events.subscribe().await?;
+
+    let stream = async_stream::try_stream! {
+        let mut last_id = match cursor {
+            Some(cursor) => cursor,
+            None => {
+                let last_id = get_last_user_transaction_id(user.id, &state.conn).await?;
+                let balance = get_or_create_economy_state(user.id, &state.conn).await?.balance;
+                yield Event::default()
+                    .event("balance")
+                    .json_data(json!({ "balance": balance }))
+                    .unwrap();
+                last_id
+            }
+        };
+
+        let mut replay = cursor.is_some();
+        loop {
+            // catch up from the database after reconnecting or falling behind the broadcast
+            while replay {
+                let missed =
+                    get_user_transactions_after(user.id, last_id, REPLAY_BATCH_SIZE, &state.conn)
+                        .await?;
+                replay = missed.len() as u64 == REPLAY_BATCH_SIZE;
+                for transaction in missed {
+                    last_id = transaction.id;
+                    yield transaction_event(user.id, transaction);
+                }
+            }
+
+            match receiver.recv().await {
+                Ok(transaction) => {
+                    let involved = transaction.payer_id == Some(user.id)
+                        || transaction.payee_id == Some(user.id);
+                    if involved && transaction.id > last_id {
+                        last_id = transaction.id;
+                        yield transaction_event(user.id, transaction);
+                    }
+                }
+                Err(RecvError::Lagged(_)) => replay = true,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::<AsyncStream<Result<Event, DbErr>, _>>::new(stream).keep_alive(KeepAlive::default()))
+}
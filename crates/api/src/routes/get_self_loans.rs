@@ -0,0 +1,28 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_user_loans;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::ApiError,
+    AppState,
+};
+
+/// Fetch loans you lent or borrowed, newest first
+#[utoipa::path(
+    get, path = "/me/loans", tag = "Loans",
+    responses(
+        (status = 200, body = [Loan], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_self_loans(
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    get_user_loans(user.id, &state.conn)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
@@ -0,0 +1,104 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    get_account_member, get_or_create_economy_state, get_reversals, get_transaction as find,
+};
+use economy_service_entity::transaction;
+use serde::Serialize;
+use users_service_client::User;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Transaction along with its reversals
+#[derive(Serialize, ToSchema)]
+pub(crate) struct TransactionDetails {
+    #[serde(flatten)]
+    pub(crate) transaction: transaction::Model,
+
+    /// Reversals of the payment, oldest first
+    pub(crate) reversals: Vec<transaction::Model>,
+}
+
+/// Fetch transaction by ID. Its participants, members of its accounts, bankers and admins only.
+#[utoipa::path(
+    get, path = "/transactions/{id}", tag = "Transactions",
+    params(
+        ("id" = String, Path, description = "Transaction ID")
+    ),
+    responses(
+        (status = 200, body = TransactionDetails, description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not allowed to see the transaction"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Transaction not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_transaction(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let transaction = find_transaction(&state, id).await?;
+
+    if !user.admin && !can_see_transaction(&state, &transaction, &user).await? {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Not allowed to see the transaction",
+        ));
+    }
+
+    let reversals = get_reversals(transaction.id, &state.conn).await?;
+
+    Ok(Json(TransactionDetails {
+        transaction,
+        reversals,
+    }))
+}
+
+/// Fetches the transaction or fails with 404 if there is none
+pub(crate) async fn find_transaction(
+    state: &AppState,
+    id: i32,
+) -> Result<transaction::Model, ApiError> {
+    find(id, &state.conn).await?.ok_or(ApiError::new(
+        ErrorCode::TransactionNotFound,
+        "Transaction not found",
+    ))
+}
+
+/// Whether the user took part in the transaction, is a member of one of its accounts or a banker
+async fn can_see_transaction(
+    state: &AppState,
+    transaction: &transaction::Model,
+    user: &User,
+) -> Result<bool, ApiError> {
+    if [transaction.payer_id, transaction.payee_id].contains(&Some(user.id)) {
+        return Ok(true);
+    }
+
+    let res = async {
+        for account_id in [transaction.payer_account_id, transaction.payee_account_id]
+            .into_iter()
+            .flatten()
+        {
+            if get_account_member(account_id, user.id, &state.conn)
+                .await?
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+
+        get_or_create_economy_state(user.id, &state.conn)
+            .await
+            .map(|state| state.banker)
+    }
+    .await;
+
+    res.map_err(ApiError::internal)
+}
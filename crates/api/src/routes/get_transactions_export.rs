@@ -0,0 +1,87 @@
+use axum::{body::StreamBody, extract::State, http::header, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use economy_service_core::{
+    export_transactions, get_or_create_economy_state, get_transactions_export_user_ids,
+    ExportFormat,
+};
+use futures::TryStreamExt;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Query},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of transactions export
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct TransactionsExportQuery {
+    /// Format of the export, `csv` by default
+    #[param(inline)]
+    format: Option<ExportFormat>,
+
+    /// Only transactions created at or after this time
+    from: Option<DateTime<Utc>>,
+
+    /// Only transactions created before this time
+    to: Option<DateTime<Utc>>,
+}
+
+/// Export transactions within a time range, oldest first. Bankers and admins only.
+#[utoipa::path(
+    get, path = "/export/transactions", tag = "Export",
+    params(TransactionsExportQuery),
+    responses(
+        (status = 200, body = String, content_type = ["text/csv", "application/x-ndjson"],
+            description = "Transactions with IDs and usernames of payers and payees"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing banker or admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_transactions_export(
+    Query(query): Query<TransactionsExportQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let is_banker = get_or_create_economy_state(user.id, &state.conn)
+        .await
+        .map(|state| state.banker)?;
+
+    if !user.admin && !is_banker {
+        return Err(ApiError::new(
+            ErrorCode::MissingBankerRole,
+            "Missing banker or admin role",
+        ));
+    }
+
+    // resolved before the response starts, so that the users service failing
+    // doesn't cut the export short
+    let user_ids = get_transactions_export_user_ids(query.from, query.to, &state.conn).await?;
+    let usernames = state
+        .users_client
+        .get_usernames(user_ids)
+        .await
+        .map_err(ApiError::upstream)?;
+
+    let format = query.format.unwrap_or_default();
+    let export = export_transactions(state.conn, usernames, format, query.from, query.to)
+        .inspect_err(|err| tracing::error!("transactions export failed: {}", err));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"transactions.{}\"",
+                    format.extension()
+                ),
+            ),
+        ],
+        StreamBody::new(export),
+    ))
+}
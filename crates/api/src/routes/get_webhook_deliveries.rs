@@ -0,0 +1,70 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{get_webhook, get_webhook_deliveries as find};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, Query},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of webhook deliveries fetch
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct WebhookDeliveriesQuery {
+    /// Maximum amount of deliveries to return, 100 by default and 1000 at most
+    limit: Option<u64>,
+
+    /// Amount of deliveries to skip
+    offset: Option<u64>,
+}
+
+/// Fetch delivery log of webhook, newest first. Admins only.
+#[utoipa::path(
+    get, path = "/webhooks/{id}/deliveries", tag = "Webhooks",
+    params(
+        ("id" = String, Path, description = "Webhook ID"),
+        WebhookDeliveriesQuery,
+    ),
+    responses(
+        (status = 200, body = [WebhookDelivery], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Webhook not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_webhook_deliveries(
+    Path(id): Path<i32>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    let webhook = get_webhook(id, &state.conn).await?;
+
+    if webhook.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::WebhookNotFound,
+            "Webhook not found",
+        ));
+    }
+
+    find(
+        id,
+        query.limit.unwrap_or(100).min(1000),
+        query.offset.unwrap_or(0),
+        &state.conn,
+    )
+    .await
+    .map(Json)
+    .map_err(ApiError::internal)
+}
@@ -0,0 +1,36 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::get_webhooks as find;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Fetch all webhooks. Admins only.
+#[utoipa::path(
+    get, path = "/webhooks", tag = "Webhooks",
+    responses(
+        (status = 200, body = [Webhook], description = "Successful fetch"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn get_webhooks(
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    find(&state.conn)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
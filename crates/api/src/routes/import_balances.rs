@@ -0,0 +1,113 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use economy_service_core::AuditEntryForm;
+use economy_service_entity::audit_log_entry::AuditAction;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Query, RequestContext},
+    import::{run_import, ImportFormat},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of balances import
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ImportQuery {
+    /// Only validate the data and report problems, without applying it
+    #[serde(default)]
+    dry_run: bool,
+
+    /// Reason recorded in the audit log
+    reason: Option<String>,
+}
+
+/// Import opening balances of users, e.g. when migrating from another economy. Admins only.
+///
+/// Every row is validated first: users must exist, appear once and hold no money yet.
+/// The whole import is applied atomically, and only if no problems were found.
+#[utoipa::path(
+    post, path = "/import", tag = "Import",
+    params(ImportQuery),
+    request_body(
+        content = String, content_type = "text/csv",
+        description = "CSV with `user_id`, `balance` and optional `banker` columns, \
+            or JSON array of objects with the same fields sent as `application/json`"
+    ),
+    responses(
+        (status = 200, body = ImportReport, description = "Import applied or validated in a dry run"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Malformed JSON"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 415, body = AppError, content_type = "application/problem+json", description = "Neither CSV nor JSON body"),
+        (status = 422, body = ImportReport, description = "Problems found, nothing applied"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn import_balances(
+    Query(query): Query<ImportQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    let format = match content_type {
+        Some("text/csv") => ImportFormat::Csv,
+        Some("application/json") => ImportFormat::Json,
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnsupportedMediaType,
+                "Expected text/csv or application/json request body",
+            ))
+        }
+    };
+
+    let audit_entry = ctx.audit_entry(user.id, AuditAction::Import, String::from("import"));
+    let report = run_import(
+        &body,
+        format,
+        query.dry_run,
+        AuditEntryForm {
+            reason: query.reason,
+            ..audit_entry
+        },
+        &state.users_client,
+        &state.conn,
+    )
+    .await
+    .map_err(|err| {
+        if err.is::<serde_json::Error>() {
+            return ApiError::new(ErrorCode::ValidationFailed, err.to_string());
+        }
+        match err.downcast::<reqwest::Error>() {
+            Ok(err) => ApiError::upstream(*err),
+            Err(err) => ApiError::internal(err),
+        }
+    })?;
+
+    let status = match report.problems.is_empty() {
+        true => StatusCode::OK,
+        false => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    Ok((status, Json(report)).into_response())
+}
@@ -1,9 +1,95 @@
+mod accept_loan;
 mod add_money;
+mod approve_proposal;
+mod batch_pay;
+mod create_account;
+mod create_api_key;
+mod create_webhook;
+mod decline_loan;
+mod delete_webhook;
+mod freeze;
+mod get_account;
+mod get_account_limits;
+mod get_account_members;
+mod get_account_transactions;
+mod get_api_keys;
+mod get_audit_log;
+mod get_balances_export;
 mod get_by_id;
+mod get_by_username;
+mod get_limits;
+mod get_loan;
+mod get_proposal;
+mod get_proposals;
+mod get_reconciliation;
 mod get_self;
+mod get_self_accounts;
+mod get_self_events;
+mod get_self_loans;
+mod get_transaction;
+mod get_transactions_export;
+mod get_webhook_deliveries;
+mod get_webhooks;
+mod import_balances;
+mod offer_loan;
 mod pay;
+mod pay_account;
+mod pay_by_username;
+mod refund_payment;
+mod reject_proposal;
+mod remove_account_member;
+mod reverse_payment;
+mod revoke_api_key;
+mod send_from_account;
+mod set_account_limit;
+mod set_account_member;
+mod set_limits;
+mod unfreeze;
 
+pub(crate) use accept_loan::*;
 pub(crate) use add_money::*;
+pub(crate) use approve_proposal::*;
+pub(crate) use batch_pay::*;
+pub(crate) use create_account::*;
+pub(crate) use create_api_key::*;
+pub(crate) use create_webhook::*;
+pub(crate) use decline_loan::*;
+pub(crate) use delete_webhook::*;
+pub(crate) use freeze::*;
+pub(crate) use get_account::*;
+pub(crate) use get_account_limits::*;
+pub(crate) use get_account_members::*;
+pub(crate) use get_account_transactions::*;
+pub(crate) use get_api_keys::*;
+pub(crate) use get_audit_log::*;
+pub(crate) use get_balances_export::*;
 pub(crate) use get_by_id::*;
+pub(crate) use get_by_username::*;
+pub(crate) use get_limits::*;
+pub(crate) use get_loan::*;
+pub(crate) use get_proposal::*;
+pub(crate) use get_proposals::*;
+pub(crate) use get_reconciliation::*;
 pub(crate) use get_self::*;
+pub(crate) use get_self_accounts::*;
+pub(crate) use get_self_events::*;
+pub(crate) use get_self_loans::*;
+pub(crate) use get_transaction::*;
+pub(crate) use get_transactions_export::*;
+pub(crate) use get_webhook_deliveries::*;
+pub(crate) use get_webhooks::*;
+pub(crate) use import_balances::*;
+pub(crate) use offer_loan::*;
 pub(crate) use pay::*;
+pub(crate) use pay_account::*;
+pub(crate) use pay_by_username::*;
+pub(crate) use refund_payment::*;
+pub(crate) use reject_proposal::*;
+pub(crate) use remove_account_member::*;
+pub(crate) use reverse_payment::*;
+pub(crate) use revoke_api_key::*;
+pub(crate) use send_from_account::*;
+pub(crate) use set_account_limit::*;
+pub(crate) use set_account_member::*;
+pub(crate) use set_limits::*;
+pub(crate) use unfreeze::*;
@@ -0,0 +1,127 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+pub(crate) use economy_service_client::models::DataOfferLoan;
+use economy_service_core::{
+    get_active_freeze, get_or_create_economy_state, loan_total_due, offer_loan as offer,
+    OfferLoanForm,
+};
+
+use crate::{
+    extractors::{AuthenticatedUser, Json},
+    responses::{ApiError, AppError, ErrorCode},
+    routes::check_payee_user,
+    AppState,
+};
+
+/// Highest interest rate of a loan in basis points
+const MAX_INTEREST_RATE_BPS: i32 = 10_000;
+
+/// Most installments a loan can be repaid in
+const MAX_INSTALLMENTS: i32 = 365;
+
+/// Longest interval between two installments in hours
+const MAX_INTERVAL_HOURS: i32 = 24 * 365;
+
+/// Offer a loan to another player. The principal is taken from your balance once the borrower
+/// accepts it, and installments are collected from the borrower's balance on schedule.
+#[utoipa::path(
+    post, path = "/loans", tag = "Loans", request_body = DataOfferLoan,
+    responses(
+        (status = 201, body = Loan, description = "Successful offer"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid amount or terms or borrower or insufficient funds or transfer limit exceeded"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Lender or borrower account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Borrower not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn offer_loan(
+    AuthenticatedUser(lender): AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(data): Json<DataOfferLoan>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if data.principal <= 0 {
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            "Principal should be more than 0",
+        ));
+    }
+
+    if !(0..=MAX_INTEREST_RATE_BPS).contains(&data.interest_rate_bps) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            format!(
+                "Interest rate should be between 0 and {} basis points",
+                MAX_INTEREST_RATE_BPS
+            ),
+        ));
+    }
+
+    if !(1..=MAX_INSTALLMENTS).contains(&data.installments) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            format!("Installments should be between 1 and {}", MAX_INSTALLMENTS),
+        ));
+    }
+
+    if !(1..=MAX_INTERVAL_HOURS).contains(&data.interval_hours) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            format!(
+                "Interval should be between 1 and {} hours",
+                MAX_INTERVAL_HOURS
+            ),
+        ));
+    }
+
+    if loan_total_due(data.principal, data.interest_rate_bps).is_none() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            "Principal with interest is too large",
+        ));
+    }
+
+    if data.borrower_id == lender.id {
+        return Err(ApiError::new(
+            ErrorCode::SelfPayment,
+            "Cannot lend money to yourself",
+        ));
+    }
+
+    check_payee_user(&state, data.borrower_id).await?;
+
+    // check whether the lender could pay the principal out right now
+    let lender_freeze = get_active_freeze(lender.id, &state.conn).await?;
+    if lender_freeze.is_some() {
+        return Err(ApiError::new(
+            ErrorCode::AccountFrozen,
+            "Your account is frozen",
+        ));
+    }
+
+    let lender_state = get_or_create_economy_state(lender.id, &state.conn).await?;
+    if lender_state.balance < data.principal {
+        return Err(
+            AppError::new(ErrorCode::InsufficientFunds, "Insufficient funds")
+                .with_available(lender_state.balance as i64)
+                .into(),
+        );
+    }
+
+    let loan = offer(
+        OfferLoanForm {
+            lender_id: lender.id,
+            borrower_id: data.borrower_id,
+            principal: data.principal,
+            interest_rate_bps: data.interest_rate_bps,
+            installments: data.installments,
+            interval_hours: data.interval_hours,
+            comment: data.comment,
+        },
+        Some(state.transfer_limits),
+        &state.conn,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(loan)))
+}
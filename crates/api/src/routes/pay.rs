@@ -1,42 +1,28 @@
-use crate::{extractors::AuthenticatedUser, responses::AppError, AppState};
-use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    AppState,
 };
-use economy_service_core::{
-    get_or_create_economy_state, update_economy_state, UpdateEconomyStateForm,
-};
-use economy_service_entity::economy_state;
-use serde::Deserialize;
-use users_service_client::GetUserResponse;
-use utoipa::ToSchema;
-
-/// Data used in pay operation
-#[derive(Deserialize, ToSchema)]
-pub(crate) struct DataPay {
-    /// Amount of money to pay
-    amount: i32,
-
-    /// Comment that will be shown to payee
-    /// Currently unused
-    #[allow(dead_code)]
-    comment: Option<String>,
-}
+use axum::{extract::State, http::StatusCode};
+pub(crate) use economy_service_client::models::DataPay;
+use economy_service_core::{get_active_freeze, LedgerParty, PaymentForm};
+use economy_service_entity::transaction;
+use users_service_client::{GetUserResponse, User};
 
 /// Pay money to other player
 #[utoipa::path(
-    put, path = "/{id}/pay", tag = "Economy state",
+    put, path = "/users/{id}/pay", tag = "Economy state",
     request_body = DataPay,
     params(
         ("id" = String, Path, description = "Payee user ID")
     ),
     responses(
         (status = 204, description = "Successful payment"),
-        (status = 400, body = AppError, description = "Validation failed: invalid amount or payee is self or insufficient funds"),
-        (status = 401, body = AppError, description = "Authentication failed"),
-        (status = 404, body = AppError, description = "User not found"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid amount or payee is self or insufficient funds or transfer limit exceeded"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Payer or payee account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
     ),
     security(("api_key" = []))
 )]
@@ -45,100 +31,57 @@ pub(crate) async fn pay(
     AuthenticatedUser(payer_user): AuthenticatedUser,
     Path(payee_id): Path<i32>,
     Json(data): Json<DataPay>,
-) -> Result<impl IntoResponse, impl IntoResponse> {
-    // validate amount
-    if data.amount <= 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(AppError::new("Amount should be more than 0")),
-        ));
-    }
+) -> Result<StatusCode, ApiError> {
+    make_payment(&state, &payer_user, LedgerParty::User(payee_id), data).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    // check whether payer is not payee
-    if payer_user.id == payee_id {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(AppError::new("Cannot pay to yourself")),
-        ));
+/// Pays money from the user to the payee user or account once the payee user is checked
+pub(crate) async fn make_payment(
+    state: &AppState,
+    payer_user: &User,
+    payee: LedgerParty,
+    data: DataPay,
+) -> Result<transaction::Model, ApiError> {
+    if let LedgerParty::User(payee_id) = payee {
+        if payee_id != payer_user.id {
+            check_payee_user(state, payee_id).await?;
+        }
     }
 
+    let form = PaymentForm {
+        payer_id: payer_user.id,
+        payee,
+        amount: data.amount,
+        comment: data.comment,
+    };
+    Ok(economy_service_core::make_payment(form, state.transfer_limits, &state.conn).await?)
+}
+
+/// Checks whether the user exists and can receive money
+pub(crate) async fn check_payee_user(state: &AppState, payee_id: i32) -> Result<(), ApiError> {
     // fetch payee (just to check whether they exist or not)
-    match state.users_client.get_user(payee_id).await.map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })? {
+    match state
+        .users_client
+        .get_user(payee_id)
+        .await
+        .map_err(ApiError::upstream)?
+    {
         GetUserResponse::Ok(_) => (),
         GetUserResponse::NotFound => {
-            return Err((StatusCode::NOT_FOUND, Json(AppError::new("User not found"))))
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
         }
         _ => unreachable!(),
     };
 
-    // get payer state
-    let payer_state = get_or_create_economy_state(payer_user.id, &state.conn)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })?;
-
-    // check whether payer has enough money
-    if payer_state.balance < data.amount {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(AppError::new("Insufficient funds")),
+    // check whether payee can receive money
+    let payee_freeze = get_active_freeze(payee_id, &state.conn).await?;
+    if matches!(payee_freeze, Some(freeze) if freeze.block_incoming) {
+        return Err(ApiError::new(
+            ErrorCode::PayeeFrozen,
+            "Payee account is frozen",
         ));
     }
 
-    // get payer state
-    let payee_state = get_or_create_economy_state(payee_id, &state.conn)
-        .await
-        .map_err(|err| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AppError::new(err.to_string())),
-            )
-        })?;
-
-    // Update payer balance
-    let payer_state: economy_state::ActiveModel = payer_state.into();
-    update_economy_state(
-        payer_state.to_owned(),
-        UpdateEconomyStateForm {
-            balance: Some(payer_state.balance.unwrap() - data.amount),
-            ..Default::default()
-        },
-        &state.conn,
-    )
-    .await
-    .map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })?;
-
-    // Update payee balance
-    let payee_state: economy_state::ActiveModel = payee_state.into();
-    update_economy_state(
-        payee_state.to_owned(),
-        UpdateEconomyStateForm {
-            balance: Some(payee_state.balance.unwrap() + data.amount),
-            ..Default::default()
-        },
-        &state.conn,
-    )
-    .await
-    .map_err(|err| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AppError::new(err.to_string())),
-        )
-    })?;
-
-    Ok(StatusCode::NO_CONTENT)
+    Ok(())
 }
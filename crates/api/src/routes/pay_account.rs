@@ -0,0 +1,36 @@
+use axum::{extract::State, http::StatusCode};
+use economy_service_core::LedgerParty;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::ApiError,
+    routes::{make_payment, DataPay},
+    AppState,
+};
+
+/// Pay money to shared account
+#[utoipa::path(
+    put, path = "/accounts/{id}/pay", tag = "Accounts",
+    request_body = DataPay,
+    params(
+        ("id" = String, Path, description = "Payee account ID")
+    ),
+    responses(
+        (status = 204, description = "Successful payment"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid amount or insufficient funds or transfer limit exceeded"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Payer account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn pay_account(
+    State(state): State<AppState>,
+    AuthenticatedUser(payer_user): AuthenticatedUser,
+    Path(account_id): Path<i32>,
+    Json(data): Json<DataPay>,
+) -> Result<StatusCode, ApiError> {
+    make_payment(&state, &payer_user, LedgerParty::Account(account_id), data).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
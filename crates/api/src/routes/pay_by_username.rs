@@ -0,0 +1,37 @@
+use axum::{extract::State, http::StatusCode};
+use economy_service_core::LedgerParty;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::ApiError,
+    routes::{find_user_by_username, make_payment, DataPay},
+    AppState,
+};
+
+/// Pay money to other player, addressed by their username ignoring case
+#[utoipa::path(
+    put, path = "/users/by-username/{username}/pay", tag = "Economy state",
+    request_body = DataPay,
+    params(
+        ("username" = String, Path, description = "Payee username")
+    ),
+    responses(
+        (status = 204, description = "Successful payment"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid username or amount or payee is self or insufficient funds or transfer limit exceeded"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Payer or payee account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn pay_by_username(
+    State(state): State<AppState>,
+    AuthenticatedUser(payer_user): AuthenticatedUser,
+    Path(username): Path<String>,
+    Json(data): Json<DataPay>,
+) -> Result<StatusCode, ApiError> {
+    let payee = find_user_by_username(&state, &username).await?;
+    make_payment(&state, &payer_user, LedgerParty::User(payee.id), data).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
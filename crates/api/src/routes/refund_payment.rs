@@ -0,0 +1,88 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{reverse, ReverseForm, SPENDING_ROLES};
+use economy_service_entity::transaction;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path},
+    responses::{ApiError, ErrorCode},
+    routes::{find_transaction, require_account_role},
+    AppState,
+};
+
+/// Data used in refund and reverse operations
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataReverse {
+    /// Amount of money to move back. The whole unreversed part of the payment if omitted.
+    pub(crate) amount: Option<i32>,
+
+    /// Comment that will be shown to the payer of the payment
+    pub(crate) comment: Option<String>,
+}
+
+/// Refund payment, fully or partially, to its payer. Payee of the payment only,
+/// or owners, managers and treasurers of the payee account.
+#[utoipa::path(
+    post, path = "/transactions/{id}/refund", tag = "Transactions",
+    request_body = DataReverse,
+    params(
+        ("id" = String, Path, description = "Payment ID")
+    ),
+    responses(
+        (status = 200, body = Transaction, description = "Successful refund"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: not a payment or invalid amount or insufficient funds"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not the payee of the payment or caller account is frozen"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Transaction not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Payment is already fully reversed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn refund_payment(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(data): Json<DataReverse>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let payment = find_transaction(&state, id).await?;
+
+    match (payment.payee_id, payment.payee_account_id) {
+        (Some(payee_id), _) if payee_id == user.id => (),
+        (_, Some(account_id)) => {
+            require_account_role(&state, account_id, user.id, &SPENDING_ROLES).await?;
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::NotAllowed,
+                "Not the payee of the payment",
+            ))
+        }
+    }
+
+    let record = make_reversal(&payment, user.id, data, false, &state.conn).await?;
+
+    Ok(Json(record))
+}
+
+/// Makes the reversal of the payment on behalf of the initiator.
+/// Forced reversals take whatever the payee has if it's less than the amount, regardless of freezes.
+pub(crate) async fn make_reversal<C: ConnectionTrait + TransactionTrait>(
+    payment: &transaction::Model,
+    initiator_id: i32,
+    data: DataReverse,
+    force: bool,
+    conn: &C,
+) -> Result<transaction::Model, ApiError> {
+    let form = ReverseForm {
+        amount: data.amount,
+        force,
+        comment: data.comment,
+        initiator_id: Some(initiator_id),
+        check_freezes: !force,
+    };
+
+    reverse(payment, form, conn).await.map_err(ApiError::from)
+}
@@ -0,0 +1,84 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{claim_proposal, record_audit_entry, AuditEntryForm};
+use economy_service_entity::{audit_log_entry::AuditAction, proposal::ProposalStatus};
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{can_approve, check_pending, find_proposal, proposal_details},
+    AppState,
+};
+
+/// Data used in reject proposal operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataRejectProposal {
+    /// Reason recorded in the audit log
+    reason: Option<String>,
+}
+
+/// Reject proposal, or withdraw it if it's your own. Proposer and approvers only.
+#[utoipa::path(
+    post, path = "/proposals/{id}/reject", tag = "Proposals",
+    request_body = DataRejectProposal,
+    params(
+        ("id" = String, Path, description = "Proposal ID")
+    ),
+    responses(
+        (status = 200, body = ProposalDetails, description = "Successful rejection"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Not allowed to reject the proposal"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Proposal not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Proposal isn't pending"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn reject_proposal(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataRejectProposal>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let proposal = find_proposal(&state, id).await?;
+    check_pending(&proposal)?;
+
+    if user.id != proposal.proposer_id && !can_approve(&state, &proposal, &user).await? {
+        return Err(ApiError::new(
+            ErrorCode::NotAllowed,
+            "Not allowed to reject the proposal",
+        ));
+    }
+
+    // reject and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let claimed = claim_proposal(id, ProposalStatus::Rejected, Some(user.id), &txn).await?;
+    if !claimed {
+        return Err(ApiError::new(
+            ErrorCode::ProposalNotPending,
+            "Proposal is not pending anymore",
+        ));
+    }
+
+    record_audit_entry(
+        AuditEntryForm {
+            reason: data.reason,
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::RejectProposal,
+                format!("proposal:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    let proposal = find_proposal(&state, id).await?;
+    proposal_details(&state, proposal).await.map(Json)
+}
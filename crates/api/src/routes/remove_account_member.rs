@@ -0,0 +1,68 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{
+    get_account_member, record_audit_entry, remove_account_member as remove, AuditEntryForm,
+};
+use economy_service_entity::{account_member::AccountRole, audit_log_entry::AuditAction};
+use sea_orm::TransactionTrait;
+
+use crate::{
+    extractors::{AuthenticatedUser, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{find_account, require_account_role},
+    AppState,
+};
+
+/// Remove member from shared account. Owners only, although members can leave by themselves.
+#[utoipa::path(
+    delete, path = "/accounts/{id}/members/{user_id}", tag = "Accounts",
+    params(
+        ("id" = String, Path, description = "Account ID"),
+        ("user_id" = String, Path, description = "Member user ID"),
+    ),
+    responses(
+        (status = 204, description = "Successful removal"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: account would be left without owners"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing account owner role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account or member not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn remove_account_member(
+    Path(path): Path<(i32, i32)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let (id, user_id) = path;
+    find_account(&state, id).await?;
+    if user.id != user_id {
+        require_account_role(&state, id, user.id, &[AccountRole::Owner]).await?;
+    }
+
+    let txn = state.conn.begin().await?;
+
+    let member = match get_account_member(id, user_id, &txn).await? {
+        Some(member) => member,
+        None => return Err(ApiError::new(ErrorCode::MemberNotFound, "Member not found")),
+    };
+    remove(id, user_id, &txn).await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(serde_json::to_value(&member).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::RemoveAccountMember,
+                format!("account:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,80 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{get_or_create_economy_state, record_audit_entry, AuditEntryForm};
+use economy_service_entity::audit_log_entry::AuditAction;
+use sea_orm::TransactionTrait;
+use serde_json::json;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{find_transaction, make_reversal, DataReverse},
+    AppState,
+};
+
+/// Reverse payment, fully or partially, even without consent of its payee. Bankers only.
+/// If the payee doesn't have enough money, whatever they have is taken back.
+#[utoipa::path(
+    post, path = "/transactions/{id}/reverse", tag = "Transactions",
+    request_body = DataReverse,
+    params(
+        ("id" = String, Path, description = "Payment ID")
+    ),
+    responses(
+        (status = 200, body = Transaction, description = "Successful reversal"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: not a payment or invalid amount or payee has no money"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing banker role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Transaction not found"),
+        (status = 409, body = AppError, content_type = "application/problem+json", description = "Payment is already fully reversed"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn reverse_payment(
+    Path(id): Path<i32>,
+    AuthenticatedUser(banker): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataReverse>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let is_banker = get_or_create_economy_state(banker.id, &state.conn)
+        .await
+        .map(|state| state.banker)?;
+
+    if !is_banker {
+        return Err(ApiError::new(
+            ErrorCode::MissingBankerRole,
+            "Missing banker role",
+        ));
+    }
+
+    let payment = find_transaction(&state, id).await?;
+
+    // reverse and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let reason = data.comment.clone();
+    let record = make_reversal(&payment, banker.id, data, true, &txn).await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(json!({ "reversed_amount": payment.reversed_amount })),
+            after: Some(json!({
+                "reversed_amount": payment.reversed_amount + record.amount,
+                "reversal_id": record.id,
+            })),
+            reason,
+            ..ctx.audit_entry(
+                banker.id,
+                AuditAction::Reverse,
+                format!("transaction:{}", payment.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(record))
+}
@@ -0,0 +1,72 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{record_audit_entry, revoke_api_key as revoke, AuditEntryForm};
+use economy_service_entity::audit_log_entry::AuditAction;
+use sea_orm::TransactionTrait;
+
+use crate::{
+    extractors::{AuthenticatedUser, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Revoke API key, so that it's rejected from now on. Admins only.
+#[utoipa::path(
+    delete, path = "/api-keys/{id}", tag = "API keys",
+    params(
+        ("id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 204, description = "Successful revocation"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "API key not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn revoke_api_key(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    // revoke and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let api_key = match revoke(id, &txn).await? {
+        Some(api_key) => api_key,
+        None => {
+            return Err(ApiError::new(
+                ErrorCode::ApiKeyNotFound,
+                "API key not found",
+            ))
+        }
+    };
+
+    // revoking an already revoked key changes nothing, so it isn't recorded again
+    if api_key.revoked_at.is_none() {
+        record_audit_entry(
+            AuditEntryForm {
+                before: Some(serde_json::to_value(&api_key).map_err(ApiError::internal)?),
+                ..ctx.audit_entry(
+                    user.id,
+                    AuditAction::RevokeApiKey,
+                    format!("api_key:{}", id),
+                )
+            },
+            &txn,
+        )
+        .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,173 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use economy_service_core::{
+    propose, transfer, Approval, EconomyError, LedgerParty, TransferForm, SPENDING_ROLES,
+};
+use economy_service_entity::{proposal::ProposalKind, transaction};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api_keys::{require_scope, Scope},
+    extractors::{Actor, Caller, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{check_payee_user, find_account, require_account_role},
+    AppState,
+};
+
+/// Data used in send from account operation. Exactly one payee should be given.
+#[derive(Deserialize, Serialize, ToSchema)]
+pub(crate) struct DataSendFromAccount {
+    /// ID of the payee user
+    pub(crate) payee_id: Option<i32>,
+
+    /// ID of the payee account
+    pub(crate) payee_account_id: Option<i32>,
+
+    /// Amount of money to pay
+    pub(crate) amount: i32,
+
+    /// Comment that will be shown to payee
+    pub(crate) comment: Option<String>,
+}
+
+/// Pay money from shared account to a user or another account. Owners, managers and treasurers only.
+/// Payments from organization accounts over the configured threshold are proposed for approval
+/// by other owners and treasurers instead.
+/// API keys with the `pay:from:{id}` scope pay within the limits of treasurers and freezes of their creator,
+/// but can't propose payments.
+#[utoipa::path(
+    put, path = "/accounts/{id}/send", tag = "Accounts",
+    request_body = DataSendFromAccount,
+    params(
+        ("id" = String, Path, description = "Payer account ID")
+    ),
+    responses(
+        (status = 204, description = "Successful payment"),
+        (status = 202, body = Proposal, description = "Payment awaits approval"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: invalid amount or payee or insufficient funds or role or transfer limit exceeded"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing account owner, manager or treasurer role or API key scope or caller or payee account is frozen or payment requires approval"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account or payee not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []), ("service_key" = []))
+)]
+pub(crate) async fn send_from_account(
+    State(state): State<AppState>,
+    caller: Caller,
+    ctx: RequestContext,
+    Path(account_id): Path<i32>,
+    Json(data): Json<DataSendFromAccount>,
+) -> Result<Response, ApiError> {
+    if let Caller::ApiKey(key) = &caller {
+        require_scope(key, Scope::PayFrom(account_id))?;
+    }
+
+    match spend_from_account(&state, caller.actor(), account_id, data, ctx, None).await? {
+        Approval::Executed(_) => Ok(StatusCode::NO_CONTENT.into_response()),
+        Approval::Proposed(proposal) => Ok((StatusCode::ACCEPTED, Json(proposal)).into_response()),
+    }
+}
+
+/// Pays money from the account on behalf of its member, holding them to their role, its limits,
+/// their own transfer limits, freezes and funds, unless it requires approval and isn't the execution of approved `proposal_id`.
+/// API keys are checked for their scope beforehand, and are held to limits of treasurers instead of a role.
+pub(crate) async fn spend_from_account(
+    state: &AppState,
+    actor: Actor,
+    account_id: i32,
+    data: DataSendFromAccount,
+    ctx: RequestContext,
+    proposal_id: Option<i32>,
+) -> Result<Approval<transaction::Model>, ApiError> {
+    // validate amount
+    if data.amount <= 0 {
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            "Amount should be more than 0",
+        ));
+    }
+
+    find_account(state, account_id).await?;
+    if let Actor::User(initiator_id) = actor {
+        require_account_role(state, account_id, initiator_id, &SPENDING_ROLES).await?;
+    }
+
+    let payee = match (data.payee_id, data.payee_account_id) {
+        (Some(payee_id), None) => {
+            check_payee_user(state, payee_id).await?;
+            LedgerParty::User(payee_id)
+        }
+        (None, Some(payee_account_id)) => {
+            if payee_account_id == account_id {
+                return Err(ApiError::new(
+                    ErrorCode::SelfPayment,
+                    "Cannot pay to the same account",
+                ));
+            }
+            find_account(state, payee_account_id).await?;
+            LedgerParty::Account(payee_account_id)
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::ValidationFailed,
+                "Either payee_id or payee_account_id should be given",
+            ))
+        }
+    };
+
+    // move money; the role, freezes, funds and the threshold are checked with the account locked,
+    // as they could have changed while this request was processed
+    let form = TransferForm {
+        payer: LedgerParty::Account(account_id),
+        payee,
+        amount: data.amount,
+        comment: data.comment.clone(),
+        initiator_id: match actor {
+            Actor::User(id) => Some(id),
+            Actor::ApiKey { .. } => None,
+        },
+        api_key_id: actor.api_key_id(),
+        limits: match actor {
+            Actor::User(_) => Some(state.transfer_limits),
+            Actor::ApiKey { .. } => None,
+        },
+        approvals: proposal_id.is_none().then_some(state.approvals),
+        check_freezes: true,
+    };
+    let record = match transfer(form, &state.conn).await {
+        Ok(record) => record,
+        Err(EconomyError::ApprovalRequired) => {
+            let proposer_id = match actor {
+                Actor::User(id) => id,
+                Actor::ApiKey { .. } => {
+                    return Err(ApiError::new(
+                        ErrorCode::ApprovalRequired,
+                        "Payment requires approval, which can't be proposed with an API key",
+                    ))
+                }
+            };
+
+            let payload = serde_json::to_value(&data).map_err(ApiError::internal)?;
+            return propose(
+                &state.approvals,
+                ProposalKind::TreasuryPayment,
+                proposer_id,
+                Some(account_id),
+                payload,
+                &ctx.audit(),
+                &state.conn,
+            )
+            .await
+            .map(Approval::Proposed)
+            .map_err(ApiError::from);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Approval::Executed(record))
+}
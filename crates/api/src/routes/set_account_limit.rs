@@ -0,0 +1,103 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    get_account_role_limits, record_audit_entry, set_account_role_limit, AuditEntryForm,
+};
+use economy_service_entity::{account_member::AccountRole, audit_log_entry::AuditAction};
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{find_account, require_account_role},
+    AppState,
+};
+
+/// Data used in set account limit operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataSetAccountLimit {
+    /// Maximum single payment, null for unlimited
+    per_transaction: Option<i32>,
+
+    /// Maximum spent by a single member per UTC day, null for unlimited
+    daily: Option<i32>,
+}
+
+/// Set spending limits of a role in shared account. Owners only.
+#[utoipa::path(
+    put, path = "/accounts/{id}/limits/{role}", tag = "Accounts",
+    request_body = DataSetAccountLimit,
+    params(
+        ("id" = String, Path, description = "Account ID"),
+        ("role" = AccountRole, Path, description = "Limited role"),
+    ),
+    responses(
+        (status = 200, body = AccountRoleLimit, description = "Successful update"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: owners can't be limited or negative limit"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing account owner role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn set_account_limit(
+    Path(path): Path<(i32, AccountRole)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataSetAccountLimit>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let (id, role) = path;
+
+    find_account(&state, id).await?;
+    require_account_role(&state, id, user.id, &[AccountRole::Owner]).await?;
+
+    if role == AccountRole::Owner {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Owners cannot be limited",
+        ));
+    }
+
+    if [data.per_transaction, data.daily]
+        .into_iter()
+        .flatten()
+        .any(|limit| limit < 0)
+    {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Limits should not be negative",
+        ));
+    }
+
+    let txn = state.conn.begin().await?;
+
+    let before = get_account_role_limits(id, &txn)
+        .await?
+        .into_iter()
+        .find(|limit| limit.role == role);
+    let limit = set_account_role_limit(id, role, data.per_transaction, data.daily, &txn).await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: before
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(ApiError::internal)?,
+            after: Some(serde_json::to_value(&limit).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::SetAccountLimit,
+                format!("account:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(limit))
+}
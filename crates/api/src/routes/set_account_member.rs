@@ -0,0 +1,118 @@
+use axum::{extract::State, response::IntoResponse};
+use economy_service_core::{
+    get_account_member, record_audit_entry, set_account_member as set, AuditEntryForm,
+};
+use economy_service_entity::{
+    account::AccountKind, account_member::AccountRole, audit_log_entry::AuditAction,
+};
+use sea_orm::{ActiveEnum, TransactionTrait};
+use serde::Deserialize;
+use users_service_client::GetUserResponse;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    routes::{find_account, require_account_role},
+    AppState,
+};
+
+/// Data used in set account member operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataSetAccountMember {
+    /// Role of the member
+    role: AccountRole,
+}
+
+/// Roles available in accounts of the kind
+fn roles_of(kind: AccountKind) -> &'static [AccountRole] {
+    match kind {
+        AccountKind::Shop | AccountKind::Company => &[AccountRole::Owner, AccountRole::Manager],
+        AccountKind::Organization => &[
+            AccountRole::Owner,
+            AccountRole::Treasurer,
+            AccountRole::Member,
+        ],
+    }
+}
+
+/// Add member to shared account or change their role. Owners only.
+/// Shops and companies have managers, while organizations have treasurers and members.
+#[utoipa::path(
+    put, path = "/accounts/{id}/members/{user_id}", tag = "Accounts",
+    request_body = DataSetAccountMember,
+    params(
+        ("id" = String, Path, description = "Account ID"),
+        ("user_id" = String, Path, description = "Member user ID"),
+    ),
+    responses(
+        (status = 200, body = AccountMember, description = "Successful update"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: role isn't available for the account kind or account would be left without owners"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing account owner role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account or user not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn set_account_member(
+    Path(path): Path<(i32, i32)>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataSetAccountMember>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    let (id, user_id) = path;
+    let account = find_account(&state, id).await?;
+    require_account_role(&state, id, user.id, &[AccountRole::Owner]).await?;
+
+    if !roles_of(account.kind).contains(&data.role) {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            format!(
+                "Role is not available for {} accounts",
+                account.kind.to_value()
+            ),
+        ));
+    }
+
+    // fetch member (just to check whether they exist or not)
+    match state
+        .users_client
+        .get_user(user_id)
+        .await
+        .map_err(ApiError::upstream)?
+    {
+        GetUserResponse::Ok(_) => (),
+        GetUserResponse::NotFound => {
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
+        }
+        _ => unreachable!(),
+    };
+
+    let txn = state.conn.begin().await?;
+
+    let before = get_account_member(id, user_id, &txn).await?;
+    let member = set(id, user_id, data.role, &txn).await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: before
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(ApiError::internal)?,
+            after: Some(serde_json::to_value(&member).map_err(ApiError::internal)?),
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::SetAccountMember,
+                format!("account:{}", id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(member))
+}
@@ -0,0 +1,140 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{
+    get_or_create_economy_state, record_audit_entry, update_economy_state, AuditEntryForm,
+    UpdateEconomyStateForm,
+};
+use economy_service_entity::{
+    audit_log_entry::AuditAction,
+    economy_state::{self, AccountTier},
+};
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::{AuthenticatedUser, Json, Path, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Data used in set limits operation
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct DataSetLimits {
+    /// Limits tier of the account
+    tier: AccountTier,
+
+    /// Override of the per-transaction limit, null to use the tier default
+    per_transaction: Option<i32>,
+
+    /// Override of the daily limit, null to use the tier default
+    daily: Option<i32>,
+
+    /// Override of the rolling window limit, null to use the tier default
+    window: Option<i32>,
+
+    /// Reason recorded in the audit log
+    reason: Option<String>,
+}
+
+fn limits_json(state: &economy_state::Model) -> Value {
+    json!({
+        "tier": state.tier,
+        "per_transaction": state.limit_per_transaction,
+        "daily": state.limit_daily,
+        "window": state.limit_window,
+    })
+}
+
+/// Set transfer limits tier and overrides of target user. Admins only.
+#[utoipa::path(
+    put, path = "/users/{id}/limits", tag = "Transfer limits", request_body = DataSetLimits,
+    params(
+        ("id" = String, Path, description = "Target user ID")
+    ),
+    responses(
+        (status = 204, description = "Successful update"),
+        (status = 400, body = AppError, content_type = "application/problem+json", description = "Validation failed: negative limit"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "User not found"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn set_limits(
+    Path(id): Path<i32>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+    Json(data): Json<DataSetLimits>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    if [data.per_transaction, data.daily, data.window]
+        .into_iter()
+        .flatten()
+        .any(|limit| limit < 0)
+    {
+        return Err(ApiError::new(
+            ErrorCode::ValidationFailed,
+            "Limits should not be negative",
+        ));
+    }
+
+    let res = state
+        .users_client
+        .get_user(id)
+        .await
+        .map_err(ApiError::upstream)?;
+
+    let target = match res {
+        users_service_client::GetUserResponse::Ok(user) => user,
+        users_service_client::GetUserResponse::NotFound => {
+            return Err(ApiError::new(ErrorCode::UserNotFound, "User not found"))
+        }
+        _ => unreachable!(),
+    };
+
+    // update limits and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let before = get_or_create_economy_state(target.id, &txn).await?;
+
+    let after = update_economy_state(
+        before.clone().into(),
+        UpdateEconomyStateForm {
+            tier: Some(data.tier),
+            limit_per_transaction: Some(data.per_transaction),
+            limit_daily: Some(data.daily),
+            limit_window: Some(data.window),
+            ..Default::default()
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(limits_json(&before)),
+            after: Some(limits_json(&after)),
+            reason: data.reason,
+            ..ctx.audit_entry(
+                user.id,
+                AuditAction::SetLimits,
+                format!("user:{}", target.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,80 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use economy_service_core::{record_audit_entry, unfreeze_account, AuditEntryForm};
+use economy_service_entity::audit_log_entry::AuditAction;
+use sea_orm::TransactionTrait;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{AuthenticatedUser, Path, Query, RequestContext},
+    responses::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Query parameters of unfreeze operation
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct UnfreezeQuery {
+    /// Reason recorded in the audit log
+    reason: Option<String>,
+}
+
+/// Unfreeze account of target user. Admins only.
+#[utoipa::path(
+    delete, path = "/users/{id}/freeze", tag = "Account freezes",
+    params(
+        ("id" = String, Path, description = "Target user ID"),
+        UnfreezeQuery,
+    ),
+    responses(
+        (status = 204, description = "Successful unfreeze"),
+        (status = 401, body = AppError, content_type = "application/problem+json", description = "Authentication failed"),
+        (status = 403, body = AppError, content_type = "application/problem+json", description = "Missing admin role"),
+        (status = 404, body = AppError, content_type = "application/problem+json", description = "Account is not frozen"),
+        (status = 429, body = AppError, content_type = "application/problem+json", description = "Too many requests"),
+    ),
+    security(("api_key" = []))
+)]
+pub(crate) async fn unfreeze(
+    Path(id): Path<i32>,
+    Query(query): Query<UnfreezeQuery>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    ctx: RequestContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    if !user.admin {
+        return Err(ApiError::new(
+            ErrorCode::MissingAdminRole,
+            "Missing admin role",
+        ));
+    }
+
+    // unfreeze and record it in the audit log atomically
+    let txn = state.conn.begin().await?;
+
+    let freeze = unfreeze_account(id, &txn).await?;
+
+    let freeze = match freeze {
+        Some(freeze) => freeze,
+        None => {
+            return Err(ApiError::new(
+                ErrorCode::FreezeNotFound,
+                "Account is not frozen",
+            ))
+        }
+    };
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(serde_json::to_value(freeze).map_err(ApiError::internal)?),
+            reason: query.reason,
+            ..ctx.audit_entry(user.id, AuditAction::Unfreeze, format!("user:{}", id))
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
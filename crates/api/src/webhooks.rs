@@ -0,0 +1,313 @@
+use chrono::{Duration, Utc};
+use economy_service_core::{
+    claim_webhook_delivery, complete_webhook_delivery, fail_webhook_delivery,
+    get_due_webhook_deliveries,
+};
+use economy_service_entity::{webhook, webhook_delivery};
+use hmac::{Hmac, Mac};
+use sea_orm::{ActiveEnum, DbConn, DbErr};
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// Amount of deliveries attempted per poll
+const BATCH_SIZE: u64 = 100;
+
+/// Longest delay between two attempts of a delivery
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
+
+/// Webhook delivery configuration, read from `WEBHOOKS_*` environment variables
+#[derive(Debug, Deserialize)]
+pub(crate) struct WebhooksConfig {
+    /// Seconds between polls for due deliveries
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+
+    /// Attempts after which a delivery is marked as failed
+    #[serde(default = "default_max_attempts")]
+    max_attempts: i32,
+
+    /// Delay before the first retry in seconds, doubled with every attempt
+    #[serde(default = "default_backoff_secs")]
+    backoff_secs: i64,
+
+    /// Seconds to wait for the receiver to respond
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+}
+
+fn default_poll_interval_secs() -> u64 {
+    5
+}
+
+fn default_max_attempts() -> i32 {
+    8
+}
+
+fn default_backoff_secs() -> i64 {
+    30
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Signs the payload with the webhook secret, as sent in `x-economy-signature` header
+pub(crate) fn sign(secret: &str, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delivers pending webhook events until the process exits
+pub(crate) async fn run_delivery_worker(conn: DbConn, config: WebhooksConfig) {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(config.timeout_secs))
+        .build()
+        .unwrap();
+
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(config.poll_interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(err) = deliver_due(&client, &conn, &config).await {
+            tracing::error!("failed to deliver webhooks: {}", err);
+        }
+    }
+}
+
+async fn deliver_due(
+    client: &reqwest::Client,
+    conn: &DbConn,
+    config: &WebhooksConfig,
+) -> Result<(), DbErr> {
+    // a claimed delivery is retried after the lease if this worker dies mid-attempt
+    let lease = Duration::seconds(config.timeout_secs as i64 * 2);
+
+    for (delivery, webhook) in get_due_webhook_deliveries(BATCH_SIZE, conn).await? {
+        let webhook = match webhook {
+            Some(webhook) => webhook,
+            None => continue,
+        };
+
+        if !claim_webhook_delivery(&delivery, lease, conn).await? {
+            continue;
+        }
+
+        deliver(client, conn, config, delivery, &webhook).await?;
+    }
+
+    Ok(())
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    conn: &DbConn,
+    config: &WebhooksConfig,
+    delivery: webhook_delivery::Model,
+    webhook: &webhook::Model,
+) -> Result<(), DbErr> {
+    let res = client
+        .post(&webhook.url)
+        .header("content-type", "application/json")
+        .header("x-economy-event", delivery.event.to_value())
+        .header("x-economy-delivery", delivery.id.to_string())
+        .header(
+            "x-economy-signature",
+            sign(&webhook.secret, &delivery.payload),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let (status_code, error) = match res {
+        Ok(res) if res.status().is_success() => {
+            complete_webhook_delivery(delivery, res.status().as_u16() as i32, conn).await?;
+            return Ok(());
+        }
+        Ok(res) => (
+            Some(res.status().as_u16() as i32),
+            format!("Receiver responded with {}", res.status()),
+        ),
+        Err(err) => (None, err.to_string()),
+    };
+
+    let attempts = delivery.attempts + 1;
+    let next_attempt_at = (attempts < config.max_attempts).then(|| {
+        let backoff = config
+            .backoff_secs
+            .saturating_mul(1 << (attempts - 1).min(20))
+            .min(MAX_BACKOFF_SECS);
+        Utc::now() + Duration::seconds(backoff)
+    });
+
+    tracing::debug!(
+        "webhook {} delivery {} attempt {} failed: {}",
+        webhook.id,
+        delivery.id,
+        attempts,
+        error
+    );
+    fail_webhook_delivery(delivery, status_code, error, next_attempt_at, conn).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Extension, Router,
+    };
+    use economy_service_core::{
+        create_webhook, get_or_create_economy_state, get_webhook_deliveries, mint,
+        CreateWebhookForm,
+    };
+    use economy_service_entity::{webhook::WebhookEvent, webhook_delivery::DeliveryStatus};
+    use economy_service_migration::{Migrator, MigratorTrait};
+    use sea_orm::{ActiveModelTrait, Database, Set};
+    use std::{
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+    /// Receiver rejecting the first two attempts and accepting the next ones
+    fn spawn_receiver(received: Received) -> String {
+        let app =
+            Router::new()
+                .route(
+                    "/hook",
+                    post(
+                        |Extension(received): Extension<Received>,
+                         headers: HeaderMap,
+                         body: String| async move {
+                            let mut received = received.lock().unwrap();
+                            received.push((headers, body));
+                            if received.len() <= 2 {
+                                StatusCode::INTERNAL_SERVER_ERROR
+                            } else {
+                                StatusCode::NO_CONTENT
+                            }
+                        },
+                    ),
+                )
+                .layer(Extension(received));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        format!("http://{}/hook", addr)
+    }
+
+    async fn delivery(webhook: &webhook::Model, conn: &DbConn) -> webhook_delivery::Model {
+        let mut deliveries = get_webhook_deliveries(webhook.id, 10, 0, conn)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        deliveries.remove(0)
+    }
+
+    /// Makes the pending delivery due right away instead of waiting for its backoff
+    async fn make_due(delivery: webhook_delivery::Model, conn: &DbConn) {
+        let mut delivery: webhook_delivery::ActiveModel = delivery.into();
+        delivery.next_attempt_at = Set(Utc::now() - Duration::seconds(1));
+        delivery.update(conn).await.unwrap();
+    }
+
+    fn assert_backoff(delivery: &webhook_delivery::Model, secs: i64) {
+        let delay = delivery.next_attempt_at - Utc::now();
+        assert!(
+            delay > Duration::seconds(secs - 5) && delay <= Duration::seconds(secs),
+            "next attempt in {}",
+            delay
+        );
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_events_with_retries() {
+        let db_path =
+            std::env::temp_dir().join(format!("economy-webhooks-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+            .await
+            .unwrap();
+        Migrator::up(&conn, None).await.unwrap();
+
+        let received = Received::default();
+        let url = spawn_receiver(received.clone());
+        let webhook = create_webhook(
+            CreateWebhookForm {
+                url,
+                secret: String::from("secret"),
+                events: vec![WebhookEvent::Minted],
+                created_by: 1,
+            },
+            &conn,
+        )
+        .await
+        .unwrap();
+
+        get_or_create_economy_state(2, &conn).await.unwrap();
+        mint(2, 100, &conn).await.unwrap();
+
+        let config = WebhooksConfig {
+            poll_interval_secs: 1,
+            max_attempts: 5,
+            backoff_secs: 30,
+            timeout_secs: 5,
+        };
+        let client = reqwest::Client::new();
+
+        // a rejected attempt is retried after the backoff
+        deliver_due(&client, &conn, &config).await.unwrap();
+        let failed = delivery(&webhook, &conn).await;
+        assert_eq!(failed.status, DeliveryStatus::Pending);
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(failed.last_status_code, Some(500));
+        assert_backoff(&failed, 30);
+
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 1);
+            let (headers, body) = &received[0];
+            assert_eq!(headers["x-economy-signature"], sign("secret", body));
+            assert_eq!(headers["x-economy-event"], WebhookEvent::Minted.to_value());
+            assert_eq!(headers["x-economy-delivery"], failed.id.to_string());
+            assert_eq!(body, &failed.payload);
+        }
+
+        // nothing is sent before the backoff passes
+        deliver_due(&client, &conn, &config).await.unwrap();
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // the backoff doubles with every attempt
+        make_due(failed, &conn).await;
+        deliver_due(&client, &conn, &config).await.unwrap();
+        let failed = delivery(&webhook, &conn).await;
+        assert_eq!(failed.attempts, 2);
+        assert_backoff(&failed, 60);
+
+        make_due(failed, &conn).await;
+        deliver_due(&client, &conn, &config).await.unwrap();
+        let delivered = delivery(&webhook, &conn).await;
+        assert_eq!(delivered.status, DeliveryStatus::Succeeded);
+        assert_eq!(delivered.attempts, 3);
+        assert_eq!(delivered.last_status_code, Some(204));
+        assert_eq!(delivered.last_error, None);
+        assert!(delivered.delivered_at.is_some());
+
+        // completed deliveries aren't sent again
+        make_due(delivered, &conn).await;
+        deliver_due(&client, &conn, &config).await.unwrap();
+        assert_eq!(received.lock().unwrap().len(), 3);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
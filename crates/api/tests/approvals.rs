@@ -0,0 +1,163 @@
+use chrono::{Duration, Utc};
+use economy_service_client::{
+    models::{
+        AppError, Approval, DataAddMoney, DataBatchPay, DataPayoutItem, ErrorCode, PayoutMode,
+        ProposalStatus,
+    },
+    EconomyServiceClient, Error,
+};
+use economy_service_core::{
+    create_proposal, freeze_account, CreateProposalForm, FreezeAccountForm,
+};
+use economy_service_entity::proposal::ProposalKind;
+use economy_service_migration::sea_orm::DbConn;
+use serde_json::json;
+
+mod common;
+
+use common::{database, serve, set_banker, ADMIN, ALICE, BANKER, BOB};
+
+/// Serves the API with mints of 100 in total needing a single approval.
+/// Every test of this file configures the same thresholds, so they can share the environment.
+async fn serve_with_approvals(conn: DbConn) -> String {
+    std::env::set_var("APPROVALS_MINT_THRESHOLD", "100");
+    std::env::set_var("APPROVALS_REQUIRED", "1");
+
+    // the admin approves mints of the banker
+    set_banker(ADMIN, true, &conn).await;
+
+    serve(conn)
+}
+
+fn minting(amount: i32) -> DataAddMoney {
+    DataAddMoney {
+        amount,
+        reason: None,
+    }
+}
+
+#[tokio::test]
+async fn mint_threshold_is_cumulative() {
+    let (conn, db_path) = database("approvals-cumulative").await;
+    let url = serve_with_approvals(conn).await;
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+
+    // 90 minted and burned, and another 10 reach the threshold without going over it
+    for amount in [60, -30, 10] {
+        assert!(matches!(
+            banker.add_money(ALICE, &minting(amount)).await,
+            Ok(Approval::Executed)
+        ));
+    }
+
+    let proposal = match banker.add_money(ALICE, &minting(1)).await {
+        Ok(Approval::Proposed(proposal)) => proposal,
+        res => panic!("unexpected result: {:?}", res),
+    };
+
+    // while other bankers mint on their own
+    assert!(matches!(
+        admin.add_money(ALICE, &minting(10)).await,
+        Ok(Approval::Executed)
+    ));
+
+    let details = admin.approve_proposal(proposal.id).await.unwrap();
+    assert_eq!(details.proposal.status, ProposalStatus::Executed);
+    assert_eq!(banker.get_by_id(ALICE).await.unwrap().state.balance, 51);
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn failed_execution_fails_proposal() {
+    let (conn, db_path) = database("approvals-failed").await;
+    let url = serve_with_approvals(conn.clone()).await;
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+
+    let proposal = match banker.add_money(ALICE, &minting(101)).await {
+        Ok(Approval::Proposed(proposal)) => proposal,
+        res => panic!("unexpected result: {:?}", res),
+    };
+
+    // the payee is frozen before the mint is approved
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: ADMIN,
+        block_incoming: true,
+        expires_at: None,
+    };
+    freeze_account(ALICE, freeze, &conn).await.unwrap();
+
+    let details = admin.approve_proposal(proposal.id).await.unwrap();
+    assert_eq!(details.proposal.status, ProposalStatus::Failed);
+    assert_eq!(
+        details.proposal.error.as_deref(),
+        Some("Payee account is frozen")
+    );
+    assert_eq!(details.proposal.transaction_id, None);
+
+    // proposals that can't be read fail instead of staying approved
+    let proposal = create_proposal(
+        CreateProposalForm {
+            kind: ProposalKind::Mint,
+            proposer_id: BANKER,
+            account_id: None,
+            payload: json!({ "amount": "a lot" }),
+            required_approvals: 1,
+            expires_at: Utc::now() + Duration::hours(1),
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    let details = admin.approve_proposal(proposal.id).await.unwrap();
+    assert_eq!(details.proposal.status, ProposalStatus::Failed);
+    assert_eq!(
+        details.proposal.error.as_deref(),
+        Some("Internal server error")
+    );
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn mint_payout_threshold_is_cumulative() {
+    let (conn, db_path) = database("approvals-payout").await;
+    let url = serve_with_approvals(conn).await;
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+
+    banker.add_money(ALICE, &minting(60)).await.unwrap();
+
+    let payout = |amount| DataBatchPay {
+        items: [ALICE, BOB]
+            .into_iter()
+            .map(|payee_id| DataPayoutItem {
+                payee_id,
+                amount,
+                comment: None,
+            })
+            .collect(),
+        mode: PayoutMode::BestEffort,
+        mint: true,
+        reason: None,
+    };
+
+    // 60 minted already, so another 42 go over the threshold
+    assert!(matches!(
+        banker.batch_pay(&payout(21)).await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::ApprovalRequired,
+            ..
+        }))
+    ));
+
+    let report = banker.batch_pay(&payout(20)).await.unwrap();
+    assert_eq!(report.paid, 2);
+    assert_eq!(report.total, 40);
+    assert_eq!(banker.get_by_id(BOB).await.unwrap().state.balance, 20);
+
+    let _ = std::fs::remove_file(&db_path);
+}
@@ -0,0 +1,685 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use economy_service_client::{
+    models::{
+        AccountKind, AccountRole, AccountTier, AppError, Approval, AuditAction, AuditLogQuery,
+        BalanceEvent, DataAddMoney, DataBatchPay, DataCreateAccount, DataCreateApiKey,
+        DataOfferLoan, DataPay, DataPayoutItem, DataReverse, DataSendFromAccount,
+        DataSetAccountLimit, DataSetAccountMember, ErrorCode, LoanStatus, PayoutMode,
+        TransactionKind,
+    },
+    EconomyServiceClient, Error,
+};
+use economy_service_core::mint;
+use futures::{Stream, StreamExt};
+use serde_json::json;
+
+mod common;
+
+use common::{database, serve, set_banker, ADMIN, ALICE, BANKER, BOB};
+
+#[tokio::test]
+async fn client_matches_router() {
+    let (conn, db_path) = database("client").await;
+    let url = serve(conn.clone());
+    let anonymous = EconomyServiceClient::new(&url);
+    let alice = EconomyServiceClient::new(&url).with_token(format!("user-{}", ALICE));
+    let bob = EconomyServiceClient::new(&url).with_token(format!("user-{}", BOB));
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+
+    let state = alice.get_self().await.unwrap();
+    assert_eq!(state.user_id, ALICE);
+    assert_eq!(state.balance, 0);
+    assert_eq!(state.tier, AccountTier::Standard);
+
+    assert!(matches!(
+        anonymous.get_self().await,
+        Err(Error::Unauthorized(_))
+    ));
+
+    let minted = banker
+        .add_money(
+            ALICE,
+            &DataAddMoney {
+                amount: 100,
+                reason: Some("Starting money".into()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(minted, Approval::Executed);
+
+    alice
+        .pay(
+            BOB,
+            &DataPay {
+                amount: 30,
+                comment: Some("Lunch".into()),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        alice
+            .pay(
+                ALICE,
+                &DataPay {
+                    amount: 1,
+                    comment: None
+                }
+            )
+            .await,
+        Err(Error::BadRequest(AppError {
+            code: ErrorCode::SelfPayment,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        alice
+            .pay(
+                99,
+                &DataPay {
+                    amount: 1,
+                    comment: None
+                }
+            )
+            .await,
+        Err(Error::NotFound(AppError {
+            code: ErrorCode::UserNotFound,
+            ..
+        }))
+    ));
+
+    let bob_state = anonymous.get_by_username("bob").await.unwrap();
+    assert_eq!(bob_state.state.user_id, BOB);
+    assert_eq!(
+        anonymous
+            .get_by_username("BoB")
+            .await
+            .unwrap()
+            .state
+            .user_id,
+        BOB
+    );
+    assert_eq!(bob_state.state.balance, 30);
+    assert_eq!(bob_state.freeze, None);
+
+    // replaying the event stream from the start yields the payment
+    let events = bob.get_self_events(Some(0)).await.unwrap();
+    let payment = match Box::pin(events).next().await.unwrap().unwrap() {
+        BalanceEvent::Transaction(update) => {
+            assert_eq!(update.transaction.kind, TransactionKind::Payment);
+            assert_eq!(update.balance, Some(30));
+            update.transaction.id
+        }
+        event => panic!("unexpected event {:?}", event),
+    };
+    let refund = bob
+        .refund_payment(
+            payment,
+            &DataReverse {
+                amount: Some(10),
+                comment: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(refund.kind, TransactionKind::Reversal);
+    assert_eq!(refund.reverses_id, Some(payment));
+    assert_eq!(alice.get_self().await.unwrap().balance, 80);
+
+    assert!(matches!(
+        alice
+            .add_money(
+                ALICE,
+                &DataAddMoney {
+                    amount: 10,
+                    reason: None
+                }
+            )
+            .await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::MissingBankerRole,
+            ..
+        }))
+    ));
+
+    let report = alice
+        .batch_pay(&DataBatchPay {
+            items: vec![
+                DataPayoutItem {
+                    payee_id: BOB,
+                    amount: 5,
+                    comment: None,
+                },
+                DataPayoutItem {
+                    payee_id: 99,
+                    amount: 5,
+                    comment: None,
+                },
+            ],
+            mode: PayoutMode::Atomic,
+            mint: false,
+            reason: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.items, 2);
+    assert_eq!(report.paid, 0);
+    assert_eq!(report.results[1].code, Some(ErrorCode::UserNotFound));
+
+    // best-effort payouts pay what they can
+    let report = alice
+        .batch_pay(&DataBatchPay {
+            items: vec![
+                DataPayoutItem {
+                    payee_id: 99,
+                    amount: 5,
+                    comment: None,
+                },
+                DataPayoutItem {
+                    payee_id: BOB,
+                    amount: 5,
+                    comment: None,
+                },
+            ],
+            mode: PayoutMode::BestEffort,
+            mint: false,
+            reason: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.paid, 1);
+    assert_eq!(report.total, 5);
+    assert_eq!(report.results[0].code, Some(ErrorCode::UserNotFound));
+    assert!(report.results[1].transaction_id.is_some());
+    assert_eq!(alice.get_self().await.unwrap().balance, 75);
+
+    // API keys mint on behalf of the admin only while they are a banker and the key isn't revoked
+    let mint_key = DataCreateApiKey {
+        name: "Game server".into(),
+        scopes: vec!["mint".into()],
+        expires_at: None,
+    };
+    assert!(matches!(
+        admin.create_api_key(&mint_key).await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::MissingBankerRole,
+            ..
+        }))
+    ));
+    set_banker(ADMIN, true, &conn).await;
+    let created = admin.create_api_key(&mint_key).await.unwrap();
+    assert!(created.key.starts_with(&created.api_key.prefix));
+    let server = EconomyServiceClient::new(&url).with_api_key(created.key);
+
+    let minted = server
+        .add_money(
+            BOB,
+            &DataAddMoney {
+                amount: 5,
+                reason: Some("Event reward".into()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(minted, Approval::Executed);
+    assert!(matches!(
+        server.get_by_id(BOB).await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::MissingScope,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        server.get_self().await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::NotAllowed,
+            ..
+        }))
+    ));
+
+    set_banker(ADMIN, false, &conn).await;
+    assert!(matches!(
+        server
+            .add_money(
+                BOB,
+                &DataAddMoney {
+                    amount: 5,
+                    reason: None
+                }
+            )
+            .await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::MissingBankerRole,
+            ..
+        }))
+    ));
+
+    let keys = admin.get_api_keys().await.unwrap();
+    assert!(keys[0].last_used_at.is_some());
+    admin.revoke_api_key(created.api_key.id).await.unwrap();
+    assert!(matches!(
+        server
+            .add_money(
+                BOB,
+                &DataAddMoney {
+                    amount: 5,
+                    reason: None
+                }
+            )
+            .await,
+        Err(Error::Unauthorized(_))
+    ));
+
+    // loans move the principal on acceptance and count as the borrower's debt
+    let offer = |principal| DataOfferLoan {
+        borrower_id: BOB,
+        principal,
+        interest_rate_bps: 500,
+        installments: 2,
+        interval_hours: 24,
+        comment: None,
+    };
+    let loan = alice.offer_loan(&offer(40)).await.unwrap();
+    assert_eq!(loan.status, LoanStatus::Offered);
+    assert_eq!(loan.total_due, 42);
+    assert!(matches!(
+        alice.accept_loan(loan.id).await,
+        Err(Error::Forbidden(_))
+    ));
+
+    let loan = bob.accept_loan(loan.id).await.unwrap();
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert!(loan.next_due_at.is_some());
+    assert_eq!(alice.get_self().await.unwrap().balance, 35);
+    let state = bob.get_self().await.unwrap();
+    assert_eq!(state.balance, 70);
+    assert_eq!(state.debt, 42);
+    assert!(matches!(
+        bob.decline_loan(loan.id).await,
+        Err(Error::Conflict(AppError {
+            code: ErrorCode::LoanNotPending,
+            ..
+        }))
+    ));
+
+    let declined = alice.offer_loan(&offer(10)).await.unwrap();
+    bob.decline_loan(declined.id).await.unwrap();
+    let loans = alice.get_self_loans().await.unwrap();
+    assert_eq!(loans.len(), 2);
+    assert_eq!(loans[0].status, LoanStatus::Declined);
+    assert_eq!(bob.get_loan(loan.id).await.unwrap().borrower_id, BOB);
+    assert_eq!(anonymous.get_by_id(BOB).await.unwrap().state.debt, 42);
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn audit_log_records_account_changes() {
+    let (conn, db_path) = database("audit").await;
+    let url = serve(conn);
+    let alice = EconomyServiceClient::new(&url).with_token(format!("user-{}", ALICE));
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+
+    let account = alice
+        .create_account(&DataCreateAccount {
+            kind: AccountKind::Organization,
+            name: "Guild".into(),
+        })
+        .await
+        .unwrap();
+    alice
+        .set_account_limit(
+            account.id,
+            AccountRole::Member,
+            &DataSetAccountLimit {
+                per_transaction: Some(10),
+                daily: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // request IDs sent by clients are replaced with our own
+    let res = reqwest::Client::new()
+        .put(format!(
+            "{}/v1/accounts/{}/members/{}",
+            url, account.id, BOB
+        ))
+        .header("x-token", format!("user-{}", ALICE))
+        .header("x-request-id", "spoofed")
+        .json(&json!({ "role": "treasurer" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let request_id = res.headers()["x-request-id"].to_str().unwrap().to_owned();
+    assert_ne!(request_id, "spoofed");
+
+    alice.remove_account_member(account.id, BOB).await.unwrap();
+
+    let mut entries = admin
+        .get_audit_log(&AuditLogQuery {
+            target: Some(format!("account:{}", account.id)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    entries.reverse();
+
+    let actions: Vec<AuditAction> = entries.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        [
+            AuditAction::SetAccountLimit,
+            AuditAction::SetAccountMember,
+            AuditAction::RemoveAccountMember,
+        ]
+    );
+    assert!(entries.iter().all(|entry| entry.actor_id == ALICE));
+
+    assert_eq!(entries[0].before, None);
+    assert_eq!(entries[0].after.as_ref().unwrap()["per_transaction"], 10);
+
+    assert_eq!(entries[1].request_id.as_deref(), Some(request_id.as_str()));
+    assert_eq!(entries[1].before, None);
+    assert_eq!(entries[1].after.as_ref().unwrap()["role"], "treasurer");
+
+    assert_eq!(entries[2].before.as_ref().unwrap()["user_id"], BOB);
+    assert_eq!(entries[2].after, None);
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn account_spending_fits_role_limits() {
+    let (conn, db_path) = database("account-spending").await;
+    mint(ALICE, 100, &conn).await.unwrap();
+    let url = serve(conn);
+    let alice = EconomyServiceClient::new(&url).with_token(format!("user-{}", ALICE));
+    let bob = EconomyServiceClient::new(&url).with_token(format!("user-{}", BOB));
+
+    let account = alice
+        .create_account(&DataCreateAccount {
+            kind: AccountKind::Organization,
+            name: "Guild".into(),
+        })
+        .await
+        .unwrap();
+    alice
+        .pay_account(
+            account.id,
+            &DataPay {
+                amount: 100,
+                comment: None,
+            },
+        )
+        .await
+        .unwrap();
+    alice
+        .set_account_member(
+            account.id,
+            BOB,
+            &DataSetAccountMember {
+                role: AccountRole::Treasurer,
+            },
+        )
+        .await
+        .unwrap();
+    alice
+        .set_account_limit(
+            account.id,
+            AccountRole::Treasurer,
+            &DataSetAccountLimit {
+                per_transaction: Some(10),
+                daily: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let send = |amount| DataSendFromAccount {
+        payee_id: Some(ALICE),
+        payee_account_id: None,
+        amount,
+        comment: None,
+    };
+    match bob.send_from_account(account.id, &send(20)).await {
+        Err(Error::BadRequest(error)) => {
+            assert_eq!(error.code, ErrorCode::TransferLimitExceeded);
+            assert_eq!(error.remaining, Some(10));
+            assert_eq!(error.limit.as_deref(), Some("per_transaction"));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert!(matches!(
+        bob.send_from_account(account.id, &send(10)).await,
+        Ok(Approval::Executed)
+    ));
+
+    // the only owner can't leave
+    let err = alice
+        .remove_account_member(account.id, ALICE)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(ErrorCode::ValidationFailed));
+
+    // keys spend on behalf of the admin creating them, who has to own the account
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+    let pay_key = DataCreateApiKey {
+        name: "Shop".into(),
+        scopes: vec![format!("pay:from:{}", account.id)],
+        expires_at: None,
+    };
+    assert!(matches!(
+        admin.create_api_key(&pay_key).await,
+        Err(Error::Forbidden(AppError {
+            code: ErrorCode::MissingAccountRole,
+            ..
+        }))
+    ));
+    alice
+        .set_account_member(
+            account.id,
+            ADMIN,
+            &DataSetAccountMember {
+                role: AccountRole::Owner,
+            },
+        )
+        .await
+        .unwrap();
+    admin.create_api_key(&pay_key).await.unwrap();
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+/// Waits for the next event of the stream, failing the test if it doesn't come in time
+async fn next_event(
+    events: &mut (impl Stream<Item = Result<BalanceEvent, Error>> + Unpin),
+) -> BalanceEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("no event received")
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn balance_events_follow_every_change() {
+    let (conn, db_path) = database("events").await;
+    let url = serve(conn.clone());
+    let alice = EconomyServiceClient::new(&url).with_token(format!("user-{}", ALICE));
+    let bob = EconomyServiceClient::new(&url).with_token(format!("user-{}", BOB));
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+
+    banker
+        .add_money(
+            ALICE,
+            &DataAddMoney {
+                amount: 100,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut events = Box::pin(bob.get_self_events(None).await.unwrap());
+    assert_eq!(next_event(&mut events).await, BalanceEvent::Balance(0));
+
+    alice
+        .pay(
+            BOB,
+            &DataPay {
+                amount: 30,
+                comment: None,
+            },
+        )
+        .await
+        .unwrap();
+    let payment = match next_event(&mut events).await {
+        BalanceEvent::Transaction(update) => {
+            assert_eq!(update.transaction.kind, TransactionKind::Payment);
+            assert_eq!(update.balance, Some(30));
+            update.transaction.id
+        }
+        event => panic!("unexpected event {:?}", event),
+    };
+
+    // changes recorded outside of the API, e.g. by the CLI, are streamed as well
+    mint(BOB, 5, &conn).await.unwrap();
+    match next_event(&mut events).await {
+        BalanceEvent::Transaction(update) => {
+            assert_eq!(update.transaction.kind, TransactionKind::Mint);
+            assert_eq!(update.balance, Some(35));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    // reconnecting replays what was missed
+    let mut events = Box::pin(bob.get_self_events(Some(payment)).await.unwrap());
+    match next_event(&mut events).await {
+        BalanceEvent::Transaction(update) => {
+            assert_eq!(update.transaction.kind, TransactionKind::Mint)
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn unversioned_routes_link_to_successors() {
+    let (conn, db_path) = database("client-unversioned").await;
+    let url = serve(conn);
+    let client = reqwest::Client::new();
+
+    for (path, successor) in [
+        (format!("/{}", ALICE), format!("/v1/users/{}", ALICE)),
+        (
+            format!("/{}/limits", ALICE),
+            format!("/v1/users/{}/limits", ALICE),
+        ),
+        ("/me".to_owned(), "/v1/me".to_owned()),
+    ] {
+        let res = client
+            .get(format!("{}{}", url, path))
+            .header("x-token", format!("user-{}", ALICE))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{}", path);
+        assert_eq!(res.headers()["deprecation"], "true");
+        assert_eq!(
+            res.headers()["link"].to_str().unwrap(),
+            format!("<{}>; rel=\"successor-version\"", successor)
+        );
+
+        let res = client
+            .get(format!("{}{}", url, successor))
+            .header("x-token", format!("user-{}", ALICE))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{}", successor);
+        assert!(!res.headers().contains_key("deprecation"));
+    }
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn malformed_requests_are_problems() {
+    let (conn, db_path) = database("client-malformed").await;
+    let url = serve(conn);
+    let client = reqwest::Client::new();
+
+    let requests = [
+        // body isn't JSON
+        client
+            .put(format!("{}/v1/users/{}/pay", url, BOB))
+            .header("content-type", "text/plain")
+            .body("10"),
+        // body misses the amount
+        client
+            .put(format!("{}/v1/users/{}/pay", url, BOB))
+            .json(&json!({ "comment": "rent" })),
+        // ID isn't a number
+        client.get(format!("{}/v1/users/bob", url)),
+        // unknown status
+        client.get(format!("{}/v1/proposals?status=lost", url)),
+    ];
+    let expected = [
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, "unsupported_media_type"),
+        (StatusCode::BAD_REQUEST, "validation_failed"),
+        (StatusCode::BAD_REQUEST, "validation_failed"),
+        (StatusCode::BAD_REQUEST, "validation_failed"),
+    ];
+
+    for (request, (status, code)) in requests.into_iter().zip(expected) {
+        let res = request
+            .header("x-token", format!("user-{}", ALICE))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status);
+        assert_eq!(res.headers()["content-type"], "application/problem+json");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["code"], code);
+    }
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn docs_are_served_from_the_binary() {
+    let (conn, db_path) = database("client-docs").await;
+    let url = serve(conn);
+    let client = reqwest::Client::new();
+
+    let page = client
+        .get(format!("{}/docs", url))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(!page.contains("https://"));
+
+    for (path, content_type) in [
+        ("/docs/swagger-ui-bundle.js", "text/javascript"),
+        ("/docs/swagger-ui.css", "text/css"),
+        ("/docs/swagger-initializer.js", "text/javascript"),
+        ("/docs/favicon-32x32.png", "image/png"),
+    ] {
+        assert!(page.contains(path), "{}", path);
+        let res = client.get(format!("{}{}", url, path)).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["content-type"], content_type);
+    }
+
+    let _ = std::fs::remove_file(&db_path);
+}
@@ -0,0 +1,121 @@
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use std::{
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+};
+
+use axum::{
+    extract::Path,
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use economy_service_core::get_or_create_economy_state;
+use economy_service_entity::economy_state;
+use economy_service_migration::{
+    sea_orm::{ActiveModelTrait, Database, DbConn, Set},
+    Migrator, MigratorTrait,
+};
+use serde_json::{json, Value};
+use users_service_client::UsersServiceClient;
+
+pub const ADMIN: i32 = 1;
+pub const ALICE: i32 = 2;
+pub const BOB: i32 = 3;
+pub const BANKER: i32 = 4;
+
+pub fn username(id: i32) -> Option<&'static str> {
+    match id {
+        ADMIN => Some("Admin"),
+        ALICE => Some("Alice"),
+        BOB => Some("Bob"),
+        BANKER => Some("Banker"),
+        _ => None,
+    }
+}
+
+pub fn user(id: i32) -> Value {
+    json!({ "id": id, "username": username(id).unwrap(), "admin": id == ADMIN })
+}
+
+/// Users service endpoint authenticating the `user-{id}` token
+pub async fn me(headers: HeaderMap) -> Result<Json<Value>, StatusCode> {
+    headers
+        .get("x-token")
+        .and_then(|token| token.to_str().ok())
+        .and_then(|token| token.strip_prefix("user-"))
+        .and_then(|id| id.parse().ok())
+        .filter(|id| username(*id).is_some())
+        .map(|id| Json(user(id)))
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+/// Users service knowing a few users, authenticated with `user-{id}` tokens
+pub fn users_service() -> Router {
+    Router::new()
+        .route("/me", get(me))
+        .route(
+            "/by-username/:username",
+            get(|Path(name): Path<String>| async move {
+                // like the real one, usernames are matched in lowercase only
+                (1..=4)
+                    .find(|id| username(*id).unwrap().to_ascii_lowercase() == name)
+                    .map(|id| Json(user(id)))
+                    .ok_or(StatusCode::NOT_FOUND)
+            }),
+        )
+        .route(
+            "/:id",
+            get(|Path(id): Path<i32>| async move {
+                username(id)
+                    .map(|_| Json(user(id)))
+                    .ok_or(StatusCode::NOT_FOUND)
+            }),
+        )
+}
+
+/// Serves the router on an ephemeral port, returning its base URL
+pub fn spawn(router: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(router.into_make_service_with_connect_info::<SocketAddr>());
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+/// Migrated SQLite database named after the test, in which `BANKER` is a banker
+pub async fn database(name: &str) -> (DbConn, PathBuf) {
+    let db_path =
+        std::env::temp_dir().join(format!("economy-{}-{}.sqlite", name, std::process::id()));
+    let _ = std::fs::remove_file(&db_path);
+    let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+        .await
+        .unwrap();
+    Migrator::up(&conn, None).await.unwrap();
+    set_banker(BANKER, true, &conn).await;
+
+    (conn, db_path)
+}
+
+/// Grants or revokes the banker role of the user
+pub async fn set_banker(user_id: i32, banker: bool, conn: &DbConn) {
+    let mut state: economy_state::ActiveModel = get_or_create_economy_state(user_id, conn)
+        .await
+        .unwrap()
+        .into();
+    state.banker = Set(banker);
+    state.update(conn).await.unwrap();
+}
+
+/// Serves the API backed by the database and the mock users service, returning its base URL
+pub fn serve(conn: DbConn) -> String {
+    let users_url = spawn(users_service());
+    spawn(economy_service_api::router(
+        conn,
+        UsersServiceClient::new(users_url),
+    ))
+}
@@ -0,0 +1,99 @@
+use axum::{routing::get, Router};
+use economy_service_client::{
+    models::{ErrorCode, ExportFormat, TransactionsExportQuery},
+    EconomyServiceClient, Error,
+};
+use economy_service_core::{mint, transfer, LedgerParty, TransferForm};
+use users_service_client::UsersServiceClient;
+
+mod common;
+
+use common::{database, me, serve, spawn, ALICE, BANKER, BOB};
+
+#[tokio::test]
+async fn export_resolves_usernames() {
+    let (conn, db_path) = database("export").await;
+    mint(ALICE, 100, &conn).await.unwrap();
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(ALICE),
+            payee: LedgerParty::User(BOB),
+            amount: 30,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    let url = serve(conn);
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+    let alice = EconomyServiceClient::new(&url).with_token(format!("user-{}", ALICE));
+
+    let csv = banker.get_balances_export(ExportFormat::Csv).await.unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("user_id,username,balance,banker,tier"));
+    assert!(csv.contains("2,Alice,70,false,standard"));
+    assert!(csv.contains("3,Bob,30,false,standard"));
+    assert!(csv.contains("4,Banker,0,true,standard"));
+
+    let ndjson = banker
+        .get_transactions_export(&TransactionsExportQuery {
+            format: ExportFormat::Ndjson,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let rows: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["kind"], "mint");
+    assert_eq!(rows[0]["payee_username"], "Alice");
+    assert_eq!(rows[1]["payer_username"], "Alice");
+    assert_eq!(rows[1]["payee_username"], "Bob");
+    assert_eq!(rows[1]["amount"], 30);
+
+    assert!(matches!(
+        alice.get_balances_export(ExportFormat::Csv).await,
+        Err(Error::Forbidden(err)) if err.code == ErrorCode::MissingBankerRole
+    ));
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn export_fails_before_streaming_when_users_service_fails() {
+    let (conn, db_path) = database("export-upstream").await;
+    mint(ALICE, 100, &conn).await.unwrap();
+
+    // authenticates callers, but can't tell who the users are
+    let users_url = spawn(
+        Router::new()
+            .route("/me", get(me))
+            .route("/:id", get(|| async { "not json" })),
+    );
+    let url = spawn(economy_service_api::router(
+        conn,
+        UsersServiceClient::new(users_url),
+    ));
+    let banker = EconomyServiceClient::new(&url).with_token(format!("user-{}", BANKER));
+
+    assert!(matches!(
+        banker.get_balances_export(ExportFormat::Csv).await,
+        Err(Error::ServiceUnavailable(err)) if err.code == ErrorCode::UpstreamUnavailable
+    ));
+    assert!(matches!(
+        banker
+            .get_transactions_export(&TransactionsExportQuery::default())
+            .await,
+        Err(Error::ServiceUnavailable(_))
+    ));
+
+    let _ = std::fs::remove_file(&db_path);
+}
@@ -0,0 +1,235 @@
+use std::{net::TcpListener, time::Duration};
+
+use economy_service_core::mint;
+use economy_service_migration::sea_orm::DbConn;
+use tonic::{transport::Channel, Code, Request, Status};
+use users_service_client::UsersServiceClient;
+
+mod common;
+
+use common::{database, spawn, users_service, ALICE, BANKER, BOB};
+use proto::{
+    economy_client::EconomyClient, mint_response, GetBalanceRequest, MintRequest, MintResponse,
+    PayRequest, Transaction,
+};
+
+mod proto {
+    tonic::include_proto!("economy");
+}
+
+/// Serves the gRPC API backed by the database and the mock users service, returning its client
+async fn serve_grpc(conn: DbConn) -> EconomyClient<Channel> {
+    serve_grpc_with_users(conn, spawn(users_service())).await
+}
+
+/// Serves the gRPC API using the users service at the URL.
+/// Mints of 100 in total need a single approval in every test of this file.
+async fn serve_grpc_with_users(conn: DbConn, users_url: String) -> EconomyClient<Channel> {
+    std::env::set_var("APPROVALS_MINT_THRESHOLD", "100");
+    std::env::set_var("APPROVALS_REQUIRED", "1");
+
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let router = economy_service_api::grpc_router(conn, UsersServiceClient::new(users_url));
+    tokio::spawn(router.serve(addr));
+
+    // the server binds the address in the background
+    for _ in 0..50 {
+        if let Ok(client) = EconomyClient::connect(format!("http://{}", addr)).await {
+            return client;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("gRPC server didn't start");
+}
+
+/// Request authenticated as the user
+fn as_user<T>(user_id: i32, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("x-token", format!("user-{}", user_id).parse().unwrap());
+    request
+}
+
+/// Transaction of a mint that didn't need approval
+fn minted(response: MintResponse) -> Transaction {
+    match response.result {
+        Some(mint_response::Result::Transaction(record)) => record,
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+fn error_code(status: &Status) -> &str {
+    status
+        .metadata()
+        .get("error-code")
+        .unwrap()
+        .to_str()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn grpc_get_balance() {
+    let (conn, db_path) = database("grpc-balance").await;
+    mint(ALICE, 100, &conn).await.unwrap();
+    let mut client = serve_grpc(conn).await;
+
+    let state = client
+        .get_balance(GetBalanceRequest { user_id: ALICE })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(state.user_id, ALICE);
+    assert_eq!(state.balance, 100);
+    assert_eq!(state.freeze, None);
+
+    let err = client
+        .get_balance(GetBalanceRequest { user_id: 99 })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+    assert_eq!(error_code(&err), "user_not_found");
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn grpc_pay() {
+    let (conn, db_path) = database("grpc-pay").await;
+    mint(ALICE, 100, &conn).await.unwrap();
+    let mut client = serve_grpc(conn).await;
+
+    let pay = |payee_id, amount| PayRequest {
+        payee_id,
+        amount,
+        comment: None,
+    };
+
+    let record = client.pay(as_user(ALICE, pay(BOB, 30))).await.unwrap();
+    let record = record.into_inner();
+    assert_eq!(record.payer_id, Some(ALICE));
+    assert_eq!(record.payee_id, Some(BOB));
+    assert_eq!(record.amount, 30);
+
+    let err = client.pay(Request::new(pay(BOB, 30))).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let err = client
+        .pay(as_user(ALICE, pay(ALICE, 30)))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&err), "self_payment");
+
+    let err = client.pay(as_user(ALICE, pay(BOB, 0))).await.unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&err), "invalid_amount");
+
+    let err = client.pay(as_user(ALICE, pay(BOB, 71))).await.unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&err), "insufficient_funds");
+
+    let err = client.pay(as_user(ALICE, pay(99, 10))).await.unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    let state = client
+        .get_balance(GetBalanceRequest { user_id: BOB })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(state.balance, 30);
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn grpc_mint() {
+    let (conn, db_path) = database("grpc-mint").await;
+    let mut client = serve_grpc(conn).await;
+
+    let mint = |user_id, amount| MintRequest {
+        user_id,
+        amount,
+        reason: Some("prize".into()),
+    };
+
+    let record = client.mint(as_user(BANKER, mint(ALICE, 50))).await.unwrap();
+    let record = minted(record.into_inner());
+    assert_eq!(record.kind, proto::TransactionKind::Mint as i32);
+    assert_eq!(record.payee_id, Some(ALICE));
+    assert_eq!(record.amount, 50);
+
+    let record = client
+        .mint(as_user(BANKER, mint(ALICE, -20)))
+        .await
+        .unwrap();
+    assert_eq!(
+        minted(record.into_inner()).kind,
+        proto::TransactionKind::Burn as i32
+    );
+
+    // 70 were minted and burned already, so this one is proposed instead
+    let response = client
+        .mint(as_user(BANKER, mint(ALICE, 40)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(matches!(
+        response.result,
+        Some(mint_response::Result::ProposalId(_))
+    ));
+
+    let err = client
+        .mint(as_user(ALICE, mint(ALICE, 50)))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+    assert_eq!(error_code(&err), "missing_banker_role");
+
+    let err = client
+        .mint(as_user(BANKER, mint(99, 50)))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    let state = client
+        .get_balance(GetBalanceRequest { user_id: ALICE })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(state.balance, 30);
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[tokio::test]
+async fn grpc_hides_upstream_errors() {
+    let (conn, db_path) = database("grpc-upstream").await;
+
+    // nothing listens on the address of the users service
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let users_url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let mut client = serve_grpc_with_users(conn, users_url).await;
+
+    let err = client
+        .pay(as_user(
+            ALICE,
+            PayRequest {
+                payee_id: BOB,
+                amount: 10,
+                comment: None,
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unavailable);
+    assert_eq!(error_code(&err), "upstream_unavailable");
+    assert_eq!(err.message(), "Users service is unavailable");
+    assert!(err.metadata().get("x-request-id").is_some());
+
+    let _ = std::fs::remove_file(&db_path);
+}
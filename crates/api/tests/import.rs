@@ -0,0 +1,81 @@
+use economy_service_client::{
+    models::{ImportFormat, ImportProblem, ImportQuery},
+    EconomyServiceClient,
+};
+use economy_service_core::{find_audit_entries, get_or_create_economy_state, mint, AuditLogFilter};
+use economy_service_entity::audit_log_entry::AuditAction;
+use serde_json::json;
+
+mod common;
+
+use common::{database, serve, ADMIN, ALICE, BOB};
+
+#[tokio::test]
+async fn import_rejects_duplicates_and_funded_accounts() {
+    let (conn, db_path) = database("import").await;
+    mint(BOB, 5, &conn).await.unwrap();
+    let url = serve(conn.clone());
+    let admin = EconomyServiceClient::new(&url).with_token(format!("user-{}", ADMIN));
+
+    // every row of a repeated user is rejected, pointing at the first one
+    let data = "user_id,balance\n2,100\n2,50\n2,-1\n";
+    let report = admin
+        .import_balances(&ImportQuery::default(), ImportFormat::Csv, data)
+        .await
+        .unwrap();
+    assert!(!report.applied);
+    assert_eq!(
+        report.problems,
+        vec![
+            ImportProblem {
+                row: 2,
+                user_id: Some(ALICE),
+                detail: "Duplicate of row 1".into(),
+            },
+            ImportProblem {
+                row: 3,
+                user_id: Some(ALICE),
+                detail: "Duplicate of row 1".into(),
+            },
+        ]
+    );
+
+    let data = r#"[{ "user_id": 2, "balance": 100 }, { "user_id": 3, "balance": 10 }]"#;
+    let report = admin
+        .import_balances(&ImportQuery::default(), ImportFormat::Json, data)
+        .await
+        .unwrap();
+    assert!(!report.applied);
+    assert_eq!(
+        report.problems,
+        vec![ImportProblem {
+            row: 2,
+            user_id: Some(BOB),
+            detail: "Account already has a balance of 5".into(),
+        }]
+    );
+
+    let data = r#"[{ "user_id": 2, "balance": 100, "banker": true }]"#;
+    let report = admin
+        .import_balances(&ImportQuery::default(), ImportFormat::Json, data)
+        .await
+        .unwrap();
+    assert!(report.applied);
+    let alice = get_or_create_economy_state(ALICE, &conn).await.unwrap();
+    assert_eq!(alice.balance, 100);
+    assert!(alice.banker);
+
+    // the granted banker role is audited like one granted on its own
+    let filter = AuditLogFilter {
+        target: Some(format!("user:{}", ALICE)),
+        ..Default::default()
+    };
+    let entries = find_audit_entries(filter, 10, 0, &conn).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditAction::GrantBanker);
+    assert_eq!(entries[0].actor_id, ADMIN);
+    assert_eq!(entries[0].before, Some(json!({ "banker": false })));
+    assert_eq!(entries[0].after, Some(json!({ "banker": true })));
+
+    let _ = std::fs::remove_file(&db_path);
+}
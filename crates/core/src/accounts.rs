@@ -0,0 +1,320 @@
+use chrono::Utc;
+use economy_service_entity::{
+    account::{self, AccountKind},
+    account_member::{self, AccountRole},
+    account_role_limit, transaction,
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::{DbResult, EconomyError, EconomyResult, TransferLimits};
+
+pub struct CreateAccountForm {
+    pub kind: AccountKind,
+    pub name: String,
+    pub owner_id: i32,
+}
+
+/// Creates an account with the given user as its only owner
+pub async fn create_account<C: ConnectionTrait + TransactionTrait>(
+    form: CreateAccountForm,
+    conn: &C,
+) -> DbResult<account::Model> {
+    let txn = conn.begin().await?;
+
+    let now = Utc::now();
+    let account = account::ActiveModel {
+        kind: Set(form.kind),
+        name: Set(form.name),
+        balance: Set(0),
+        created_by: Set(form.owner_id),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    account_member::ActiveModel {
+        account_id: Set(account.id),
+        user_id: Set(form.owner_id),
+        role: Set(AccountRole::Owner),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(account)
+}
+
+pub async fn get_account<C: ConnectionTrait>(
+    account_id: i32,
+    conn: &C,
+) -> DbResult<Option<account::Model>> {
+    account::Entity::find_by_id(account_id).one(conn).await
+}
+
+/// Returns membership of the user in the account, if they are a member
+pub async fn get_account_member<C: ConnectionTrait>(
+    account_id: i32,
+    user_id: i32,
+    conn: &C,
+) -> DbResult<Option<account_member::Model>> {
+    account_member::Entity::find()
+        .filter(account_member::Column::AccountId.eq(account_id))
+        .filter(account_member::Column::UserId.eq(user_id))
+        .one(conn)
+        .await
+}
+
+/// Returns members of the account, oldest first
+pub async fn get_account_members<C: ConnectionTrait>(
+    account_id: i32,
+    conn: &C,
+) -> DbResult<Vec<account_member::Model>> {
+    account_member::Entity::find()
+        .filter(account_member::Column::AccountId.eq(account_id))
+        .order_by_asc(account_member::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Returns accounts the user is a member of along with the membership
+pub async fn get_user_accounts(
+    user_id: i32,
+    conn: &DbConn,
+) -> DbResult<Vec<(account_member::Model, account::Model)>> {
+    let memberships = account_member::Entity::find()
+        .filter(account_member::Column::UserId.eq(user_id))
+        .find_also_related(account::Entity)
+        .order_by_asc(account_member::Column::AccountId)
+        .all(conn)
+        .await?;
+
+    Ok(memberships
+        .into_iter()
+        .filter_map(|(member, account)| account.map(|account| (member, account)))
+        .collect())
+}
+
+/// Roles allowed to spend money of an account
+pub const SPENDING_ROLES: [AccountRole; 3] = [
+    AccountRole::Owner,
+    AccountRole::Manager,
+    AccountRole::Treasurer,
+];
+
+/// Locks the account's row for the rest of the database transaction.
+/// Changes of its members and payments from it lock it too, so that they wait for each other.
+async fn lock_account<C: ConnectionTrait>(account_id: i32, conn: &C) -> EconomyResult<()> {
+    match add_to_account_balance(account_id, 0, None, conn).await? {
+        Some(_) => Ok(()),
+        None => Err(EconomyError::AccountNotFound),
+    }
+}
+
+/// Fails with [`EconomyError::LastOwner`] unless the account has owners other than the user
+async fn check_other_owners<C: ConnectionTrait>(
+    account_id: i32,
+    user_id: i32,
+    conn: &C,
+) -> EconomyResult<()> {
+    let other_owners = account_member::Entity::find()
+        .filter(account_member::Column::AccountId.eq(account_id))
+        .filter(account_member::Column::Role.eq(AccountRole::Owner))
+        .filter(account_member::Column::UserId.ne(user_id))
+        .count(conn)
+        .await?;
+
+    match other_owners {
+        0 => Err(EconomyError::LastOwner),
+        _ => Ok(()),
+    }
+}
+
+/// Adds the user to the account or changes their role if they are a member already.
+/// Fails with [`EconomyError::LastOwner`] if it would leave the account without owners.
+pub async fn set_account_member<C: ConnectionTrait + TransactionTrait>(
+    account_id: i32,
+    user_id: i32,
+    role: AccountRole,
+    conn: &C,
+) -> EconomyResult<account_member::Model> {
+    let txn = conn.begin().await?;
+
+    lock_account(account_id, &txn).await?;
+    if role != AccountRole::Owner {
+        check_other_owners(account_id, user_id, &txn).await?;
+    }
+
+    let member = match get_account_member(account_id, user_id, &txn).await? {
+        Some(member) => {
+            let mut member: account_member::ActiveModel = member.into();
+            member.role = Set(role);
+            member.update(&txn).await?
+        }
+        None => {
+            account_member::ActiveModel {
+                account_id: Set(account_id),
+                user_id: Set(user_id),
+                role: Set(role),
+                created_at: Set(Utc::now()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+
+    txn.commit().await?;
+    Ok(member)
+}
+
+/// Removes the user from the account. Returns `false` if they weren't a member.
+/// Fails with [`EconomyError::LastOwner`] if it would leave the account without owners.
+pub async fn remove_account_member<C: ConnectionTrait + TransactionTrait>(
+    account_id: i32,
+    user_id: i32,
+    conn: &C,
+) -> EconomyResult<bool> {
+    let txn = conn.begin().await?;
+
+    lock_account(account_id, &txn).await?;
+    check_other_owners(account_id, user_id, &txn).await?;
+
+    let removed = account_member::Entity::delete_many()
+        .filter(account_member::Column::AccountId.eq(account_id))
+        .filter(account_member::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?
+        .rows_affected
+        > 0;
+
+    txn.commit().await?;
+    Ok(removed)
+}
+
+/// Fetches membership of the user in the account, failing with
+/// [`EconomyError::MissingAccountRole`] unless they can spend its money.
+/// Run with the account locked, so that the role can't be changed until the payment is made.
+pub(crate) async fn check_spending_role<C: ConnectionTrait>(
+    account_id: i32,
+    user_id: i32,
+    conn: &C,
+) -> EconomyResult<account_member::Model> {
+    match get_account_member(account_id, user_id, conn).await? {
+        Some(member) if SPENDING_ROLES.contains(&member.role) => Ok(member),
+        _ => Err(EconomyError::MissingAccountRole),
+    }
+}
+
+/// Returns limits of the role in the account, unlimited if none were set
+pub async fn get_account_role_limit<C: ConnectionTrait>(
+    account_id: i32,
+    role: AccountRole,
+    conn: &C,
+) -> DbResult<TransferLimits> {
+    let limit = account_role_limit::Entity::find()
+        .filter(account_role_limit::Column::AccountId.eq(account_id))
+        .filter(account_role_limit::Column::Role.eq(role))
+        .one(conn)
+        .await?;
+
+    Ok(
+        limit.map_or_else(TransferLimits::default, |limit| TransferLimits {
+            per_transaction: limit.per_transaction,
+            daily: limit.daily,
+            window: None,
+        }),
+    )
+}
+
+/// Returns limits set for roles of the account
+pub async fn get_account_role_limits<C: ConnectionTrait>(
+    account_id: i32,
+    conn: &C,
+) -> DbResult<Vec<account_role_limit::Model>> {
+    account_role_limit::Entity::find()
+        .filter(account_role_limit::Column::AccountId.eq(account_id))
+        .order_by_asc(account_role_limit::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Sets limits of the role in the account, replacing the previous ones
+pub async fn set_account_role_limit<C: ConnectionTrait>(
+    account_id: i32,
+    role: AccountRole,
+    per_transaction: Option<i32>,
+    daily: Option<i32>,
+    conn: &C,
+) -> DbResult<account_role_limit::Model> {
+    let limit = account_role_limit::Entity::find()
+        .filter(account_role_limit::Column::AccountId.eq(account_id))
+        .filter(account_role_limit::Column::Role.eq(role))
+        .one(conn)
+        .await?;
+
+    let mut limit: account_role_limit::ActiveModel = match limit {
+        Some(limit) => limit.into(),
+        None => account_role_limit::ActiveModel {
+            account_id: Set(account_id),
+            role: Set(role),
+            ..Default::default()
+        },
+    };
+    limit.per_transaction = Set(per_transaction);
+    limit.daily = Set(daily);
+    limit.save(conn).await?.try_into_model()
+}
+
+/// Returns transactions the account took part in with IDs less than `before_id`, newest first
+pub async fn get_account_transactions(
+    account_id: i32,
+    before_id: Option<i32>,
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<transaction::Model>> {
+    let mut query = transaction::Entity::find().filter(
+        Condition::any()
+            .add(transaction::Column::PayerAccountId.eq(account_id))
+            .add(transaction::Column::PayeeAccountId.eq(account_id)),
+    );
+    if let Some(before_id) = before_id {
+        query = query.filter(transaction::Column::Id.lt(before_id));
+    }
+
+    query
+        .order_by_desc(transaction::Column::Id)
+        .limit(limit)
+        .all(conn)
+        .await
+}
+
+/// Adds `delta` to the account's balance and returns the new balance.
+/// If `min_balance` is given, the balance is left untouched and `None` is returned
+/// when it would end up below it.
+pub(crate) async fn add_to_account_balance<C: ConnectionTrait>(
+    account_id: i32,
+    delta: i32,
+    min_balance: Option<i32>,
+    conn: &C,
+) -> DbResult<Option<i32>> {
+    let mut query = account::Entity::update_many()
+        .col_expr(
+            account::Column::Balance,
+            Expr::col(account::Column::Balance).add(delta),
+        )
+        .filter(account::Column::Id.eq(account_id));
+    if let Some(min_balance) = min_balance {
+        query = query.filter(account::Column::Balance.gte(min_balance - delta));
+    }
+
+    if query.exec(conn).await?.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    get_account(account_id, conn)
+        .await
+        .map(|account| account.map(|account| account.balance))
+}
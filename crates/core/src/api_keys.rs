@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Utc};
+use economy_service_entity::api_key::{self, ApiKeyScopes};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::DbResult;
+
+pub struct CreateApiKeyForm {
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub created_by: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub async fn create_api_key<C: ConnectionTrait>(
+    form: CreateApiKeyForm,
+    conn: &C,
+) -> DbResult<api_key::Model> {
+    api_key::ActiveModel {
+        name: Set(form.name),
+        prefix: Set(form.prefix),
+        key_hash: Set(form.key_hash),
+        scopes: Set(ApiKeyScopes(form.scopes)),
+        created_by: Set(form.created_by),
+        created_at: Set(Utc::now()),
+        expires_at: Set(form.expires_at),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+/// Returns all API keys, including revoked and expired ones
+pub async fn get_api_keys(conn: &DbConn) -> DbResult<Vec<api_key::Model>> {
+    api_key::Entity::find()
+        .order_by_asc(api_key::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Returns the key with the hash, unless it is revoked or expired
+pub async fn get_active_api_key(key_hash: &str, conn: &DbConn) -> DbResult<Option<api_key::Model>> {
+    api_key::Entity::find()
+        .filter(api_key::Column::KeyHash.eq(key_hash))
+        .filter(api_key::Column::RevokedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(api_key::Column::ExpiresAt.is_null())
+                .add(api_key::Column::ExpiresAt.gt(Utc::now())),
+        )
+        .one(conn)
+        .await
+}
+
+/// Records that the key was used, unless it was already recorded within the last minute,
+/// so that busy keys don't cause a write on every request
+pub async fn touch_api_key(id: i32, conn: &DbConn) -> DbResult<()> {
+    let now = Utc::now();
+    api_key::Entity::update_many()
+        .col_expr(api_key::Column::LastUsedAt, Expr::value(now))
+        .filter(api_key::Column::Id.eq(id))
+        .filter(
+            Condition::any()
+                .add(api_key::Column::LastUsedAt.is_null())
+                .add(api_key::Column::LastUsedAt.lt(now - Duration::minutes(1))),
+        )
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
+
+/// Key with the ID, whether it's active or not
+pub(crate) async fn get_api_key<C: ConnectionTrait>(
+    id: i32,
+    conn: &C,
+) -> DbResult<Option<api_key::Model>> {
+    api_key::Entity::find_by_id(id).one(conn).await
+}
+
+/// Revokes the key. Returns the key as it was before, if it existed.
+/// Revoked keys are kept, so that operations performed with them can still be told apart.
+pub async fn revoke_api_key<C: ConnectionTrait>(
+    id: i32,
+    conn: &C,
+) -> DbResult<Option<api_key::Model>> {
+    let key = get_api_key(id, conn).await?;
+
+    if matches!(&key, Some(key) if key.revoked_at.is_none()) {
+        api_key::Entity::update_many()
+            .col_expr(api_key::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(api_key::Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+    }
+
+    Ok(key)
+}
@@ -0,0 +1,159 @@
+use chrono::{Duration, Utc};
+use economy_service_entity::{
+    audit_log_entry::AuditAction,
+    proposal::{self, ProposalKind},
+    transaction::{self, TransactionKind},
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::{
+    create_proposal, record_audit_entry, AuditContext, AuditEntryForm, CreateProposalForm, DbResult,
+};
+
+/// Thresholds over which operations have to be approved by several users before they're executed
+#[derive(Clone, Copy, Debug)]
+pub struct ApprovalPolicy {
+    /// Absolute amount of mints and burns a banker can make within the window without approval
+    pub mint_threshold: Option<i32>,
+
+    /// Amount a member can pay from an organization account within the window without approval
+    pub treasury_payment_threshold: Option<i32>,
+
+    /// Amount of distinct approvers needed, not counting the proposer
+    pub required: i32,
+
+    /// Time after which unapproved proposals expire
+    pub expiry: Duration,
+
+    /// Length of the rolling window amounts add up over
+    pub window: Duration,
+}
+impl ApprovalPolicy {
+    /// Whether the amount requires approval given what the user already minted or paid
+    /// within the window, as returned by [`get_approval_usage`]
+    pub fn requires_approval(&self, kind: ProposalKind, used: i64, amount: i64) -> bool {
+        let threshold = match kind {
+            ProposalKind::Mint => self.mint_threshold,
+            ProposalKind::TreasuryPayment => self.treasury_payment_threshold,
+        };
+
+        matches!(threshold, Some(threshold) if used + amount.abs() > threshold as i64)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum QueryAs {
+    Total,
+}
+
+/// Sums up money the banker minted and burned, or the member paid from the account,
+/// within the window of the policy. Executed proposals count too.
+pub async fn get_approval_usage<C: ConnectionTrait>(
+    policy: &ApprovalPolicy,
+    kind: ProposalKind,
+    user_id: i32,
+    account_id: Option<i32>,
+    conn: &C,
+) -> DbResult<i64> {
+    let operations = match kind {
+        ProposalKind::Mint => Condition::all()
+            .add(transaction::Column::Kind.is_in([TransactionKind::Mint, TransactionKind::Burn])),
+        ProposalKind::TreasuryPayment => Condition::all()
+            .add(transaction::Column::Kind.eq(TransactionKind::Payment))
+            .add(transaction::Column::PayerAccountId.eq(account_id)),
+    };
+
+    sum_approval_usage(
+        policy,
+        operations.add(transaction::Column::InitiatorId.eq(user_id)),
+        conn,
+    )
+    .await
+}
+
+/// Sums up money paid from the account with the API key within the window of the policy.
+/// Keys pay without an initiator, so their payments add up on their own.
+pub async fn get_api_key_approval_usage<C: ConnectionTrait>(
+    policy: &ApprovalPolicy,
+    api_key_id: i32,
+    account_id: i32,
+    conn: &C,
+) -> DbResult<i64> {
+    let operations = Condition::all()
+        .add(transaction::Column::Kind.eq(TransactionKind::Payment))
+        .add(transaction::Column::PayerAccountId.eq(account_id))
+        .add(transaction::Column::ApiKeyId.eq(api_key_id));
+
+    sum_approval_usage(policy, operations, conn).await
+}
+
+async fn sum_approval_usage<C: ConnectionTrait>(
+    policy: &ApprovalPolicy,
+    operations: Condition,
+    conn: &C,
+) -> DbResult<i64> {
+    let total: Option<Option<i64>> = transaction::Entity::find()
+        .select_only()
+        .column_as(Expr::col(transaction::Column::Amount).sum(), QueryAs::Total)
+        .filter(operations)
+        .filter(transaction::Column::CreatedAt.gte(Utc::now() - policy.window))
+        .into_values::<_, QueryAs>()
+        .one(conn)
+        .await?;
+
+    Ok(total.flatten().unwrap_or(0))
+}
+
+/// Result of an operation that may require approval
+#[derive(Debug)]
+pub enum Approval<T> {
+    /// Operation was executed right away
+    Executed(T),
+
+    /// Operation awaits approval
+    Proposed(proposal::Model),
+}
+
+/// Proposes the operation and records it in the audit log, all in one database transaction
+pub async fn propose<C: ConnectionTrait + TransactionTrait>(
+    policy: &ApprovalPolicy,
+    kind: ProposalKind,
+    proposer_id: i32,
+    account_id: Option<i32>,
+    payload: JsonValue,
+    ctx: &AuditContext,
+    conn: &C,
+) -> DbResult<proposal::Model> {
+    let txn = conn.begin().await?;
+
+    let proposal = create_proposal(
+        CreateProposalForm {
+            kind,
+            proposer_id,
+            account_id,
+            payload,
+            required_approvals: policy.required,
+            expires_at: Utc::now() + policy.expiry,
+        },
+        &txn,
+    )
+    .await?;
+
+    let after = serde_json::to_value(&proposal).map_err(|err| DbErr::Custom(err.to_string()))?;
+    record_audit_entry(
+        AuditEntryForm {
+            after: Some(after),
+            ..ctx.entry(
+                proposer_id,
+                AuditAction::CreateProposal,
+                format!("proposal:{}", proposal.id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(proposal)
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::audit_log_entry::{self, AuditAction};
+use sea_orm::*;
+
+use crate::DbResult;
+
+#[derive(Clone)]
+pub struct AuditEntryForm {
+    pub actor_id: i32,
+    pub api_key_id: Option<i32>,
+    pub action: AuditAction,
+    pub target: String,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+    pub reason: Option<String>,
+    pub request_id: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+/// Performer of an operation: a user, or an API key acting on behalf of the admin who created it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Actor {
+    User(i32),
+    ApiKey { id: i32, created_by: i32 },
+}
+impl Actor {
+    /// User the operation is recorded under in the audit log
+    pub fn user_id(self) -> i32 {
+        match self {
+            Actor::User(id) => id,
+            Actor::ApiKey { created_by, .. } => created_by,
+        }
+    }
+
+    /// Key the operation was performed with
+    pub fn api_key_id(self) -> Option<i32> {
+        match self {
+            Actor::User(_) => None,
+            Actor::ApiKey { id, .. } => Some(id),
+        }
+    }
+}
+
+/// Request details recorded along with privileged operations
+#[derive(Clone, Debug, Default)]
+pub struct AuditContext {
+    pub request_id: Option<String>,
+    pub client_ip: Option<String>,
+}
+impl AuditContext {
+    /// Starts an audit log entry of an operation performed within the request
+    pub fn entry(&self, actor_id: i32, action: AuditAction, target: String) -> AuditEntryForm {
+        AuditEntryForm {
+            actor_id,
+            action,
+            target,
+            before: None,
+            after: None,
+            reason: None,
+            request_id: self.request_id.clone(),
+            client_ip: self.client_ip.clone(),
+            api_key_id: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<i32>,
+    pub target: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Appends an entry to the audit log.
+/// Should be called with the same database transaction as the recorded operation.
+pub async fn record_audit_entry<C: ConnectionTrait>(
+    form: AuditEntryForm,
+    conn: &C,
+) -> DbResult<audit_log_entry::Model> {
+    audit_log_entry::ActiveModel {
+        actor_id: Set(form.actor_id),
+        api_key_id: Set(form.api_key_id),
+        action: Set(form.action),
+        target: Set(form.target),
+        before: Set(form.before),
+        after: Set(form.after),
+        reason: Set(form.reason),
+        request_id: Set(form.request_id),
+        client_ip: Set(form.client_ip),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+/// Returns audit log entries matching the filter, newest first
+pub async fn find_audit_entries(
+    filter: AuditLogFilter,
+    limit: u64,
+    offset: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<audit_log_entry::Model>> {
+    let mut query = audit_log_entry::Entity::find();
+
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(audit_log_entry::Column::ActorId.eq(actor_id));
+    }
+    if let Some(target) = filter.target {
+        query = query.filter(audit_log_entry::Column::Target.eq(target));
+    }
+    if let Some(from) = filter.from {
+        query = query.filter(audit_log_entry::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = filter.to {
+        query = query.filter(audit_log_entry::Column::CreatedAt.lt(to));
+    }
+
+    query
+        .order_by_desc(audit_log_entry::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(conn)
+        .await
+}
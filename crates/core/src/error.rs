@@ -0,0 +1,121 @@
+use std::fmt;
+
+use sea_orm::DbErr;
+
+use crate::TransferHeadroom;
+
+/// Failure of an economy operation, either because the economy doesn't allow it
+/// or because the database failed
+#[derive(Debug)]
+pub enum EconomyError {
+    /// Amount isn't positive
+    InvalidAmount,
+
+    /// Payer and payee are the same
+    SelfPayment,
+
+    /// Account taking part in the operation doesn't exist
+    AccountNotFound,
+
+    /// Performer of the operation isn't a banker
+    MissingBankerRole,
+
+    /// User doesn't have an account role allowed to perform the operation
+    MissingAccountRole,
+
+    /// Account would be left without owners
+    LastOwner,
+
+    /// Operation requires approval, which can't be proposed by its performer
+    ApprovalRequired,
+
+    /// Payer doesn't have enough money at the time of the operation
+    InsufficientFunds {
+        /// Balance the payer has
+        available: i64,
+    },
+
+    /// Account of the paying user is frozen
+    AccountFrozen,
+
+    /// Account of the receiving user is frozen and blocks incoming money
+    PayeeFrozen,
+
+    /// Payment exceeds the tightest transfer limit of the payer
+    TransferLimitExceeded(TransferHeadroom),
+
+    /// Spending of the account exceeds the tightest limit of the member's role
+    RoleLimitExceeded(TransferHeadroom),
+
+    /// Amount exceeds the part of the payment that isn't reversed yet
+    ReversalExceedsRemaining {
+        /// Part of the payment that can still be reversed
+        remaining: i64,
+    },
+
+    /// Payment is reversed fully already
+    AlreadyReversed,
+
+    /// Transaction isn't a payment between two parties
+    NotReversible,
+
+    /// Opening balance can't be imported, as the user already holds money
+    AlreadyFunded { user_id: i32 },
+
+    /// Database failed to run the operation
+    Database(DbErr),
+}
+impl fmt::Display for EconomyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EconomyError::InvalidAmount => f.write_str("amount should be more than 0"),
+            EconomyError::SelfPayment => f.write_str("payer is payee"),
+            EconomyError::AccountNotFound => f.write_str("account not found"),
+            EconomyError::MissingBankerRole => f.write_str("missing banker role"),
+            EconomyError::MissingAccountRole => f.write_str("missing account role"),
+            EconomyError::LastOwner => f.write_str("account should have at least one owner"),
+            EconomyError::ApprovalRequired => f.write_str("operation requires approval"),
+            EconomyError::InsufficientFunds { available } => {
+                write!(f, "insufficient funds, {} available", available)
+            }
+            EconomyError::AccountFrozen => f.write_str("account is frozen"),
+            EconomyError::PayeeFrozen => f.write_str("payee account is frozen"),
+            EconomyError::TransferLimitExceeded(headroom) => write!(
+                f,
+                "transfer exceeds {} limit, {} remaining",
+                headroom.kind, headroom.remaining
+            ),
+            EconomyError::RoleLimitExceeded(headroom) => write!(
+                f,
+                "transfer exceeds {} limit of the role, {} remaining",
+                headroom.kind, headroom.remaining
+            ),
+            EconomyError::ReversalExceedsRemaining { remaining } => write!(
+                f,
+                "amount exceeds the unreversed part of the payment, {} remaining",
+                remaining
+            ),
+            EconomyError::AlreadyReversed => f.write_str("payment is already fully reversed"),
+            EconomyError::NotReversible => f.write_str("transaction is not a payment"),
+            EconomyError::AlreadyFunded { user_id } => {
+                write!(f, "user {} already has a balance", user_id)
+            }
+            EconomyError::Database(err) => write!(f, "database error: {}", err),
+        }
+    }
+}
+impl std::error::Error for EconomyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EconomyError::Database(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+impl From<DbErr> for EconomyError {
+    fn from(err: DbErr) -> Self {
+        EconomyError::Database(err)
+    }
+}
+
+pub type EconomyResult<T> = Result<T, EconomyError>;
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::{economy_state, transaction};
+use futures::{Stream, StreamExt};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
+use utoipa::ToSchema;
+
+use crate::{stream_economy_states, stream_transactions, DbResult};
+
+/// Amount of rows fetched and written at once
+const CHUNK_SIZE: usize = 500;
+
+pub type ExportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Format of exported data
+#[derive(Clone, Copy, Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    #[default]
+    Csv,
+
+    /// Newline-delimited JSON
+    Ndjson,
+}
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    /// Writes the rows, starting with a header row if the format has one and `first` is set
+    fn write<T: Serialize>(
+        self,
+        rows: impl IntoIterator<Item = T>,
+        first: bool,
+    ) -> Result<Vec<u8>, ExportError> {
+        match self {
+            ExportFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(first)
+                    .from_writer(vec![]);
+                for row in rows {
+                    writer.serialize(row)?;
+                }
+                Ok(writer.into_inner().map_err(|err| err.into_error())?)
+            }
+            ExportFormat::Ndjson => {
+                let mut buf = vec![];
+                for row in rows {
+                    serde_json::to_writer(&mut buf, &row)?;
+                    buf.push(b'\n');
+                }
+                Ok(buf)
+            }
+        }
+    }
+}
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            _ => Err(format!(
+                "unknown format `{}`, expected `csv` or `ndjson`",
+                s
+            )),
+        }
+    }
+}
+
+/// Usernames of the users appearing in an export, resolved before it starts
+pub type Usernames = HashMap<i32, String>;
+
+#[derive(FromQueryResult)]
+struct UserIdRow {
+    user_id: i32,
+}
+
+#[derive(FromQueryResult)]
+struct PartiesRow {
+    payer_id: Option<i32>,
+    payee_id: Option<i32>,
+}
+
+/// Returns IDs of the users whose balances are exported
+pub async fn get_balances_export_user_ids(conn: &DbConn) -> DbResult<Vec<i32>> {
+    let rows = economy_state::Entity::find()
+        .select_only()
+        .column(economy_state::Column::UserId)
+        .into_model::<UserIdRow>()
+        .all(conn)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.user_id).collect())
+}
+
+/// Returns IDs of the users paying or paid in the transactions exported within the time range
+pub async fn get_transactions_export_user_ids(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    conn: &DbConn,
+) -> DbResult<Vec<i32>> {
+    let mut query = transaction::Entity::find()
+        .select_only()
+        .column(transaction::Column::PayerId)
+        .column(transaction::Column::PayeeId)
+        .distinct();
+    if let Some(from) = from {
+        query = query.filter(transaction::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(transaction::Column::CreatedAt.lt(to));
+    }
+
+    let rows = query.into_model::<PartiesRow>().all(conn).await?;
+    let mut ids: Vec<i32> = rows
+        .into_iter()
+        .flat_map(|row| [row.payer_id, row.payee_id])
+        .flatten()
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+fn username(usernames: &Usernames, id: Option<i32>) -> Option<String> {
+    id.and_then(|id| usernames.get(&id).cloned())
+}
+
+/// Row of balances export
+#[derive(Serialize)]
+struct BalanceRow {
+    user_id: i32,
+    username: Option<String>,
+    balance: i32,
+    banker: bool,
+    tier: economy_state::AccountTier,
+}
+
+/// Row of transactions export
+#[derive(Serialize)]
+struct TransactionRow {
+    id: i32,
+    created_at: DateTime<Utc>,
+    kind: transaction::TransactionKind,
+    payer_id: Option<i32>,
+    payer_username: Option<String>,
+    payee_id: Option<i32>,
+    payee_username: Option<String>,
+    payer_account_id: Option<i32>,
+    payee_account_id: Option<i32>,
+    initiator_id: Option<i32>,
+    reverses_id: Option<i32>,
+    amount: i32,
+    comment: Option<String>,
+}
+
+/// Streams balances of all users in chunks, without loading them all at once.
+/// Usernames should be resolved beforehand, as users missing from them are exported without one.
+pub fn export_balances(
+    conn: DbConn,
+    usernames: Usernames,
+    format: ExportFormat,
+) -> impl Stream<Item = Result<Vec<u8>, ExportError>> {
+    async_stream::try_stream! {
+        let mut chunks = stream_economy_states(&conn).await?.chunks(CHUNK_SIZE).boxed();
+
+        let mut first = true;
+        while let Some(chunk) = chunks.next().await {
+            let states = chunk.into_iter().collect::<Result<Vec<_>, _>>()?;
+            let rows = states.into_iter().map(|state| BalanceRow {
+                user_id: state.user_id,
+                username: username(&usernames, Some(state.user_id)),
+                balance: state.balance,
+                banker: state.banker,
+                tier: state.tier,
+            });
+            yield format.write(rows, first)?;
+            first = false;
+        }
+    }
+}
+
+/// Streams transactions created within the time range in chunks, without loading them all at once.
+/// Usernames should be resolved beforehand, like for [`export_balances`].
+pub fn export_transactions(
+    conn: DbConn,
+    usernames: Usernames,
+    format: ExportFormat,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> impl Stream<Item = Result<Vec<u8>, ExportError>> {
+    async_stream::try_stream! {
+        let mut chunks = stream_transactions(from, to, &conn).await?.chunks(CHUNK_SIZE).boxed();
+
+        let mut first = true;
+        while let Some(chunk) = chunks.next().await {
+            let records = chunk.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+            let rows = records.into_iter().map(|record| TransactionRow {
+                id: record.id,
+                created_at: record.created_at,
+                kind: record.kind,
+                payer_username: username(&usernames, record.payer_id),
+                payer_id: record.payer_id,
+                payee_username: username(&usernames, record.payee_id),
+                payee_id: record.payee_id,
+                payer_account_id: record.payer_account_id,
+                payee_account_id: record.payee_account_id,
+                initiator_id: record.initiator_id,
+                reverses_id: record.reverses_id,
+                amount: record.amount,
+                comment: record.comment,
+            });
+            yield format.write(rows, first)?;
+            first = false;
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::account_freeze;
+use sea_orm::{sea_query::Condition, *};
+
+use crate::{add_to_balance, DbResult};
+
+/// Amount of user IDs looked up with a single query
+const LOOKUP_CHUNK_SIZE: usize = 500;
+
+pub struct FreezeAccountForm {
+    pub reason: String,
+    pub actor_id: i32,
+    pub block_incoming: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Matches freezes that haven't expired yet
+pub(crate) fn get_active_freeze_condition() -> Condition {
+    Condition::any()
+        .add(account_freeze::Column::ExpiresAt.is_null())
+        .add(account_freeze::Column::ExpiresAt.gt(Utc::now()))
+}
+
+/// Returns the freeze of the user's account, unless there is none or it has expired
+pub async fn get_active_freeze<C: ConnectionTrait>(
+    user_id: i32,
+    conn: &C,
+) -> DbResult<Option<account_freeze::Model>> {
+    account_freeze::Entity::find()
+        .filter(account_freeze::Column::UserId.eq(user_id))
+        .filter(get_active_freeze_condition())
+        .one(conn)
+        .await
+}
+
+/// Returns freezes of the users' accounts that haven't expired yet
+pub async fn get_active_freezes<C: ConnectionTrait>(
+    user_ids: &[i32],
+    conn: &C,
+) -> DbResult<Vec<account_freeze::Model>> {
+    let mut freezes = vec![];
+    for chunk in user_ids.chunks(LOOKUP_CHUNK_SIZE) {
+        freezes.extend(
+            account_freeze::Entity::find()
+                .filter(account_freeze::Column::UserId.is_in(chunk.iter().copied()))
+                .filter(get_active_freeze_condition())
+                .all(conn)
+                .await?,
+        );
+    }
+    Ok(freezes)
+}
+
+/// Freezes the user's account, replacing its previous freeze if there is one
+pub async fn freeze_account<C: ConnectionTrait + TransactionTrait>(
+    user_id: i32,
+    form: FreezeAccountForm,
+    conn: &C,
+) -> DbResult<account_freeze::Model> {
+    let txn = conn.begin().await?;
+
+    // lock the balance, so that transfers of the user either see the freeze or finish before it
+    add_to_balance(user_id, 0, None, &txn).await?;
+
+    account_freeze::Entity::delete_many()
+        .filter(account_freeze::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    let freeze = account_freeze::ActiveModel {
+        user_id: Set(user_id),
+        reason: Set(form.reason),
+        actor_id: Set(form.actor_id),
+        block_incoming: Set(form.block_incoming),
+        created_at: Set(Utc::now()),
+        expires_at: Set(form.expires_at),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(freeze)
+}
+
+/// Removes the freeze of the user's account. Returns the removed freeze if it was active.
+pub async fn unfreeze_account<C: ConnectionTrait>(
+    user_id: i32,
+    conn: &C,
+) -> DbResult<Option<account_freeze::Model>> {
+    let active = get_active_freeze(user_id, conn).await?;
+
+    account_freeze::Entity::delete_many()
+        .filter(account_freeze::Column::UserId.eq(user_id))
+        .exec(conn)
+        .await?;
+
+    Ok(active)
+}
@@ -0,0 +1,107 @@
+use chrono::Utc;
+use economy_service_entity::{
+    economy_state,
+    transaction::{self, TransactionKind},
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::{get_or_create_economy_state, DbResult, EconomyError, EconomyResult};
+
+/// Amount of user IDs looked up with a single query
+const LOOKUP_CHUNK_SIZE: usize = 500;
+
+/// Opening balance of a user, loaded by an import
+#[derive(Clone, Debug)]
+pub struct ImportEntry {
+    pub user_id: i32,
+    pub balance: i32,
+    pub banker: Option<bool>,
+}
+
+/// Banker role of a user changed by an import
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BankerChange {
+    pub user_id: i32,
+    pub before: bool,
+    pub after: bool,
+}
+
+/// Returns economy states of the users that already hold money
+pub async fn get_funded_economy_states(
+    user_ids: &[i32],
+    conn: &DbConn,
+) -> DbResult<Vec<economy_state::Model>> {
+    let mut states = vec![];
+    for chunk in user_ids.chunks(LOOKUP_CHUNK_SIZE) {
+        states.extend(
+            economy_state::Entity::find()
+                .filter(economy_state::Column::UserId.is_in(chunk.iter().copied()))
+                .filter(economy_state::Column::Balance.ne(0))
+                .all(conn)
+                .await?,
+        );
+    }
+    Ok(states)
+}
+
+/// Credits opening balances and records them as imports, all in one database transaction.
+/// Fails with [`EconomyError::AlreadyFunded`] if any of the users holds money by then.
+/// Returns the banker roles the import changed, leaving out the ones that were set already.
+pub async fn import_balances<C: ConnectionTrait + TransactionTrait>(
+    entries: &[ImportEntry],
+    conn: &C,
+) -> EconomyResult<Vec<BankerChange>> {
+    let txn = conn.begin().await?;
+
+    let mut banker_changes = vec![];
+
+    for entry in entries {
+        get_or_create_economy_state(entry.user_id, &txn).await?;
+
+        // credited only while the balance is empty, which locks the row until the import is done
+        let credited = economy_state::Entity::update_many()
+            .col_expr(
+                economy_state::Column::Balance,
+                Expr::col(economy_state::Column::Balance).add(entry.balance),
+            )
+            .filter(economy_state::Column::UserId.eq(entry.user_id))
+            .filter(economy_state::Column::Balance.eq(0))
+            .exec(&txn)
+            .await?;
+        if credited.rows_affected == 0 {
+            return Err(EconomyError::AlreadyFunded {
+                user_id: entry.user_id,
+            });
+        }
+
+        let state = get_or_create_economy_state(entry.user_id, &txn).await?;
+        if let Some(banker) = entry.banker.filter(|&banker| banker != state.banker) {
+            banker_changes.push(BankerChange {
+                user_id: entry.user_id,
+                before: state.banker,
+                after: banker,
+            });
+            let mut state: economy_state::ActiveModel = state.clone().into();
+            state.banker = Set(banker);
+            state.update(&txn).await?;
+        }
+
+        if entry.balance == 0 {
+            continue;
+        }
+
+        transaction::ActiveModel {
+            kind: Set(TransactionKind::Import),
+            payee_id: Set(Some(entry.user_id)),
+            payee_balance: Set(Some(state.balance)),
+            amount: Set(entry.balance),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+
+    txn.commit().await?;
+    Ok(banker_changes)
+}
@@ -1,4 +1,40 @@
-use economy_service_entity::economy_state;
+mod accounts;
+mod api_keys;
+mod approvals;
+mod audit;
+mod error;
+mod export;
+mod freezes;
+mod imports;
+mod limits;
+mod loans;
+mod proposals;
+mod reconciliation;
+mod stats;
+mod transactions;
+mod webhooks;
+
+pub use accounts::*;
+pub use api_keys::*;
+pub use approvals::*;
+pub use audit::*;
+pub use error::*;
+pub use export::*;
+pub use freezes::*;
+pub use imports::*;
+pub use limits::*;
+pub use loans::*;
+pub use proposals::*;
+pub use reconciliation::*;
+pub use stats::*;
+pub use transactions::*;
+pub use webhooks::*;
+
+use economy_service_entity::{
+    account_freeze,
+    economy_state::{self, AccountTier},
+};
+use futures::Stream;
 use sea_orm::*;
 
 type DbResult<T> = Result<T, DbErr>;
@@ -7,11 +43,26 @@ type DbResult<T> = Result<T, DbErr>;
 pub struct UpdateEconomyStateForm {
     pub balance: Option<i32>,
     pub banker: Option<bool>,
+    pub tier: Option<AccountTier>,
+    pub limit_per_transaction: Option<Option<i32>>,
+    pub limit_daily: Option<Option<i32>>,
+    pub limit_window: Option<Option<i32>>,
 }
 
-pub async fn get_or_create_economy_state(
+/// Returns economy state of the user without creating it, unlike [`get_or_create_economy_state`]
+pub async fn get_economy_state<C: ConnectionTrait>(
     user_id: i32,
-    conn: &DbConn,
+    conn: &C,
+) -> DbResult<Option<economy_state::Model>> {
+    economy_state::Entity::find()
+        .filter(economy_state::Column::UserId.eq(user_id))
+        .one(conn)
+        .await
+}
+
+pub async fn get_or_create_economy_state<C: ConnectionTrait>(
+    user_id: i32,
+    conn: &C,
 ) -> DbResult<economy_state::Model> {
     match economy_state::Entity::find()
         .filter(economy_state::Column::UserId.eq(user_id))
@@ -30,10 +81,55 @@ pub async fn get_or_create_economy_state(
     }
 }
 
-pub async fn update_economy_state(
+/// Economy state of a user along with details kept apart from it
+pub struct EconomyStateDetails {
+    pub state: economy_state::Model,
+
+    /// Outstanding amount of the user's loans
+    pub debt: i64,
+
+    /// Active freeze of the account, if it was asked for
+    pub freeze: Option<account_freeze::Model>,
+}
+
+/// Fetches economy state of the user and their debt, along with the active freeze
+/// of their account if `with_freeze` is set
+pub async fn get_economy_state_details<C: ConnectionTrait>(
+    user_id: i32,
+    with_freeze: bool,
+    conn: &C,
+) -> DbResult<EconomyStateDetails> {
+    let state = get_or_create_economy_state(user_id, conn).await?;
+    let debt = get_outstanding_debt(user_id, conn).await?;
+
+    let freeze = if with_freeze {
+        get_active_freeze(user_id, conn).await?
+    } else {
+        None
+    };
+
+    Ok(EconomyStateDetails {
+        state,
+        debt,
+        freeze,
+    })
+}
+
+/// Streams economy states of all users, ordered by user ID
+pub async fn stream_economy_states(
+    conn: &DbConn,
+) -> DbResult<impl Stream<Item = DbResult<economy_state::Model>> + Send + '_> {
+    economy_state::Entity::find()
+        .order_by_asc(economy_state::Column::UserId)
+        .stream(conn)
+        .await
+}
+
+#[allow(clippy::unnecessary_unwrap)]
+pub async fn update_economy_state<C: ConnectionTrait>(
     mut state: economy_state::ActiveModel,
     form: UpdateEconomyStateForm,
-    conn: &DbConn,
+    conn: &C,
 ) -> DbResult<economy_state::Model> {
     if form.balance.is_some() {
         state.balance = Set(form.balance.unwrap());
@@ -41,6 +137,18 @@ pub async fn update_economy_state(
     if form.banker.is_some() {
         state.banker = Set(form.banker.unwrap());
     }
+    if let Some(tier) = form.tier {
+        state.tier = Set(tier);
+    }
+    if let Some(limit) = form.limit_per_transaction {
+        state.limit_per_transaction = Set(limit);
+    }
+    if let Some(limit) = form.limit_daily {
+        state.limit_daily = Set(limit);
+    }
+    if let Some(limit) = form.limit_window {
+        state.limit_window = Set(limit);
+    }
 
     state.update(conn).await
 }
@@ -0,0 +1,374 @@
+use chrono::{DateTime, Duration, Utc};
+use economy_service_entity::{
+    account_member::{self, AccountRole},
+    economy_state::{self, AccountTier},
+    transaction::{self, TransactionKind},
+};
+use sea_orm::{sea_query::Expr, *};
+use std::fmt;
+
+use crate::{
+    get_account_role_limit, get_or_create_economy_state, DbResult, EconomyError, EconomyResult,
+};
+
+/// Limits on money sent by a single account. `None` means unlimited.
+#[derive(Default, Copy, Clone, Debug)]
+pub struct TransferLimits {
+    pub per_transaction: Option<i32>,
+    pub daily: Option<i32>,
+    pub window: Option<i32>,
+}
+
+/// Default transfer limits of every account tier
+#[derive(Copy, Clone, Debug)]
+pub struct TransferLimitsPolicy {
+    pub standard: TransferLimits,
+    pub verified: TransferLimits,
+    pub merchant: TransferLimits,
+
+    /// Length of the rolling window
+    pub window: Duration,
+}
+impl TransferLimitsPolicy {
+    /// Effective limits of the account: its overrides, falling back to its tier defaults
+    pub fn limits_for(&self, state: &economy_state::Model) -> TransferLimits {
+        let tier = match state.tier {
+            AccountTier::Standard => self.standard,
+            AccountTier::Verified => self.verified,
+            AccountTier::Merchant => self.merchant,
+        };
+
+        TransferLimits {
+            per_transaction: state.limit_per_transaction.or(tier.per_transaction),
+            daily: state.limit_daily.or(tier.daily),
+            window: state.limit_window.or(tier.window),
+        }
+    }
+}
+
+/// Money already sent by an account within the limit periods
+#[derive(Default, Copy, Clone, Debug)]
+pub struct TransferUsage {
+    pub daily: i64,
+    pub window: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransferLimitKind {
+    PerTransaction,
+    Daily,
+    Window,
+}
+impl fmt::Display for TransferLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransferLimitKind::PerTransaction => "per-transaction",
+            TransferLimitKind::Daily => "daily",
+            TransferLimitKind::Window => "rolling window",
+        })
+    }
+}
+
+/// The tightest limit of an account and how much can still be sent under it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransferHeadroom {
+    pub kind: TransferLimitKind,
+    pub remaining: i64,
+}
+
+/// Returns the tightest limit given what was already sent, or `None` if the account is unlimited
+pub fn transfer_headroom(
+    limits: &TransferLimits,
+    usage: &TransferUsage,
+) -> Option<TransferHeadroom> {
+    [
+        (TransferLimitKind::PerTransaction, limits.per_transaction, 0),
+        (TransferLimitKind::Daily, limits.daily, usage.daily),
+        (TransferLimitKind::Window, limits.window, usage.window),
+    ]
+    .into_iter()
+    .filter_map(|(kind, limit, used)| {
+        limit.map(|limit| TransferHeadroom {
+            kind,
+            remaining: (limit as i64 - used).max(0),
+        })
+    })
+    .min_by_key(|headroom| headroom.remaining)
+}
+
+/// Fails with [`EconomyError::TransferLimitExceeded`] if sending the amount would exceed
+/// the user's limits. Run within the transfer after the payer's balance is updated, so that
+/// concurrent payments of the user wait for each other instead of all fitting the same headroom.
+pub(crate) async fn check_transfer_limits<C: ConnectionTrait>(
+    user_id: i32,
+    amount: i32,
+    policy: &TransferLimitsPolicy,
+    conn: &C,
+) -> EconomyResult<()> {
+    let state = get_or_create_economy_state(user_id, conn).await?;
+    let limits = policy.limits_for(&state);
+    let usage = get_transfer_usage(user_id, policy.window, conn).await?;
+
+    match transfer_headroom(&limits, &usage) {
+        Some(headroom) if amount as i64 > headroom.remaining => {
+            Err(EconomyError::TransferLimitExceeded(headroom))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails with [`EconomyError::RoleLimitExceeded`] if spending the amount of the account would
+/// exceed limits of the member's role given what they already spent. Owners aren't limited.
+/// Run within the transfer after the account's balance is updated, like [`check_transfer_limits`].
+pub(crate) async fn check_account_role_limit<C: ConnectionTrait>(
+    member: &account_member::Model,
+    amount: i32,
+    conn: &C,
+) -> EconomyResult<()> {
+    if member.role == AccountRole::Owner {
+        return Ok(());
+    }
+
+    let usage = get_account_spending_usage(member.account_id, member.user_id, conn).await?;
+    check_role_headroom(member.account_id, member.role, &usage, amount, conn).await
+}
+
+/// Fails with [`EconomyError::RoleLimitExceeded`] if spending the amount of the account with
+/// the API key would exceed limits of treasurers given what the key already spent.
+/// Run within the transfer like [`check_account_role_limit`].
+pub(crate) async fn check_api_key_spending_limit<C: ConnectionTrait>(
+    account_id: i32,
+    api_key_id: i32,
+    amount: i32,
+    conn: &C,
+) -> EconomyResult<()> {
+    let usage = get_api_key_spending_usage(account_id, api_key_id, conn).await?;
+    check_role_headroom(account_id, AccountRole::Treasurer, &usage, amount, conn).await
+}
+
+async fn check_role_headroom<C: ConnectionTrait>(
+    account_id: i32,
+    role: AccountRole,
+    usage: &TransferUsage,
+    amount: i32,
+    conn: &C,
+) -> EconomyResult<()> {
+    let limits = get_account_role_limit(account_id, role, conn).await?;
+
+    match transfer_headroom(&limits, usage) {
+        Some(headroom) if amount as i64 > headroom.remaining => {
+            Err(EconomyError::RoleLimitExceeded(headroom))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum QueryAs {
+    Total,
+}
+
+/// Transactions counting towards the limits. Loans are lent like payments are sent, while
+/// repayments are collected on schedule rather than sent by the borrower, so they don't count.
+const SENT_KINDS: [TransactionKind; 2] = [TransactionKind::Payment, TransactionKind::Loan];
+
+async fn sent_since<C: ConnectionTrait>(
+    payer: Condition,
+    since: DateTime<Utc>,
+    conn: &C,
+) -> DbResult<i64> {
+    let total: Option<Option<i64>> = transaction::Entity::find()
+        .select_only()
+        .column_as(Expr::col(transaction::Column::Amount).sum(), QueryAs::Total)
+        .filter(transaction::Column::Kind.is_in(SENT_KINDS))
+        .filter(payer)
+        .filter(transaction::Column::CreatedAt.gte(since))
+        .into_values::<_, QueryAs>()
+        .one(conn)
+        .await?;
+
+    Ok(total.flatten().unwrap_or(0))
+}
+
+/// Sums up payments and loans sent by the user today (UTC) and within the rolling window,
+/// both from their own balance and from accounts they spend money of
+pub async fn get_transfer_usage<C: ConnectionTrait>(
+    user_id: i32,
+    window: Duration,
+    conn: &C,
+) -> DbResult<TransferUsage> {
+    let now = Utc::now();
+    let payer = || {
+        Condition::any()
+            .add(transaction::Column::PayerId.eq(user_id))
+            .add(transaction::Column::InitiatorId.eq(user_id))
+    };
+
+    Ok(TransferUsage {
+        daily: sent_since(payer(), day_start(now), conn).await?,
+        window: sent_since(payer(), now - window, conn).await?,
+    })
+}
+
+/// Sums up payments the member initiated from the account today (UTC).
+/// Role limits have no rolling window, so its usage is left at 0.
+pub async fn get_account_spending_usage<C: ConnectionTrait>(
+    account_id: i32,
+    initiator_id: i32,
+    conn: &C,
+) -> DbResult<TransferUsage> {
+    let payer = Condition::all()
+        .add(transaction::Column::PayerAccountId.eq(account_id))
+        .add(transaction::Column::InitiatorId.eq(initiator_id));
+
+    Ok(TransferUsage {
+        daily: sent_since(payer, day_start(Utc::now()), conn).await?,
+        window: 0,
+    })
+}
+
+/// Sums up payments made from the account with the API key today (UTC), like
+/// [`get_account_spending_usage`] does for members
+pub async fn get_api_key_spending_usage<C: ConnectionTrait>(
+    account_id: i32,
+    api_key_id: i32,
+    conn: &C,
+) -> DbResult<TransferUsage> {
+    let payer = Condition::all()
+        .add(transaction::Column::PayerAccountId.eq(account_id))
+        .add(transaction::Column::ApiKeyId.eq(api_key_id));
+
+    Ok(TransferUsage {
+        daily: sent_since(payer, day_start(Utc::now()), conn).await?,
+        window: 0,
+    })
+}
+
+fn day_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    DateTime::<Utc>::from_utc(now.date_naive().and_hms_opt(0, 0, 0).unwrap(), Utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(tier: AccountTier) -> economy_state::Model {
+        economy_state::Model {
+            id: 1,
+            user_id: 1,
+            balance: 0,
+            banker: false,
+            tier,
+            limit_per_transaction: None,
+            limit_daily: None,
+            limit_window: None,
+        }
+    }
+
+    fn policy() -> TransferLimitsPolicy {
+        TransferLimitsPolicy {
+            standard: TransferLimits {
+                per_transaction: Some(100),
+                daily: Some(500),
+                window: Some(1000),
+            },
+            verified: TransferLimits {
+                per_transaction: Some(1000),
+                daily: None,
+                window: None,
+            },
+            merchant: TransferLimits::default(),
+            window: Duration::days(7),
+        }
+    }
+
+    #[test]
+    fn limits_fall_back_to_tier_defaults() {
+        let limits = policy().limits_for(&state(AccountTier::Standard));
+        assert_eq!(limits.per_transaction, Some(100));
+        assert_eq!(limits.daily, Some(500));
+        assert_eq!(limits.window, Some(1000));
+
+        let limits = policy().limits_for(&state(AccountTier::Verified));
+        assert_eq!(limits.per_transaction, Some(1000));
+        assert_eq!(limits.daily, None);
+
+        let limits = policy().limits_for(&state(AccountTier::Merchant));
+        assert_eq!(limits.per_transaction, None);
+        assert_eq!(limits.window, None);
+    }
+
+    #[test]
+    fn overrides_replace_tier_defaults() {
+        let limits = policy().limits_for(&economy_state::Model {
+            limit_per_transaction: Some(5),
+            limit_window: Some(50),
+            ..state(AccountTier::Merchant)
+        });
+        assert_eq!(limits.per_transaction, Some(5));
+        assert_eq!(limits.daily, None);
+        assert_eq!(limits.window, Some(50));
+    }
+
+    #[test]
+    fn headroom_is_the_tightest_limit() {
+        let limits = policy().standard;
+
+        let headroom = transfer_headroom(&limits, &TransferUsage::default());
+        assert_eq!(
+            headroom,
+            Some(TransferHeadroom {
+                kind: TransferLimitKind::PerTransaction,
+                remaining: 100,
+            })
+        );
+
+        let usage = TransferUsage {
+            daily: 450,
+            window: 450,
+        };
+        assert_eq!(
+            transfer_headroom(&limits, &usage),
+            Some(TransferHeadroom {
+                kind: TransferLimitKind::Daily,
+                remaining: 50,
+            })
+        );
+
+        let usage = TransferUsage {
+            daily: 0,
+            window: 980,
+        };
+        assert_eq!(
+            transfer_headroom(&limits, &usage),
+            Some(TransferHeadroom {
+                kind: TransferLimitKind::Window,
+                remaining: 20,
+            })
+        );
+    }
+
+    #[test]
+    fn headroom_never_goes_below_zero() {
+        let usage = TransferUsage {
+            daily: 700,
+            window: 700,
+        };
+        assert_eq!(
+            transfer_headroom(&policy().standard, &usage),
+            Some(TransferHeadroom {
+                kind: TransferLimitKind::Daily,
+                remaining: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn unlimited_accounts_have_no_headroom() {
+        let usage = TransferUsage {
+            daily: 1_000_000,
+            window: 1_000_000,
+        };
+        assert_eq!(transfer_headroom(&TransferLimits::default(), &usage), None);
+    }
+}
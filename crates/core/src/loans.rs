@@ -0,0 +1,320 @@
+use chrono::{DateTime, Duration, Utc};
+use economy_service_entity::{
+    loan::{self, LoanStatus},
+    transaction::{self, TransactionKind},
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::{
+    check_transfer_limits, get_or_create_economy_state, record_transfer, DbResult, EconomyError,
+    EconomyResult, LedgerParty, TransferForm, TransferLimitsPolicy,
+};
+
+/// Denominator of rates given in basis points
+const BPS: i64 = 10_000;
+
+/// Statuses of loans that are still being repaid
+const OUTSTANDING_STATUSES: [LoanStatus; 2] = [LoanStatus::Active, LoanStatus::Defaulted];
+
+pub struct OfferLoanForm {
+    pub lender_id: i32,
+    pub borrower_id: i32,
+    pub principal: i32,
+    pub interest_rate_bps: i32,
+    pub installments: i32,
+    pub interval_hours: i32,
+    pub comment: Option<String>,
+}
+
+/// How missed installments are handled
+#[derive(Clone, Copy, Debug)]
+pub struct LatePaymentPolicy {
+    /// Fee charged on the missed amount in basis points
+    pub late_fee_bps: i32,
+
+    /// Installments missed in a row after which the loan is defaulted
+    pub default_after: i32,
+}
+
+/// Outcome of collecting a due installment
+pub struct Collection {
+    pub loan: loan::Model,
+
+    /// Repayment moved from the borrower, unless they had no money
+    pub repayment: Option<transaction::Model>,
+
+    /// Part of the installment the borrower couldn't pay
+    pub missed: i32,
+}
+
+/// Amount repaid for the principal with interest over the whole term,
+/// or `None` if it doesn't fit into a balance
+pub fn loan_total_due(principal: i32, interest_rate_bps: i32) -> Option<i32> {
+    let interest = (principal as i64 * interest_rate_bps as i64 + BPS - 1) / BPS;
+    i32::try_from(principal as i64 + interest).ok()
+}
+
+/// Amount left to repay of the loan
+pub fn loan_outstanding(loan: &loan::Model) -> i32 {
+    loan.total_due - loan.repaid
+}
+
+/// Offers the loan on behalf of the lender.
+/// Fails with [`EconomyError::TransferLimitExceeded`] if the principal doesn't fit into
+/// the lender's limits right now, as it's checked against them again once the loan is accepted.
+pub async fn offer_loan<C: ConnectionTrait>(
+    form: OfferLoanForm,
+    limits: Option<TransferLimitsPolicy>,
+    conn: &C,
+) -> EconomyResult<loan::Model> {
+    if let Some(policy) = &limits {
+        check_transfer_limits(form.lender_id, form.principal, policy, conn).await?;
+    }
+
+    let total_due = loan_total_due(form.principal, form.interest_rate_bps)
+        .ok_or_else(|| DbErr::Custom(String::from("Loan total doesn't fit into a balance")))?;
+
+    loan::ActiveModel {
+        lender_id: Set(form.lender_id),
+        borrower_id: Set(form.borrower_id),
+        status: Set(LoanStatus::Offered),
+        principal: Set(form.principal),
+        interest_rate_bps: Set(form.interest_rate_bps),
+        installments: Set(form.installments),
+        interval_hours: Set(form.interval_hours),
+        installment_amount: Set((total_due + form.installments - 1) / form.installments),
+        total_due: Set(total_due),
+        repaid: Set(0),
+        overdue: Set(0),
+        missed_installments: Set(0),
+        comment: Set(form.comment),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .map_err(EconomyError::from)
+}
+
+pub async fn get_loan<C: ConnectionTrait>(id: i32, conn: &C) -> DbResult<Option<loan::Model>> {
+    loan::Entity::find_by_id(id).one(conn).await
+}
+
+/// Returns loans the user lends or borrows, newest first
+pub async fn get_user_loans(user_id: i32, conn: &DbConn) -> DbResult<Vec<loan::Model>> {
+    loan::Entity::find()
+        .filter(
+            Condition::any()
+                .add(loan::Column::LenderId.eq(user_id))
+                .add(loan::Column::BorrowerId.eq(user_id)),
+        )
+        .order_by_desc(loan::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Returns the amount the user still has to repay of the loans they borrowed
+pub async fn get_outstanding_debt<C: ConnectionTrait>(user_id: i32, conn: &C) -> DbResult<i64> {
+    let loans = loan::Entity::find()
+        .filter(loan::Column::BorrowerId.eq(user_id))
+        .filter(loan::Column::Status.is_in(OUTSTANDING_STATUSES))
+        .all(conn)
+        .await?;
+
+    Ok(loans.iter().map(|loan| loan_outstanding(loan) as i64).sum())
+}
+
+/// Accepts the offered loan and moves its principal from the lender to the borrower,
+/// all in one database transaction. Returns `None` if the loan isn't offered anymore.
+/// Fails with [`EconomyError::InsufficientFunds`](crate::EconomyError::InsufficientFunds)
+/// if the lender doesn't have the principal, with
+/// [`EconomyError::TransferLimitExceeded`] if it doesn't fit into their limits anymore,
+/// or if either account is frozen like [`transfer`](crate::transfer) does.
+pub async fn accept_loan<C: ConnectionTrait + TransactionTrait>(
+    id: i32,
+    limits: Option<TransferLimitsPolicy>,
+    conn: &C,
+) -> EconomyResult<Option<(loan::Model, transaction::Model)>> {
+    let txn = conn.begin().await?;
+
+    let loan = match get_loan(id, &txn).await? {
+        Some(loan) if loan.status == LoanStatus::Offered => loan,
+        _ => return Ok(None),
+    };
+
+    // claim the offer, so that it's accepted only once
+    let now = Utc::now();
+    let claimed = loan::Entity::update_many()
+        .col_expr(loan::Column::Status, Expr::value(LoanStatus::Active))
+        .col_expr(loan::Column::AcceptedAt, Expr::value(now))
+        .col_expr(
+            loan::Column::NextDueAt,
+            Expr::value(now + Duration::hours(loan.interval_hours as i64)),
+        )
+        .filter(loan::Column::Id.eq(id))
+        .filter(loan::Column::Status.eq(LoanStatus::Offered))
+        .exec(&txn)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    let record = record_transfer(
+        TransferForm {
+            payer: LedgerParty::User(loan.lender_id),
+            payee: LedgerParty::User(loan.borrower_id),
+            amount: loan.principal,
+            comment: Some(format!("Loan #{}", loan.id)),
+            initiator_id: None,
+            api_key_id: None,
+            limits,
+            approvals: None,
+            check_freezes: true,
+        },
+        TransactionKind::Loan,
+        Some(loan.id),
+        &txn,
+    )
+    .await?;
+
+    let loan = get_loan(id, &txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("loan {}", id)))?;
+
+    txn.commit().await?;
+    Ok(Some((loan, record)))
+}
+
+/// Declines the offered loan. Returns `None` if the loan isn't offered anymore.
+pub async fn decline_loan<C: ConnectionTrait>(id: i32, conn: &C) -> DbResult<Option<loan::Model>> {
+    let declined = loan::Entity::update_many()
+        .col_expr(loan::Column::Status, Expr::value(LoanStatus::Declined))
+        .col_expr(loan::Column::ClosedAt, Expr::value(Utc::now()))
+        .filter(loan::Column::Id.eq(id))
+        .filter(loan::Column::Status.eq(LoanStatus::Offered))
+        .exec(conn)
+        .await?;
+
+    if declined.rows_affected == 0 {
+        return Ok(None);
+    }
+    get_loan(id, conn).await
+}
+
+/// Returns loans whose next installment is due, oldest due first
+pub async fn get_due_loans(
+    now: DateTime<Utc>,
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<loan::Model>> {
+    loan::Entity::find()
+        .filter(loan::Column::Status.is_in(OUTSTANDING_STATUSES))
+        .filter(loan::Column::NextDueAt.lte(now))
+        .order_by_asc(loan::Column::NextDueAt)
+        .limit(limit)
+        .all(conn)
+        .await
+}
+
+/// Collects the due installment of the loan, along with whatever was missed before, taking as much
+/// of it as the borrower has. Missed amounts are charged a late fee until the loan defaults,
+/// and carried over to the next installment. Returns `None` if the installment was already
+/// collected, e.g. by another replica.
+pub async fn collect_installment<C: ConnectionTrait + TransactionTrait>(
+    loan: &loan::Model,
+    policy: LatePaymentPolicy,
+    conn: &C,
+) -> EconomyResult<Option<Collection>> {
+    let due_at = match loan.next_due_at {
+        Some(due_at) => due_at,
+        None => return Ok(None),
+    };
+
+    let txn = conn.begin().await?;
+
+    // claim the installment by moving the due date, so that it's collected only once
+    let next_due_at = due_at + Duration::hours(loan.interval_hours as i64);
+    let claimed = loan::Entity::update_many()
+        .col_expr(loan::Column::NextDueAt, Expr::value(next_due_at))
+        .filter(loan::Column::Id.eq(loan.id))
+        .filter(loan::Column::NextDueAt.eq(due_at))
+        .filter(loan::Column::Status.is_in(OUTSTANDING_STATUSES))
+        .exec(&txn)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    let owed = (loan.installment_amount + loan.overdue).min(loan_outstanding(loan));
+    let balance = get_or_create_economy_state(loan.borrower_id, &txn)
+        .await?
+        .balance;
+    let paid = owed.min(balance.max(0));
+
+    let repayment = if paid > 0 {
+        let record = record_transfer(
+            TransferForm {
+                payer: LedgerParty::User(loan.borrower_id),
+                payee: LedgerParty::User(loan.lender_id),
+                amount: paid,
+                comment: Some(format!("Repayment of loan #{}", loan.id)),
+                initiator_id: None,
+                api_key_id: None,
+                limits: None,
+                approvals: None,
+                check_freezes: false,
+            },
+            TransactionKind::Repayment,
+            Some(loan.id),
+            &txn,
+        )
+        .await?;
+        Some(record)
+    } else {
+        None
+    };
+
+    let missed = owed - paid;
+    let mut total_due = loan.total_due;
+    let mut status = loan.status;
+    let (overdue, missed_installments) = if missed > 0 {
+        let fee = if status == LoanStatus::Active {
+            ((missed as i64 * policy.late_fee_bps as i64 + BPS - 1) / BPS) as i32
+        } else {
+            0
+        };
+        total_due = total_due.saturating_add(fee);
+
+        let missed_installments = loan.missed_installments + 1;
+        if missed_installments >= policy.default_after {
+            status = LoanStatus::Defaulted;
+        }
+        (missed.saturating_add(fee), missed_installments)
+    } else {
+        (0, 0)
+    };
+
+    let repaid = loan.repaid + paid;
+    let mut update = loan::ActiveModel {
+        id: Unchanged(loan.id),
+        total_due: Set(total_due),
+        repaid: Set(repaid),
+        overdue: Set(overdue),
+        missed_installments: Set(missed_installments),
+        status: Set(status),
+        ..Default::default()
+    };
+    if repaid >= total_due {
+        update.status = Set(LoanStatus::Repaid);
+        update.next_due_at = Set(None);
+        update.closed_at = Set(Some(Utc::now()));
+    }
+    let loan = update.update(&txn).await?;
+
+    txn.commit().await?;
+    Ok(Some(Collection {
+        loan,
+        repayment,
+        missed,
+    }))
+}
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::{
+    proposal::{self, ProposalKind, ProposalStatus},
+    proposal_approval,
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::DbResult;
+
+pub struct CreateProposalForm {
+    pub kind: ProposalKind,
+    pub proposer_id: i32,
+    pub account_id: Option<i32>,
+    pub payload: JsonValue,
+    pub required_approvals: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Proposals a user can see unless they're an admin
+pub struct ProposalVisibility {
+    pub user_id: i32,
+
+    /// Whether the user can see all mint proposals
+    pub mints: bool,
+
+    /// Accounts whose payment proposals the user can see
+    pub account_ids: Vec<i32>,
+}
+
+pub async fn create_proposal<C: ConnectionTrait>(
+    form: CreateProposalForm,
+    conn: &C,
+) -> DbResult<proposal::Model> {
+    proposal::ActiveModel {
+        kind: Set(form.kind),
+        status: Set(ProposalStatus::Pending),
+        proposer_id: Set(form.proposer_id),
+        account_id: Set(form.account_id),
+        payload: Set(form.payload),
+        required_approvals: Set(form.required_approvals),
+        created_at: Set(Utc::now()),
+        expires_at: Set(form.expires_at),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+pub async fn get_proposal<C: ConnectionTrait>(
+    id: i32,
+    conn: &C,
+) -> DbResult<Option<proposal::Model>> {
+    proposal::Entity::find_by_id(id).one(conn).await
+}
+
+/// Returns proposals with the status visible to the user (or all of them if `None`), newest first
+pub async fn find_proposals(
+    status: Option<ProposalStatus>,
+    visible_to: Option<ProposalVisibility>,
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<proposal::Model>> {
+    let mut query = proposal::Entity::find();
+
+    if let Some(status) = status {
+        query = query.filter(proposal::Column::Status.eq(status));
+    }
+    if let Some(visibility) = visible_to {
+        let mut condition =
+            Condition::any().add(proposal::Column::ProposerId.eq(visibility.user_id));
+        if visibility.mints {
+            condition = condition.add(proposal::Column::Kind.eq(ProposalKind::Mint));
+        }
+        if !visibility.account_ids.is_empty() {
+            condition = condition.add(proposal::Column::AccountId.is_in(visibility.account_ids));
+        }
+        query = query.filter(condition);
+    }
+
+    query
+        .order_by_desc(proposal::Column::Id)
+        .limit(limit)
+        .all(conn)
+        .await
+}
+
+/// Marks pending proposals past their expiry as expired, returning the amount of them
+pub async fn expire_proposals<C: ConnectionTrait>(conn: &C) -> DbResult<u64> {
+    let now = Utc::now();
+
+    proposal::Entity::update_many()
+        .col_expr(
+            proposal::Column::Status,
+            Expr::value(ProposalStatus::Expired),
+        )
+        .col_expr(proposal::Column::ResolvedAt, Expr::value(now))
+        .filter(proposal::Column::Status.eq(ProposalStatus::Pending))
+        .filter(proposal::Column::ExpiresAt.lte(now))
+        .exec(conn)
+        .await
+        .map(|res| res.rows_affected)
+}
+
+pub async fn get_proposal_approvals<C: ConnectionTrait>(
+    proposal_id: i32,
+    conn: &C,
+) -> DbResult<Vec<proposal_approval::Model>> {
+    proposal_approval::Entity::find()
+        .filter(proposal_approval::Column::ProposalId.eq(proposal_id))
+        .order_by_asc(proposal_approval::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Records approval of the proposal by the user. Returns `None` if they approved it already,
+/// otherwise the amount of approvals including this one.
+pub async fn approve_proposal<C: ConnectionTrait>(
+    proposal_id: i32,
+    approver_id: i32,
+    conn: &C,
+) -> DbResult<Option<u64>> {
+    let approved = proposal_approval::Entity::find()
+        .filter(proposal_approval::Column::ProposalId.eq(proposal_id))
+        .filter(proposal_approval::Column::ApproverId.eq(approver_id))
+        .one(conn)
+        .await?;
+    if approved.is_some() {
+        return Ok(None);
+    }
+
+    proposal_approval::ActiveModel {
+        proposal_id: Set(proposal_id),
+        approver_id: Set(approver_id),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    proposal_approval::Entity::find()
+        .filter(proposal_approval::Column::ProposalId.eq(proposal_id))
+        .count(conn)
+        .await
+        .map(Some)
+}
+
+/// Moves a pending proposal to the new status. Returns `false` if it wasn't pending anymore,
+/// so that only one of concurrent approvers gets to execute or reject it.
+pub async fn claim_proposal<C: ConnectionTrait>(
+    id: i32,
+    status: ProposalStatus,
+    rejected_by: Option<i32>,
+    conn: &C,
+) -> DbResult<bool> {
+    let mut query = proposal::Entity::update_many()
+        .col_expr(proposal::Column::Status, Expr::value(status))
+        .col_expr(proposal::Column::RejectedBy, Expr::value(rejected_by))
+        .filter(proposal::Column::Id.eq(id))
+        .filter(proposal::Column::Status.eq(ProposalStatus::Pending))
+        .filter(proposal::Column::ExpiresAt.gt(Utc::now()));
+    if status != ProposalStatus::Approved {
+        query = query.col_expr(proposal::Column::ResolvedAt, Expr::value(Utc::now()));
+    }
+
+    query.exec(conn).await.map(|res| res.rows_affected > 0)
+}
+
+/// Records the outcome of executing an approved proposal
+pub async fn finish_proposal<C: ConnectionTrait>(
+    id: i32,
+    transaction_id: Option<i32>,
+    error: Option<String>,
+    conn: &C,
+) -> DbResult<Option<proposal::Model>> {
+    let status = match error {
+        Some(_) => ProposalStatus::Failed,
+        None => ProposalStatus::Executed,
+    };
+
+    proposal::Entity::update_many()
+        .col_expr(proposal::Column::Status, Expr::value(status))
+        .col_expr(proposal::Column::TransactionId, Expr::value(transaction_id))
+        .col_expr(proposal::Column::Error, Expr::value(error))
+        .col_expr(proposal::Column::ResolvedAt, Expr::value(Utc::now()))
+        .filter(proposal::Column::Id.eq(id))
+        .exec(conn)
+        .await?;
+
+    get_proposal(id, conn).await
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::{
+    account, economy_state,
+    transaction::{self, TransactionKind},
+};
+use sea_orm::*;
+use std::collections::HashMap;
+
+use crate::{DbResult, LedgerParty};
+
+/// Account whose balance doesn't match its recorded movements
+#[derive(Clone, Debug)]
+pub struct AccountDrift {
+    pub holder: LedgerParty,
+    pub balance: i32,
+    pub expected: i64,
+    pub drift: i64,
+}
+
+/// Comparison of balances with the money movements recorded in the ledger
+#[derive(Clone, Debug)]
+pub struct Reconciliation {
+    pub checked_at: DateTime<Utc>,
+    pub accounts: usize,
+
+    /// Sum of all balances
+    pub total_balance: i64,
+
+    /// Minted and imported money minus burned money
+    pub expected_total: i64,
+
+    /// Difference between the total balance and the expected one.
+    /// Positive drift means money was created outside of the ledger, negative means it was destroyed.
+    pub drift: i64,
+
+    /// Accounts with drift, users ordered by ID followed by accounts owned by players ordered by ID
+    pub drifted_accounts: Vec<AccountDrift>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum QueryAs {
+    HolderId,
+    Total,
+}
+
+/// Sums up amounts of the movements per holder in the given column
+async fn sum_by_holder<C: ConnectionTrait>(
+    holder_column: transaction::Column,
+    kinds: &[TransactionKind],
+    conn: &C,
+) -> DbResult<HashMap<i32, i64>> {
+    let totals: Vec<(Option<i32>, Option<i64>)> = transaction::Entity::find()
+        .select_only()
+        .column_as(holder_column, QueryAs::HolderId)
+        .column_as(
+            sea_query::Expr::col(transaction::Column::Amount).sum(),
+            QueryAs::Total,
+        )
+        .filter(transaction::Column::Kind.is_in(kinds.iter().copied()))
+        .filter(holder_column.is_not_null())
+        .group_by(holder_column)
+        .into_values::<_, QueryAs>()
+        .all(conn)
+        .await?;
+
+    Ok(totals
+        .into_iter()
+        .filter_map(|(holder_id, total)| Some((holder_id?, total.unwrap_or(0))))
+        .collect())
+}
+
+/// Recomputes balances of all accounts from the ledger and compares them with the stored ones
+pub async fn reconcile(conn: &DbConn) -> DbResult<Reconciliation> {
+    let txn = conn.begin().await?;
+
+    // all queries have to see the same snapshot, which SQLite transactions always do
+    if txn.get_database_backend() == DbBackend::Postgres {
+        txn.execute(Statement::from_string(
+            DbBackend::Postgres,
+            String::from("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY"),
+        ))
+        .await?;
+    }
+
+    let incoming_kinds = [
+        TransactionKind::Payment,
+        TransactionKind::Mint,
+        TransactionKind::Import,
+        TransactionKind::Reversal,
+        TransactionKind::Loan,
+        TransactionKind::Repayment,
+    ];
+    let outgoing_kinds = [
+        TransactionKind::Payment,
+        TransactionKind::Burn,
+        TransactionKind::Reversal,
+        TransactionKind::Loan,
+        TransactionKind::Repayment,
+    ];
+
+    let user_incoming = sum_by_holder(transaction::Column::PayeeId, &incoming_kinds, &txn).await?;
+    let user_outgoing = sum_by_holder(transaction::Column::PayerId, &outgoing_kinds, &txn).await?;
+    let account_incoming =
+        sum_by_holder(transaction::Column::PayeeAccountId, &incoming_kinds, &txn).await?;
+    let account_outgoing =
+        sum_by_holder(transaction::Column::PayerAccountId, &outgoing_kinds, &txn).await?;
+    let states = economy_state::Entity::find()
+        .order_by_asc(economy_state::Column::UserId)
+        .all(&txn)
+        .await?;
+    let accounts = account::Entity::find()
+        .order_by_asc(account::Column::Id)
+        .all(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    let balances = states
+        .iter()
+        .map(|state| (LedgerParty::User(state.user_id), state.balance))
+        .chain(
+            accounts
+                .iter()
+                .map(|account| (LedgerParty::Account(account.id), account.balance)),
+        );
+
+    let mut drifted_accounts = vec![];
+    let mut total_balance = 0;
+    for (holder, balance) in balances {
+        let (incoming, outgoing, id) = match holder {
+            LedgerParty::User(id) => (&user_incoming, &user_outgoing, id),
+            LedgerParty::Account(id) => (&account_incoming, &account_outgoing, id),
+        };
+        let expected =
+            incoming.get(&id).copied().unwrap_or(0) - outgoing.get(&id).copied().unwrap_or(0);
+        let drift = balance as i64 - expected;
+
+        total_balance += balance as i64;
+        if drift != 0 {
+            drifted_accounts.push(AccountDrift {
+                holder,
+                balance,
+                expected,
+                drift,
+            });
+        }
+    }
+
+    // payments cancel out, so only money entering and leaving the economy is left
+    let expected_total = [user_incoming, account_incoming]
+        .iter()
+        .flat_map(|totals| totals.values())
+        .sum::<i64>()
+        - [user_outgoing, account_outgoing]
+            .iter()
+            .flat_map(|totals| totals.values())
+            .sum::<i64>();
+
+    Ok(Reconciliation {
+        checked_at: Utc::now(),
+        accounts: states.len() + accounts.len(),
+        total_balance,
+        expected_total,
+        drift: total_balance - expected_total,
+        drifted_accounts,
+    })
+}
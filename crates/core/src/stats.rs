@@ -0,0 +1,102 @@
+use economy_service_entity::{
+    account, account_freeze, economy_state,
+    transaction::{self, TransactionKind},
+};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::{get_active_freeze_condition, DbResult};
+
+/// Overview of the whole economy
+#[derive(Clone, Debug)]
+pub struct EconomyStats {
+    pub accounts: u64,
+    pub shared_accounts: u64,
+    pub bankers: u64,
+    pub frozen_accounts: u64,
+    pub total_balance: i64,
+    pub minted: i64,
+    pub burned: i64,
+    pub imported: i64,
+    pub payments: u64,
+    pub payments_volume: i64,
+    pub reversed: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum QueryAs {
+    Total,
+}
+
+async fn sum<E: EntityTrait>(
+    query: Select<E>,
+    column: impl ColumnTrait,
+    conn: &DbConn,
+) -> DbResult<i64> {
+    let total: Option<Option<i64>> = query
+        .select_only()
+        .column_as(Expr::col(column).sum(), QueryAs::Total)
+        .into_values::<_, QueryAs>()
+        .one(conn)
+        .await?;
+
+    Ok(total.flatten().unwrap_or(0))
+}
+
+fn transactions_of(kind: TransactionKind) -> Select<transaction::Entity> {
+    transaction::Entity::find().filter(transaction::Column::Kind.eq(kind))
+}
+
+pub async fn get_economy_stats(conn: &DbConn) -> DbResult<EconomyStats> {
+    Ok(EconomyStats {
+        accounts: economy_state::Entity::find().count(conn).await?,
+        shared_accounts: account::Entity::find().count(conn).await?,
+        bankers: economy_state::Entity::find()
+            .filter(economy_state::Column::Banker.eq(true))
+            .count(conn)
+            .await?,
+        frozen_accounts: account_freeze::Entity::find()
+            .filter(get_active_freeze_condition())
+            .count(conn)
+            .await?,
+        total_balance: sum(
+            economy_state::Entity::find(),
+            economy_state::Column::Balance,
+            conn,
+        )
+        .await?
+            + sum(account::Entity::find(), account::Column::Balance, conn).await?,
+        minted: sum(
+            transactions_of(TransactionKind::Mint),
+            transaction::Column::Amount,
+            conn,
+        )
+        .await?,
+        burned: sum(
+            transactions_of(TransactionKind::Burn),
+            transaction::Column::Amount,
+            conn,
+        )
+        .await?,
+        imported: sum(
+            transactions_of(TransactionKind::Import),
+            transaction::Column::Amount,
+            conn,
+        )
+        .await?,
+        payments: transactions_of(TransactionKind::Payment)
+            .count(conn)
+            .await?,
+        payments_volume: sum(
+            transactions_of(TransactionKind::Payment),
+            transaction::Column::Amount,
+            conn,
+        )
+        .await?,
+        reversed: sum(
+            transactions_of(TransactionKind::Reversal),
+            transaction::Column::Amount,
+            conn,
+        )
+        .await?,
+    })
+}
@@ -0,0 +1,930 @@
+use chrono::{DateTime, Utc};
+use economy_service_entity::{
+    account::AccountKind,
+    audit_log_entry::AuditAction,
+    economy_state,
+    proposal::ProposalKind,
+    transaction::{self, TransactionKind},
+    webhook::WebhookEvent,
+};
+use futures::Stream;
+use sea_orm::{sea_query::Expr, *};
+use serde_json::json;
+
+use crate::{
+    add_to_account_balance, check_account_role_limit, check_api_key_spending_limit,
+    check_spending_role, check_transfer_limits, enqueue_webhook_event, get_account,
+    get_active_freeze, get_api_key, get_api_key_approval_usage, get_approval_usage,
+    get_or_create_economy_state, propose, record_audit_entry, Actor, Approval, ApprovalPolicy,
+    AuditContext, AuditEntryForm, DbResult, EconomyError, EconomyResult, TransferLimitsPolicy,
+};
+
+/// Adds `delta` to the user's balance and returns the new balance.
+/// If `min_balance` is given, the balance is left untouched and `None` is returned
+/// when it would end up below it.
+pub(crate) async fn add_to_balance<C: ConnectionTrait>(
+    user_id: i32,
+    delta: i32,
+    min_balance: Option<i32>,
+    conn: &C,
+) -> DbResult<Option<i32>> {
+    let mut query = economy_state::Entity::update_many()
+        .col_expr(
+            economy_state::Column::Balance,
+            Expr::col(economy_state::Column::Balance).add(delta),
+        )
+        .filter(economy_state::Column::UserId.eq(user_id));
+    if let Some(min_balance) = min_balance {
+        query = query.filter(economy_state::Column::Balance.gte(min_balance - delta));
+    }
+
+    if query.exec(conn).await?.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    get_or_create_economy_state(user_id, conn)
+        .await
+        .map(|state| Some(state.balance))
+}
+
+/// Holder of money on either side of a transfer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LedgerParty {
+    /// Economy state of a user
+    User(i32),
+
+    /// Account owned by players
+    Account(i32),
+}
+impl LedgerParty {
+    pub fn user_id(self) -> Option<i32> {
+        match self {
+            LedgerParty::User(id) => Some(id),
+            LedgerParty::Account(_) => None,
+        }
+    }
+
+    pub fn account_id(self) -> Option<i32> {
+        match self {
+            LedgerParty::User(_) => None,
+            LedgerParty::Account(id) => Some(id),
+        }
+    }
+}
+
+/// Adds `delta` to the balance of the party, like [`add_to_balance`] does for users
+async fn add_to_party_balance<C: ConnectionTrait>(
+    party: LedgerParty,
+    delta: i32,
+    min_balance: Option<i32>,
+    conn: &C,
+) -> DbResult<Option<i32>> {
+    match party {
+        LedgerParty::User(user_id) => add_to_balance(user_id, delta, min_balance, conn).await,
+        LedgerParty::Account(account_id) => {
+            add_to_account_balance(account_id, delta, min_balance, conn).await
+        }
+    }
+}
+
+/// Balance of the party, or 0 if an account doesn't exist
+async fn party_balance<C: ConnectionTrait>(party: LedgerParty, conn: &C) -> DbResult<i32> {
+    match party {
+        LedgerParty::User(id) => get_or_create_economy_state(id, conn)
+            .await
+            .map(|state| state.balance),
+        LedgerParty::Account(id) => get_account(id, conn)
+            .await
+            .map(|account| account.map_or(0, |account| account.balance)),
+    }
+}
+
+/// [`EconomyError::InsufficientFunds`] telling how much the party has
+async fn insufficient_funds<C: ConnectionTrait>(
+    party: LedgerParty,
+    conn: &C,
+) -> DbResult<EconomyError> {
+    let available = party_balance(party, conn).await? as i64;
+    Ok(EconomyError::InsufficientFunds { available })
+}
+
+pub struct TransferForm {
+    pub payer: LedgerParty,
+    pub payee: LedgerParty,
+    pub amount: i32,
+    pub comment: Option<String>,
+
+    /// User who spends money of the payer account. They should have one of [`crate::SPENDING_ROLES`]
+    /// and are held to freezes and transfer limits like when paying from their own balance.
+    pub initiator_id: Option<i32>,
+
+    /// API key money of the payer account is spent with. Without an initiator, the key is held
+    /// to limits of treasurers and to freezes of its creator.
+    pub api_key_id: Option<i32>,
+
+    /// Transfer limits the paying user, or the initiator paying from an account, is held to
+    pub limits: Option<TransferLimitsPolicy>,
+
+    /// Approval policy payments of the initiator from an organization account are held to.
+    /// Left out when executing an approved payment.
+    pub approvals: Option<ApprovalPolicy>,
+
+    /// Whether frozen accounts can't send money, nor receive it if their freeze blocks incoming
+    /// money. Left out when collecting what a user owes.
+    pub check_freezes: bool,
+}
+
+/// Moves money from payer to payee and records the payment, all in one database transaction.
+/// Fails with [`EconomyError::InsufficientFunds`] if the payer doesn't have enough money
+/// at the time of the transfer, with [`EconomyError::TransferLimitExceeded`] if the payment
+/// doesn't fit into the payer's limits given what they already sent, or with
+/// [`EconomyError::AccountFrozen`] or [`EconomyError::PayeeFrozen`] if either side is frozen,
+/// with [`EconomyError::MissingAccountRole`] or [`EconomyError::RoleLimitExceeded`]
+/// if the initiator or API key can't spend that much money of the account,
+/// or with [`EconomyError::ApprovalRequired`] if the payment from an organization account
+/// has to be approved first.
+pub async fn transfer<C: ConnectionTrait + TransactionTrait>(
+    form: TransferForm,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    record_transfer(form, TransactionKind::Payment, None, conn).await
+}
+
+/// Moves money like [`transfer`] does, recording it as a transaction of the kind,
+/// linked to the loan if it disburses or repays one
+pub(crate) async fn record_transfer<C: ConnectionTrait + TransactionTrait>(
+    form: TransferForm,
+    kind: TransactionKind,
+    loan_id: Option<i32>,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    let txn = conn.begin().await?;
+
+    if let LedgerParty::User(payee_id) = form.payee {
+        get_or_create_economy_state(payee_id, &txn).await?;
+    }
+
+    // money of an account is spent by the initiator, or on behalf of the API key's creator
+    let spender_id = match (form.payer, form.initiator_id, form.api_key_id) {
+        (LedgerParty::User(payer_id), _, _) => Some(payer_id),
+        (LedgerParty::Account(_), Some(initiator_id), _) => Some(initiator_id),
+        (LedgerParty::Account(_), None, Some(api_key_id)) => {
+            let key = get_api_key(api_key_id, &txn)
+                .await?
+                .ok_or_else(|| DbErr::RecordNotFound(format!("API key {}", api_key_id)))?;
+            Some(key.created_by)
+        }
+        (LedgerParty::Account(_), None, None) => None,
+    };
+
+    // user who spends money of an account is locked before it, like when they pay to it
+    if let (LedgerParty::Account(_), Some(spender_id)) = (form.payer, spender_id) {
+        get_or_create_economy_state(spender_id, &txn).await?;
+        add_to_balance(spender_id, 0, None, &txn).await?;
+    }
+
+    let payer_balance = match add_to_party_balance(form.payer, -form.amount, Some(0), &txn).await? {
+        Some(balance) => balance,
+        None => return Err(insufficient_funds(form.payer, &txn).await?),
+    };
+
+    // members can't be changed nor spend concurrently while the account is locked
+    if let LedgerParty::Account(account_id) = form.payer {
+        match (form.initiator_id, form.api_key_id) {
+            (Some(initiator_id), _) => {
+                let member = check_spending_role(account_id, initiator_id, &txn).await?;
+                check_account_role_limit(&member, form.amount, &txn).await?;
+            }
+            (None, Some(api_key_id)) => {
+                check_api_key_spending_limit(account_id, api_key_id, form.amount, &txn).await?;
+            }
+            (None, None) => (),
+        }
+
+        if let Some(policy) = &form.approvals {
+            if treasury_payment_requires_approval(account_id, &form, policy, &txn).await? {
+                return Err(EconomyError::ApprovalRequired);
+            }
+        }
+    }
+
+    // the spender's row is locked by now, so their other payments can't be counted in between
+    if let (Some(policy), Some(spender_id)) = (&form.limits, spender_id) {
+        check_transfer_limits(spender_id, form.amount, policy, &txn).await?;
+    }
+
+    let payee_balance = add_to_party_balance(form.payee, form.amount, None, &txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("{:?}", form.payee)))?;
+
+    // checked with both balances locked, as freezing an account locks its balance too
+    if form.check_freezes {
+        if let Some(spender_id) = spender_id {
+            if get_active_freeze(spender_id, &txn).await?.is_some() {
+                return Err(EconomyError::AccountFrozen);
+            }
+        }
+        if let LedgerParty::User(payee_id) = form.payee {
+            let freeze = get_active_freeze(payee_id, &txn).await?;
+            if matches!(freeze, Some(freeze) if freeze.block_incoming) {
+                return Err(EconomyError::PayeeFrozen);
+            }
+        }
+    }
+
+    // Recorded after the balances are updated, so that while their rows are locked
+    // the transactions of the same user get IDs in commit order
+    let record = transaction::ActiveModel {
+        kind: Set(kind),
+        payer_id: Set(form.payer.user_id()),
+        payee_id: Set(form.payee.user_id()),
+        payer_account_id: Set(form.payer.account_id()),
+        payee_account_id: Set(form.payee.account_id()),
+        initiator_id: Set(form.initiator_id),
+        api_key_id: Set(form.api_key_id),
+        amount: Set(form.amount),
+        comment: Set(form.comment),
+        created_at: Set(Utc::now()),
+        loan_id: Set(loan_id),
+        payer_balance: Set(Some(payer_balance)),
+        payee_balance: Set(Some(payee_balance)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    enqueue_webhook_event(WebhookEvent::PaymentReceived, &record, &txn).await?;
+
+    txn.commit().await?;
+    Ok(record)
+}
+
+pub struct PaymentForm {
+    pub payer_id: i32,
+    pub payee: LedgerParty,
+    pub amount: i32,
+    pub comment: Option<String>,
+}
+
+/// Pays money from the user to the payee, checking funds, freezes and transfer limits
+/// of the payer like [`transfer`] does.
+/// Fails with [`EconomyError::InvalidAmount`] unless the amount is positive,
+/// with [`EconomyError::SelfPayment`] if the user pays to themselves,
+/// or with [`EconomyError::AccountNotFound`] if the payee account doesn't exist.
+pub async fn make_payment<C: ConnectionTrait + TransactionTrait>(
+    form: PaymentForm,
+    limits: TransferLimitsPolicy,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    if form.amount <= 0 {
+        return Err(EconomyError::InvalidAmount);
+    }
+
+    match form.payee {
+        LedgerParty::User(payee_id) if payee_id == form.payer_id => {
+            return Err(EconomyError::SelfPayment)
+        }
+        LedgerParty::Account(account_id) if get_account(account_id, conn).await?.is_none() => {
+            return Err(EconomyError::AccountNotFound)
+        }
+        _ => (),
+    }
+
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(form.payer_id),
+            payee: form.payee,
+            amount: form.amount,
+            comment: form.comment,
+            initiator_id: None,
+            api_key_id: None,
+            limits: Some(limits),
+            approvals: None,
+            check_freezes: true,
+        },
+        conn,
+    )
+    .await
+}
+
+pub struct ReverseForm {
+    /// Amount to move back, the whole unreversed part of the payment if `None`
+    pub amount: Option<i32>,
+
+    /// Whether to take whatever the payee has if it's less than the amount
+    pub force: bool,
+
+    pub comment: Option<String>,
+
+    /// User who made the reversal
+    pub initiator_id: Option<i32>,
+
+    /// Whether a frozen initiator can't give money back, nor a payer receive it if their freeze
+    /// blocks incoming money. Left out when money is taken back by force.
+    pub check_freezes: bool,
+}
+
+fn payer_of(record: &transaction::Model) -> Option<LedgerParty> {
+    match (record.payer_id, record.payer_account_id) {
+        (Some(id), _) => Some(LedgerParty::User(id)),
+        (None, Some(id)) => Some(LedgerParty::Account(id)),
+        (None, None) => None,
+    }
+}
+
+fn payee_of(record: &transaction::Model) -> Option<LedgerParty> {
+    match (record.payee_id, record.payee_account_id) {
+        (Some(id), _) => Some(LedgerParty::User(id)),
+        (None, Some(id)) => Some(LedgerParty::Account(id)),
+        (None, None) => None,
+    }
+}
+
+/// Moves money of the payment back from its payee to its payer and records it as a reversal
+/// linked to the payment, all in one database transaction.
+/// Fails with [`EconomyError::NotReversible`] unless the transaction is a payment between
+/// two parties, with [`EconomyError::AlreadyReversed`] if it's reversed fully,
+/// with [`EconomyError::InvalidAmount`] unless the amount is positive,
+/// with [`EconomyError::ReversalExceedsRemaining`] if it exceeds the unreversed part,
+/// with [`EconomyError::InsufficientFunds`] if the payee has nothing to give back,
+/// or with [`EconomyError::AccountFrozen`] or [`EconomyError::PayeeFrozen`] if either side is frozen.
+pub async fn reverse<C: ConnectionTrait + TransactionTrait>(
+    payment: &transaction::Model,
+    form: ReverseForm,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    let (payer, payee) = match (payment.kind, payer_of(payment), payee_of(payment)) {
+        (TransactionKind::Payment, Some(payer), Some(payee)) => (payer, payee),
+        _ => return Err(EconomyError::NotReversible),
+    };
+
+    let txn = conn.begin().await?;
+
+    // member giving back money of an account is locked before it, like when they spend it
+    if let (LedgerParty::Account(_), Some(initiator_id), true) =
+        (payee, form.initiator_id, form.check_freezes)
+    {
+        get_or_create_economy_state(initiator_id, &txn).await?;
+        add_to_balance(initiator_id, 0, None, &txn).await?;
+    }
+
+    // every reversal of the payment locks its payee first, so that neither what was already
+    // reversed nor what the payee has can change until this one is made
+    add_to_party_balance(payee, 0, None, &txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("{:?}", payee)))?;
+    let payment = get_transaction(payment.id, &txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("transaction {}", payment.id)))?;
+
+    let remaining = payment.amount - payment.reversed_amount;
+    if remaining <= 0 {
+        return Err(EconomyError::AlreadyReversed);
+    }
+
+    let mut amount = form.amount.unwrap_or(remaining);
+    if amount <= 0 {
+        return Err(EconomyError::InvalidAmount);
+    }
+    if amount > remaining {
+        return Err(EconomyError::ReversalExceedsRemaining {
+            remaining: remaining as i64,
+        });
+    }
+
+    if form.force {
+        let balance = party_balance(payee, &txn).await?;
+        amount = amount.min(balance);
+        if amount <= 0 {
+            return Err(EconomyError::InsufficientFunds {
+                available: balance as i64,
+            });
+        }
+    }
+
+    // counted on the payment itself too, in case it was reversed in between anyway
+    let counted = transaction::Entity::update_many()
+        .col_expr(
+            transaction::Column::ReversedAmount,
+            Expr::col(transaction::Column::ReversedAmount).add(amount),
+        )
+        .filter(transaction::Column::Id.eq(payment.id))
+        .filter(transaction::Column::ReversedAmount.lte(payment.amount - amount))
+        .exec(&txn)
+        .await?;
+    if counted.rows_affected == 0 {
+        return Err(EconomyError::AlreadyReversed);
+    }
+
+    if let LedgerParty::User(payer_id) = payer {
+        get_or_create_economy_state(payer_id, &txn).await?;
+    }
+
+    let payee_balance = match add_to_party_balance(payee, -amount, Some(0), &txn).await? {
+        Some(balance) => balance,
+        None => return Err(insufficient_funds(payee, &txn).await?),
+    };
+    let payer_balance = add_to_party_balance(payer, amount, None, &txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("{:?}", payer)))?;
+
+    // checked with both balances locked, as freezing an account locks its balance too
+    if form.check_freezes {
+        if let Some(initiator_id) = form.initiator_id {
+            if get_active_freeze(initiator_id, &txn).await?.is_some() {
+                return Err(EconomyError::AccountFrozen);
+            }
+        }
+        if let LedgerParty::User(payer_id) = payer {
+            let freeze = get_active_freeze(payer_id, &txn).await?;
+            if matches!(freeze, Some(freeze) if freeze.block_incoming) {
+                return Err(EconomyError::PayeeFrozen);
+            }
+        }
+    }
+
+    // the payee of the payment pays back to its payer
+    let record = transaction::ActiveModel {
+        kind: Set(TransactionKind::Reversal),
+        payer_id: Set(payee.user_id()),
+        payee_id: Set(payer.user_id()),
+        payer_account_id: Set(payee.account_id()),
+        payee_account_id: Set(payer.account_id()),
+        initiator_id: Set(form.initiator_id),
+        amount: Set(amount),
+        comment: Set(form.comment),
+        created_at: Set(Utc::now()),
+        payer_balance: Set(Some(payee_balance)),
+        payee_balance: Set(Some(payer_balance)),
+        reverses_id: Set(Some(payment.id)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    enqueue_webhook_event(WebhookEvent::PaymentReversed, &record, &txn).await?;
+
+    txn.commit().await?;
+    Ok(record)
+}
+
+pub async fn get_transaction<C: ConnectionTrait>(
+    id: i32,
+    conn: &C,
+) -> DbResult<Option<transaction::Model>> {
+    transaction::Entity::find_by_id(id).one(conn).await
+}
+
+/// Returns reversals of the payment, oldest first
+pub async fn get_reversals<C: ConnectionTrait>(
+    payment_id: i32,
+    conn: &C,
+) -> DbResult<Vec<transaction::Model>> {
+    transaction::Entity::find()
+        .filter(transaction::Column::ReversesId.eq(payment_id))
+        .order_by_asc(transaction::Column::Id)
+        .all(conn)
+        .await
+}
+
+/// Adds money to (or removes it from, if `amount` is negative) the user's balance
+/// and records it as a mint (or a burn).
+/// Fails with [`EconomyError::PayeeFrozen`] if money is minted to a frozen account
+/// blocking incoming money. Frozen accounts may still be fined, so burns aren't checked.
+pub async fn mint<C: ConnectionTrait + TransactionTrait>(
+    user_id: i32,
+    amount: i32,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    let txn = conn.begin().await?;
+    let record = record_mint(user_id, amount, None, None, &txn).await?;
+    txn.commit().await?;
+    Ok(record)
+}
+
+pub struct MintMoneyForm {
+    /// User whose money is minted or burned
+    pub user_id: i32,
+
+    /// Amount to mint, or to burn if it's negative
+    pub amount: i32,
+
+    pub reason: Option<String>,
+
+    /// Banker or API key the money is minted by
+    pub actor: Actor,
+
+    /// Approved proposal the mint executes, if it does
+    pub proposal_id: Option<i32>,
+}
+
+/// Mints (or burns) money of the user on behalf of the banker and records it in the audit log,
+/// all in one database transaction. Amounts that would bring what the banker minted and burned
+/// within the window of the policy over its threshold are proposed instead,
+/// unless the mint executes an approved proposal.
+/// Fails with [`EconomyError::MissingBankerRole`] if the user minting, or the creator
+/// of the API key minting, isn't a banker, with [`EconomyError::ApprovalRequired`] if an API key mints an amount requiring approval,
+/// or with [`EconomyError::PayeeFrozen`] if money is minted to a frozen account.
+/// Frozen accounts may still be fined, so burns aren't checked for freezes.
+pub async fn mint_money<C: ConnectionTrait + TransactionTrait>(
+    form: MintMoneyForm,
+    policy: &ApprovalPolicy,
+    ctx: &AuditContext,
+    conn: &C,
+) -> EconomyResult<Approval<transaction::Model>> {
+    // keys mint on behalf of their creator, who may have lost the role since creating them
+    if !get_or_create_economy_state(form.actor.user_id(), conn)
+        .await?
+        .banker
+    {
+        return Err(EconomyError::MissingBankerRole);
+    }
+
+    let txn = conn.begin().await?;
+
+    if form.proposal_id.is_none()
+        && mint_requires_approval(form.actor, form.amount as i64, policy, &txn).await?
+    {
+        txn.rollback().await?;
+
+        let banker_id = match form.actor {
+            Actor::User(id) => id,
+            Actor::ApiKey { .. } => return Err(EconomyError::ApprovalRequired),
+        };
+        check_mint_freeze(form.user_id, form.amount, conn).await?;
+
+        let payload = json!({
+            "user_id": form.user_id,
+            "amount": form.amount,
+            "reason": form.reason,
+        });
+        return propose(
+            policy,
+            ProposalKind::Mint,
+            banker_id,
+            None,
+            payload,
+            ctx,
+            conn,
+        )
+        .await
+        .map(Approval::Proposed)
+        .map_err(EconomyError::from);
+    }
+
+    let record = record_mint(form.user_id, form.amount, None, Some(form.actor), &txn).await?;
+
+    let balance = record
+        .payee_balance
+        .or(record.payer_balance)
+        .unwrap_or_default();
+    let action = if form.amount < 0 {
+        AuditAction::Burn
+    } else {
+        AuditAction::Mint
+    };
+    let mut after = json!({ "balance": balance });
+    if let Some(proposal_id) = form.proposal_id {
+        after["proposal_id"] = json!(proposal_id);
+    }
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(json!({ "balance": balance - form.amount })),
+            after: Some(after),
+            reason: form.reason,
+            api_key_id: form.actor.api_key_id(),
+            ..ctx.entry(
+                form.actor.user_id(),
+                action,
+                format!("user:{}", form.user_id),
+            )
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Approval::Executed(record))
+}
+
+/// Whether minting (or burning) the amount requires approval given what the banker already
+/// minted and burned within the window of the policy. API keys count towards their creator.
+/// Locks the banker's row, so that their concurrent mints can't all fit under the threshold.
+async fn mint_requires_approval<C: ConnectionTrait>(
+    actor: Actor,
+    amount: i64,
+    policy: &ApprovalPolicy,
+    conn: &C,
+) -> DbResult<bool> {
+    let banker_id = actor.user_id();
+    get_or_create_economy_state(banker_id, conn).await?;
+    add_to_balance(banker_id, 0, None, conn).await?;
+
+    let used = get_approval_usage(policy, ProposalKind::Mint, banker_id, None, conn).await?;
+    Ok(policy.requires_approval(ProposalKind::Mint, used, amount))
+}
+
+/// Whether paying the amount from the account requires approval given what the initiator,
+/// or the API key paying without one, already paid from it within the window of the policy. Only payments from organization
+/// accounts are held to the threshold. Should be called with the account locked,
+/// so that concurrent payments can't all fit under the threshold.
+async fn treasury_payment_requires_approval<C: ConnectionTrait>(
+    account_id: i32,
+    form: &TransferForm,
+    policy: &ApprovalPolicy,
+    conn: &C,
+) -> DbResult<bool> {
+    let account = get_account(account_id, conn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("account {}", account_id)))?;
+    if account.kind != AccountKind::Organization {
+        return Ok(false);
+    }
+
+    // payments of the member, or of the key, add up towards the threshold
+    let used = match (form.initiator_id, form.api_key_id) {
+        (Some(initiator_id), _) => {
+            get_approval_usage(
+                policy,
+                ProposalKind::TreasuryPayment,
+                initiator_id,
+                Some(account_id),
+                conn,
+            )
+            .await?
+        }
+        (None, Some(api_key_id)) => {
+            get_api_key_approval_usage(policy, api_key_id, account_id, conn).await?
+        }
+        (None, None) => 0,
+    };
+    Ok(policy.requires_approval(ProposalKind::TreasuryPayment, used, form.amount as i64))
+}
+
+/// Fails with [`EconomyError::PayeeFrozen`] if money is minted to the account blocking it
+async fn check_mint_freeze<C: ConnectionTrait>(
+    user_id: i32,
+    amount: i32,
+    conn: &C,
+) -> EconomyResult<()> {
+    if amount > 0 {
+        let freeze = get_active_freeze(user_id, conn).await?;
+        if matches!(freeze, Some(freeze) if freeze.block_incoming) {
+            return Err(EconomyError::PayeeFrozen);
+        }
+    }
+
+    Ok(())
+}
+
+/// Mints or burns money like [`mint`] does, within the caller's database transaction.
+/// The banker or API key minting it is recorded as its initiator.
+async fn record_mint<C: ConnectionTrait>(
+    user_id: i32,
+    amount: i32,
+    comment: Option<String>,
+    actor: Option<Actor>,
+    conn: &C,
+) -> EconomyResult<transaction::Model> {
+    get_or_create_economy_state(user_id, conn).await?;
+
+    let balance = add_to_balance(user_id, amount, None, conn).await?;
+
+    // checked with the balance locked, as freezing an account locks its balance too
+    check_mint_freeze(user_id, amount, conn).await?;
+
+    let record = if amount < 0 {
+        transaction::ActiveModel {
+            kind: Set(TransactionKind::Burn),
+            payer_id: Set(Some(user_id)),
+            payer_balance: Set(balance),
+            ..Default::default()
+        }
+    } else {
+        transaction::ActiveModel {
+            kind: Set(TransactionKind::Mint),
+            payee_id: Set(Some(user_id)),
+            payee_balance: Set(balance),
+            ..Default::default()
+        }
+    };
+
+    let record = transaction::ActiveModel {
+        amount: Set(amount.abs()),
+        comment: Set(comment),
+        initiator_id: Set(actor.map(Actor::user_id)),
+        api_key_id: Set(actor.and_then(Actor::api_key_id)),
+        created_at: Set(Utc::now()),
+        ..record
+    }
+    .insert(conn)
+    .await?;
+
+    let event = match record.kind {
+        TransactionKind::Burn => WebhookEvent::Burned,
+        _ => WebhookEvent::Minted,
+    };
+    enqueue_webhook_event(event, &record, conn).await?;
+
+    Ok(record)
+}
+
+/// Single payment of a payout
+#[derive(Clone, Debug)]
+pub struct PayoutItem {
+    pub payee_id: i32,
+    pub amount: i32,
+    pub comment: Option<String>,
+}
+
+fn payout_transfer(
+    payer_id: i32,
+    item: &PayoutItem,
+    limits: Option<TransferLimitsPolicy>,
+) -> TransferForm {
+    TransferForm {
+        payer: LedgerParty::User(payer_id),
+        payee: LedgerParty::User(item.payee_id),
+        amount: item.amount,
+        comment: item.comment.clone(),
+        initiator_id: None,
+        api_key_id: None,
+        limits,
+        approvals: None,
+        check_freezes: true,
+    }
+}
+
+/// Pays every item from the payer's balance, all in one database transaction.
+/// Fails with [`EconomyError::InsufficientFunds`] if the payer doesn't have enough money
+/// for all of the items, or with [`EconomyError::TransferLimitExceeded`] if they don't fit
+/// into the payer's limits.
+pub async fn pay_out<C: ConnectionTrait + TransactionTrait>(
+    payer_id: i32,
+    items: &[PayoutItem],
+    limits: Option<TransferLimitsPolicy>,
+    conn: &C,
+) -> EconomyResult<Vec<transaction::Model>> {
+    let txn = conn.begin().await?;
+
+    let mut records = Vec::with_capacity(items.len());
+    for item in items {
+        records.push(transfer(payout_transfer(payer_id, item, limits), &txn).await?);
+    }
+
+    txn.commit().await?;
+    Ok(records)
+}
+
+/// Pays every item from the payer's balance on its own, each in its own database transaction.
+/// Items that can't be paid don't stop the rest, so the outcome of every item is returned in order.
+pub async fn pay_out_each<C: ConnectionTrait + TransactionTrait>(
+    payer_id: i32,
+    items: &[PayoutItem],
+    limits: Option<TransferLimitsPolicy>,
+    conn: &C,
+) -> Vec<EconomyResult<transaction::Model>> {
+    let mut records = Vec::with_capacity(items.len());
+    for item in items {
+        records.push(transfer(payout_transfer(payer_id, item, limits), conn).await);
+    }
+
+    records
+}
+
+/// Mints every item on behalf of the banker, all in one database transaction.
+/// Payouts can't be proposed, so this fails with [`EconomyError::ApprovalRequired`]
+/// if their total would bring what the banker minted within the window of the policy
+/// over its threshold, and with [`EconomyError::PayeeFrozen`] if any payee blocks incoming money.
+pub async fn mint_payout<C: ConnectionTrait + TransactionTrait>(
+    actor: Actor,
+    items: &[PayoutItem],
+    policy: &ApprovalPolicy,
+    conn: &C,
+) -> EconomyResult<Vec<transaction::Model>> {
+    let txn = conn.begin().await?;
+
+    let total = items.iter().map(|item| item.amount as i64).sum();
+    if mint_requires_approval(actor, total, policy, &txn).await? {
+        return Err(EconomyError::ApprovalRequired);
+    }
+
+    let mut records = Vec::with_capacity(items.len());
+    for item in items {
+        let comment = item.comment.clone();
+        records.push(record_mint(item.payee_id, item.amount, comment, Some(actor), &txn).await?);
+    }
+
+    txn.commit().await?;
+    Ok(records)
+}
+
+/// Mints every item on behalf of the banker like [`mint_payout`], but items that can't be minted,
+/// such as ones to frozen payees, don't stop the rest, so the outcome of every item is returned
+/// in order. The threshold is still checked for the total of all items at once.
+pub async fn mint_payout_each<C: ConnectionTrait + TransactionTrait>(
+    actor: Actor,
+    items: &[PayoutItem],
+    policy: &ApprovalPolicy,
+    conn: &C,
+) -> EconomyResult<Vec<EconomyResult<transaction::Model>>> {
+    let txn = conn.begin().await?;
+
+    let total = items.iter().map(|item| item.amount as i64).sum();
+    if mint_requires_approval(actor, total, policy, &txn).await? {
+        return Err(EconomyError::ApprovalRequired);
+    }
+
+    let mut records = Vec::with_capacity(items.len());
+    for item in items {
+        // each item is minted in a savepoint, so a failed one leaves nothing behind
+        let item_txn = txn.begin().await?;
+        let comment = item.comment.clone();
+        match record_mint(item.payee_id, item.amount, comment, Some(actor), &item_txn).await {
+            Ok(record) => {
+                item_txn.commit().await?;
+                records.push(Ok(record));
+            }
+            Err(EconomyError::Database(err)) => return Err(err.into()),
+            Err(err) => records.push(Err(err)),
+        }
+    }
+
+    txn.commit().await?;
+    Ok(records)
+}
+
+/// Returns transactions with IDs greater than `after_id`, oldest first
+pub async fn get_transactions_after(
+    after_id: i32,
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<transaction::Model>> {
+    transaction::Entity::find()
+        .filter(transaction::Column::Id.gt(after_id))
+        .order_by_asc(transaction::Column::Id)
+        .limit(limit)
+        .all(conn)
+        .await
+}
+
+/// Returns ID of the latest transaction, or 0 if there is none
+pub async fn get_last_transaction_id(conn: &DbConn) -> DbResult<i32> {
+    transaction::Entity::find()
+        .order_by_desc(transaction::Column::Id)
+        .one(conn)
+        .await
+        .map(|record| record.map_or(0, |record| record.id))
+}
+
+/// Returns transactions the user took part in with IDs greater than `after_id`, oldest first
+pub async fn get_user_transactions_after(
+    user_id: i32,
+    after_id: i32,
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<transaction::Model>> {
+    transaction::Entity::find()
+        .filter(
+            Condition::any()
+                .add(transaction::Column::PayerId.eq(user_id))
+                .add(transaction::Column::PayeeId.eq(user_id)),
+        )
+        .filter(transaction::Column::Id.gt(after_id))
+        .order_by_asc(transaction::Column::Id)
+        .limit(limit)
+        .all(conn)
+        .await
+}
+
+/// Returns ID of the latest transaction the user took part in, or 0 if there is none
+pub async fn get_last_user_transaction_id(user_id: i32, conn: &DbConn) -> DbResult<i32> {
+    transaction::Entity::find()
+        .filter(
+            Condition::any()
+                .add(transaction::Column::PayerId.eq(user_id))
+                .add(transaction::Column::PayeeId.eq(user_id)),
+        )
+        .order_by_desc(transaction::Column::Id)
+        .one(conn)
+        .await
+        .map(|record| record.map_or(0, |record| record.id))
+}
+
+/// Streams transactions created within the time range (`from` inclusive, `to` exclusive), oldest first
+pub async fn stream_transactions(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    conn: &DbConn,
+) -> DbResult<impl Stream<Item = DbResult<transaction::Model>> + Send + '_> {
+    let mut query = transaction::Entity::find().order_by_asc(transaction::Column::Id);
+    if let Some(from) = from {
+        query = query.filter(transaction::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(transaction::Column::CreatedAt.lt(to));
+    }
+
+    query.stream(conn).await
+}
@@ -0,0 +1,200 @@
+use chrono::{DateTime, Duration, Utc};
+use economy_service_entity::{
+    transaction,
+    webhook::{self, WebhookEvent, WebhookEvents},
+    webhook_delivery::{self, DeliveryStatus},
+};
+use sea_orm::*;
+use serde_json::json;
+
+use crate::DbResult;
+
+pub struct CreateWebhookForm {
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_by: i32,
+}
+
+pub async fn create_webhook<C: ConnectionTrait>(
+    form: CreateWebhookForm,
+    conn: &C,
+) -> DbResult<webhook::Model> {
+    webhook::ActiveModel {
+        url: Set(form.url),
+        secret: Set(form.secret),
+        events: Set(WebhookEvents(form.events)),
+        created_by: Set(form.created_by),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+pub async fn get_webhooks(conn: &DbConn) -> DbResult<Vec<webhook::Model>> {
+    webhook::Entity::find()
+        .order_by_asc(webhook::Column::Id)
+        .all(conn)
+        .await
+}
+
+pub async fn get_webhook(id: i32, conn: &DbConn) -> DbResult<Option<webhook::Model>> {
+    webhook::Entity::find_by_id(id).one(conn).await
+}
+
+/// Deletes the webhook along with its deliveries. Returns the deleted webhook if it existed.
+pub async fn delete_webhook<C: ConnectionTrait>(
+    id: i32,
+    conn: &C,
+) -> DbResult<Option<webhook::Model>> {
+    let webhook = webhook::Entity::find_by_id(id).one(conn).await?;
+
+    if webhook.is_some() {
+        webhook_delivery::Entity::delete_many()
+            .filter(webhook_delivery::Column::WebhookId.eq(id))
+            .exec(conn)
+            .await?;
+        webhook::Entity::delete_by_id(id).exec(conn).await?;
+    }
+
+    Ok(webhook)
+}
+
+/// Schedules delivery of the event to every webhook subscribed to it.
+/// Should be called with the same database transaction as the transaction being recorded,
+/// so that events are only sent for committed balance changes.
+pub async fn enqueue_webhook_event<C: ConnectionTrait>(
+    event: WebhookEvent,
+    transaction: &transaction::Model,
+    conn: &C,
+) -> DbResult<()> {
+    let subscribers: Vec<_> = webhook::Entity::find()
+        .all(conn)
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.events.0.contains(&event))
+        .collect();
+
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+
+    let payload = json!({
+        "event": event,
+        "created_at": transaction.created_at,
+        "transaction": transaction,
+    })
+    .to_string();
+
+    let now = Utc::now();
+    webhook_delivery::Entity::insert_many(subscribers.into_iter().map(|webhook| {
+        webhook_delivery::ActiveModel {
+            webhook_id: Set(webhook.id),
+            event: Set(event),
+            payload: Set(payload.clone()),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        }
+    }))
+    .exec(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns pending deliveries whose next attempt is due, along with their webhooks
+pub async fn get_due_webhook_deliveries(
+    limit: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<(webhook_delivery::Model, Option<webhook::Model>)>> {
+    webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::Status.eq(DeliveryStatus::Pending))
+        .filter(webhook_delivery::Column::NextAttemptAt.lte(Utc::now()))
+        .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+        .limit(limit)
+        .find_also_related(webhook::Entity)
+        .all(conn)
+        .await
+}
+
+/// Postpones the next attempt of the delivery by `lease`, unless another worker already did.
+/// Returns whether the delivery was claimed by the caller.
+pub async fn claim_webhook_delivery(
+    delivery: &webhook_delivery::Model,
+    lease: Duration,
+    conn: &DbConn,
+) -> DbResult<bool> {
+    let res = webhook_delivery::Entity::update_many()
+        .col_expr(
+            webhook_delivery::Column::NextAttemptAt,
+            (Utc::now() + lease).into(),
+        )
+        .filter(webhook_delivery::Column::Id.eq(delivery.id))
+        .filter(webhook_delivery::Column::Status.eq(DeliveryStatus::Pending))
+        .filter(webhook_delivery::Column::NextAttemptAt.eq(delivery.next_attempt_at))
+        .exec(conn)
+        .await?;
+
+    Ok(res.rows_affected == 1)
+}
+
+/// Records a successful attempt of the delivery
+pub async fn complete_webhook_delivery(
+    delivery: webhook_delivery::Model,
+    status_code: i32,
+    conn: &DbConn,
+) -> DbResult<webhook_delivery::Model> {
+    let attempts = delivery.attempts + 1;
+    let mut delivery: webhook_delivery::ActiveModel = delivery.into();
+
+    delivery.status = Set(DeliveryStatus::Succeeded);
+    delivery.attempts = Set(attempts);
+    delivery.last_status_code = Set(Some(status_code));
+    delivery.last_error = Set(None);
+    delivery.delivered_at = Set(Some(Utc::now()));
+
+    delivery.update(conn).await
+}
+
+/// Records a failed attempt of the delivery.
+/// The delivery is retried at `next_attempt_at`, or marked as failed if it is `None`.
+pub async fn fail_webhook_delivery(
+    delivery: webhook_delivery::Model,
+    status_code: Option<i32>,
+    error: String,
+    next_attempt_at: Option<DateTime<Utc>>,
+    conn: &DbConn,
+) -> DbResult<webhook_delivery::Model> {
+    let attempts = delivery.attempts + 1;
+    let mut delivery: webhook_delivery::ActiveModel = delivery.into();
+
+    delivery.attempts = Set(attempts);
+    delivery.last_status_code = Set(status_code);
+    delivery.last_error = Set(Some(error));
+    match next_attempt_at {
+        Some(next_attempt_at) => delivery.next_attempt_at = Set(next_attempt_at),
+        None => delivery.status = Set(DeliveryStatus::Failed),
+    }
+
+    delivery.update(conn).await
+}
+
+/// Returns deliveries of the webhook, newest first
+pub async fn get_webhook_deliveries(
+    webhook_id: i32,
+    limit: u64,
+    offset: u64,
+    conn: &DbConn,
+) -> DbResult<Vec<webhook_delivery::Model>> {
+    webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::WebhookId.eq(webhook_id))
+        .order_by_desc(webhook_delivery::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(conn)
+        .await
+}
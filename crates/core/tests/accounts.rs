@@ -0,0 +1,415 @@
+mod common;
+
+use chrono::Duration;
+use common::{balance, connect, fund};
+use economy_service_core::{
+    create_account, create_api_key, freeze_account, get_account, get_account_members,
+    remove_account_member, set_account_member, set_account_role_limit, transfer, ApprovalPolicy,
+    CreateAccountForm, CreateApiKeyForm, EconomyError, FreezeAccountForm, LedgerParty,
+    TransferForm, TransferHeadroom, TransferLimitKind, TransferLimits, TransferLimitsPolicy,
+};
+use economy_service_entity::{account::AccountKind, account_member::AccountRole};
+use sea_orm::DbConn;
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+const CAROL: i32 = 3;
+const DAVE: i32 = 4;
+
+fn policy() -> TransferLimitsPolicy {
+    let limits = TransferLimits {
+        per_transaction: Some(50),
+        daily: Some(80),
+        window: None,
+    };
+    TransferLimitsPolicy {
+        standard: limits,
+        verified: limits,
+        merchant: limits,
+        window: Duration::days(7),
+    }
+}
+
+/// Creates an organization owned by Alice with the money she pays into it
+async fn organization(funds: i32, conn: &DbConn) -> i32 {
+    let account = create_account(
+        CreateAccountForm {
+            kind: AccountKind::Organization,
+            name: "Guild".into(),
+            owner_id: ALICE,
+        },
+        conn,
+    )
+    .await
+    .unwrap();
+
+    fund(ALICE, funds, conn).await;
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(ALICE),
+            payee: LedgerParty::Account(account.id),
+            amount: funds,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        conn,
+    )
+    .await
+    .unwrap();
+
+    account.id
+}
+
+fn spending(account_id: i32, initiator_id: i32, amount: i32) -> TransferForm {
+    TransferForm {
+        payer: LedgerParty::Account(account_id),
+        payee: LedgerParty::User(CAROL),
+        amount,
+        comment: None,
+        initiator_id: Some(initiator_id),
+        api_key_id: None,
+        limits: Some(policy()),
+        approvals: None,
+        check_freezes: true,
+    }
+}
+
+async fn account_balance(account_id: i32, conn: &DbConn) -> i32 {
+    get_account(account_id, conn)
+        .await
+        .unwrap()
+        .unwrap()
+        .balance
+}
+
+#[tokio::test]
+async fn account_keeps_an_owner() {
+    let conn = connect("account-owners").await;
+    let account_id = organization(0, &conn).await;
+
+    assert!(matches!(
+        set_account_member(account_id, ALICE, AccountRole::Treasurer, &conn).await,
+        Err(EconomyError::LastOwner)
+    ));
+    assert!(matches!(
+        remove_account_member(account_id, ALICE, &conn).await,
+        Err(EconomyError::LastOwner)
+    ));
+
+    // with another owner the first one can step down and leave
+    set_account_member(account_id, BOB, AccountRole::Owner, &conn)
+        .await
+        .unwrap();
+    set_account_member(account_id, ALICE, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+    assert!(remove_account_member(account_id, ALICE, &conn)
+        .await
+        .unwrap());
+
+    let members = get_account_members(account_id, &conn).await.unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].user_id, BOB);
+    assert_eq!(members[0].role, AccountRole::Owner);
+
+    assert!(matches!(
+        set_account_member(99, BOB, AccountRole::Owner, &conn).await,
+        Err(EconomyError::AccountNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn spending_requires_role() {
+    let conn = connect("account-spending-role").await;
+    let account_id = organization(100, &conn).await;
+
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 10), &conn).await,
+        Err(EconomyError::MissingAccountRole)
+    ));
+
+    set_account_member(account_id, BOB, AccountRole::Member, &conn)
+        .await
+        .unwrap();
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 10), &conn).await,
+        Err(EconomyError::MissingAccountRole)
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 100);
+
+    set_account_member(account_id, BOB, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+    let record = transfer(spending(account_id, BOB, 10), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.payer_account_id, Some(account_id));
+    assert_eq!(record.initiator_id, Some(BOB));
+
+    assert_eq!(account_balance(account_id, &conn).await, 90);
+    assert_eq!(balance(CAROL, &conn).await, 10);
+
+    // the account is checked for funds like users are
+    assert!(matches!(
+        transfer(spending(account_id, ALICE, 91), &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 90 })
+    ));
+}
+
+#[tokio::test]
+async fn spending_counts_towards_initiator_limits() {
+    let conn = connect("account-spending-limits").await;
+    let account_id = organization(1000, &conn).await;
+    set_account_member(account_id, BOB, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 60), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::PerTransaction,
+            remaining: 50,
+        }))
+    ));
+    transfer(spending(account_id, BOB, 50), &conn)
+        .await
+        .unwrap();
+
+    // spending of the account counts towards the daily limit of their own payments
+    fund(BOB, 100, &conn).await;
+    let own_payment = TransferForm {
+        payer: LedgerParty::User(BOB),
+        initiator_id: None,
+        ..spending(account_id, BOB, 40)
+    };
+    assert!(matches!(
+        transfer(own_payment, &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 30,
+        }))
+    ));
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 40), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(_))
+    ));
+
+    // other members have limits of their own
+    set_account_member(account_id, DAVE, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+    transfer(spending(account_id, DAVE, 50), &conn)
+        .await
+        .unwrap();
+    assert_eq!(account_balance(account_id, &conn).await, 900);
+}
+
+#[tokio::test]
+async fn spending_fits_into_role_limits() {
+    let conn = connect("account-role-limits").await;
+    let account_id = organization(1000, &conn).await;
+    set_account_member(account_id, BOB, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+    set_account_role_limit(
+        account_id,
+        AccountRole::Treasurer,
+        Some(30),
+        Some(50),
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 40), &conn).await,
+        Err(EconomyError::RoleLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::PerTransaction,
+            remaining: 30,
+        }))
+    ));
+    transfer(spending(account_id, BOB, 30), &conn)
+        .await
+        .unwrap();
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 30), &conn).await,
+        Err(EconomyError::RoleLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 20,
+        }))
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 970);
+
+    // owners aren't limited by roles
+    let form = TransferForm {
+        limits: None,
+        ..spending(account_id, ALICE, 100)
+    };
+    transfer(form, &conn).await.unwrap();
+    assert_eq!(account_balance(account_id, &conn).await, 870);
+}
+
+#[tokio::test]
+async fn frozen_initiator_cannot_spend() {
+    let conn = connect("account-spending-freeze").await;
+    let account_id = organization(100, &conn).await;
+    set_account_member(account_id, BOB, AccountRole::Treasurer, &conn)
+        .await
+        .unwrap();
+
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: ALICE,
+        block_incoming: false,
+        expires_at: None,
+    };
+    freeze_account(BOB, freeze, &conn).await.unwrap();
+
+    assert!(matches!(
+        transfer(spending(account_id, BOB, 10), &conn).await,
+        Err(EconomyError::AccountFrozen)
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 100);
+}
+
+#[tokio::test]
+async fn api_key_spends_like_treasurer() {
+    let conn = connect("account-api-key").await;
+    let account_id = organization(1000, &conn).await;
+    set_account_role_limit(
+        account_id,
+        AccountRole::Treasurer,
+        Some(30),
+        Some(50),
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    let mut keys = Vec::new();
+    for name in ["Shop", "Bank"] {
+        let key = create_api_key(
+            CreateApiKeyForm {
+                name: name.into(),
+                prefix: name.into(),
+                key_hash: name.into(),
+                scopes: vec![format!("pay:from:{}", account_id)],
+                created_by: DAVE,
+                expires_at: None,
+            },
+            &conn,
+        )
+        .await
+        .unwrap();
+        keys.push(key.id);
+    }
+    let spending = |api_key_id, amount| TransferForm {
+        initiator_id: None,
+        api_key_id: Some(api_key_id),
+        limits: None,
+        ..spending(account_id, ALICE, amount)
+    };
+
+    assert!(matches!(
+        transfer(spending(keys[0], 40), &conn).await,
+        Err(EconomyError::RoleLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::PerTransaction,
+            remaining: 30,
+        }))
+    ));
+    transfer(spending(keys[0], 30), &conn).await.unwrap();
+    assert!(matches!(
+        transfer(spending(keys[0], 30), &conn).await,
+        Err(EconomyError::RoleLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 20,
+        }))
+    ));
+
+    // every key has limits of its own
+    transfer(spending(keys[1], 30), &conn).await.unwrap();
+    assert_eq!(account_balance(account_id, &conn).await, 940);
+
+    // keys of a frozen user can't spend
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: ALICE,
+        block_incoming: false,
+        expires_at: None,
+    };
+    freeze_account(DAVE, freeze, &conn).await.unwrap();
+    assert!(matches!(
+        transfer(spending(keys[1], 10), &conn).await,
+        Err(EconomyError::AccountFrozen)
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 940);
+}
+
+#[tokio::test]
+async fn treasury_payments_over_threshold_require_approval() {
+    let conn = connect("account-approval").await;
+    let account_id = organization(1000, &conn).await;
+    let approvals = ApprovalPolicy {
+        mint_threshold: None,
+        treasury_payment_threshold: Some(100),
+        required: 1,
+        expiry: Duration::hours(72),
+        window: Duration::hours(24),
+    };
+    let spending = |amount| TransferForm {
+        limits: None,
+        approvals: Some(approvals),
+        ..spending(account_id, ALICE, amount)
+    };
+
+    // payments reaching the threshold don't need approval, but the ones going over it do
+    transfer(spending(60), &conn).await.unwrap();
+    transfer(spending(40), &conn).await.unwrap();
+    assert!(matches!(
+        transfer(spending(1), &conn).await,
+        Err(EconomyError::ApprovalRequired)
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 900);
+
+    // approved payments are executed without the policy
+    transfer(
+        TransferForm {
+            approvals: None,
+            ..spending(1)
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert_eq!(account_balance(account_id, &conn).await, 899);
+
+    // keys pay without an initiator, so their own payments add up instead
+    let key = create_api_key(
+        CreateApiKeyForm {
+            name: "Shop".into(),
+            prefix: "Shop".into(),
+            key_hash: "Shop".into(),
+            scopes: vec![format!("pay:from:{}", account_id)],
+            created_by: ALICE,
+            expires_at: None,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    let key_spending = |amount| TransferForm {
+        initiator_id: None,
+        api_key_id: Some(key.id),
+        ..spending(amount)
+    };
+    transfer(key_spending(100), &conn).await.unwrap();
+    assert!(matches!(
+        transfer(key_spending(1), &conn).await,
+        Err(EconomyError::ApprovalRequired)
+    ));
+    assert_eq!(account_balance(account_id, &conn).await, 799);
+}
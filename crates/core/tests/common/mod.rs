@@ -0,0 +1,34 @@
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use economy_service_core::{get_or_create_economy_state, mint};
+use economy_service_migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DbConn};
+
+/// Connects to a fresh, migrated SQLite database named after the test
+pub async fn connect(name: &str) -> DbConn {
+    let db_path = std::env::temp_dir().join(format!(
+        "economy-core-{}-{}.sqlite",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&db_path);
+    let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+        .await
+        .unwrap();
+    Migrator::up(&conn, None).await.unwrap();
+    conn
+}
+
+/// Gives the user money, creating their economy state if needed
+pub async fn fund(user_id: i32, amount: i32, conn: &DbConn) {
+    get_or_create_economy_state(user_id, conn).await.unwrap();
+    mint(user_id, amount, conn).await.unwrap();
+}
+
+pub async fn balance(user_id: i32, conn: &DbConn) -> i32 {
+    get_or_create_economy_state(user_id, conn)
+        .await
+        .unwrap()
+        .balance
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{balance, connect, fund};
+use economy_service_core::{
+    get_or_create_economy_state, import_balances, BankerChange, EconomyError, ImportEntry,
+};
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+
+fn entry(user_id: i32, balance: i32) -> ImportEntry {
+    ImportEntry {
+        user_id,
+        balance,
+        banker: None,
+    }
+}
+
+#[tokio::test]
+async fn import_credits_empty_balances() {
+    let conn = connect("import").await;
+
+    import_balances(&[entry(ALICE, 100), entry(BOB, 0)], &conn)
+        .await
+        .unwrap();
+    assert_eq!(balance(ALICE, &conn).await, 100);
+    assert_eq!(balance(BOB, &conn).await, 0);
+}
+
+#[tokio::test]
+async fn import_rejects_funded_balances() {
+    let conn = connect("import-funded").await;
+    fund(BOB, 5, &conn).await;
+
+    // funded after the import was validated, so the whole import is rolled back
+    assert!(matches!(
+        import_balances(&[entry(ALICE, 100), entry(BOB, 10)], &conn).await,
+        Err(EconomyError::AlreadyFunded { user_id: BOB })
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 0);
+    assert_eq!(balance(BOB, &conn).await, 5);
+}
+
+#[tokio::test]
+async fn import_reports_banker_changes() {
+    let conn = connect("import-banker").await;
+    let entries = [
+        ImportEntry {
+            banker: Some(true),
+            ..entry(ALICE, 100)
+        },
+        ImportEntry {
+            banker: Some(false),
+            ..entry(BOB, 10)
+        },
+    ];
+
+    // Bob isn't a banker already, so only Alice changes
+    let changes = import_balances(&entries, &conn).await.unwrap();
+    assert_eq!(
+        changes,
+        vec![BankerChange {
+            user_id: ALICE,
+            before: false,
+            after: true,
+        }]
+    );
+    let alice = get_or_create_economy_state(ALICE, &conn).await.unwrap();
+    assert!(alice.banker);
+}
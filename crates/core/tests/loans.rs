@@ -0,0 +1,274 @@
+mod common;
+
+use chrono::Duration;
+use common::{balance, connect, fund};
+use economy_service_core::{
+    accept_loan, collect_installment, get_loan, get_transfer_usage, offer_loan, transfer,
+    EconomyError, LatePaymentPolicy, LedgerParty, OfferLoanForm, TransferForm, TransferHeadroom,
+    TransferLimitKind, TransferLimits, TransferLimitsPolicy,
+};
+use economy_service_entity::{
+    loan::{self, LoanStatus},
+    transaction::TransactionKind,
+};
+use sea_orm::DbConn;
+
+const LENDER: i32 = 1;
+const BORROWER: i32 = 2;
+const SHOP: i32 = 3;
+
+const POLICY: LatePaymentPolicy = LatePaymentPolicy {
+    late_fee_bps: 500,
+    default_after: 2,
+};
+
+/// Terms of a loan of the principal from the lender to the borrower
+fn offering(principal: i32) -> OfferLoanForm {
+    OfferLoanForm {
+        lender_id: LENDER,
+        borrower_id: BORROWER,
+        principal,
+        interest_rate_bps: 1000,
+        installments: 2,
+        interval_hours: 24,
+        comment: None,
+    }
+}
+
+/// Lends 100 to the borrower, to be repaid with 10% interest in two installments of 55
+async fn active_loan(conn: &DbConn) -> loan::Model {
+    fund(LENDER, 100, conn).await;
+    let offer = offer_loan(offering(100), None, conn).await.unwrap();
+
+    let (loan, _) = accept_loan(offer.id, None, conn).await.unwrap().unwrap();
+    assert_eq!(loan.total_due, 110);
+    assert_eq!(loan.installment_amount, 55);
+    loan
+}
+
+/// The borrower spends the amount, leaving less to collect
+async fn spend(amount: i32, conn: &DbConn) {
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(BORROWER),
+            payee: LedgerParty::User(SHOP),
+            amount,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        conn,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn collect_scheduled_installment() {
+    let conn = connect("loan-scheduled").await;
+    let loan = active_loan(&conn).await;
+
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.missed, 0);
+    let repayment = collection.repayment.unwrap();
+    assert_eq!(repayment.kind, TransactionKind::Repayment);
+    assert_eq!(repayment.loan_id, Some(loan.id));
+    assert_eq!(repayment.amount, 55);
+
+    let collected = collection.loan;
+    assert_eq!(collected.status, LoanStatus::Active);
+    assert_eq!(collected.repaid, 55);
+    assert_eq!(
+        collected.next_due_at,
+        loan.next_due_at.map(|due_at| due_at + Duration::hours(24))
+    );
+    assert_eq!(balance(BORROWER, &conn).await, 45);
+    assert_eq!(balance(LENDER, &conn).await, 55);
+
+    // the installment is collected only once
+    assert!(collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(balance(BORROWER, &conn).await, 45);
+}
+
+#[tokio::test]
+async fn collect_partial_installment_with_late_fee() {
+    let conn = connect("loan-partial").await;
+    let loan = active_loan(&conn).await;
+    spend(80, &conn).await;
+
+    // 35 of the installment is missed, and charged a fee of 2 rounded up
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.repayment.unwrap().amount, 20);
+    assert_eq!(collection.missed, 35);
+
+    let loan = collection.loan;
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.repaid, 20);
+    assert_eq!(loan.overdue, 37);
+    assert_eq!(loan.total_due, 112);
+    assert_eq!(loan.missed_installments, 1);
+    assert_eq!(balance(BORROWER, &conn).await, 0);
+
+    // the missed amount is carried over to the next installment
+    fund(BORROWER, 100, &conn).await;
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.repayment.unwrap().amount, 92);
+    assert_eq!(collection.missed, 0);
+    assert_eq!(collection.loan.overdue, 0);
+    assert_eq!(collection.loan.missed_installments, 0);
+}
+
+#[tokio::test]
+async fn loan_defaults_after_missed_installments() {
+    let conn = connect("loan-default").await;
+    let loan = active_loan(&conn).await;
+    spend(100, &conn).await;
+
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(collection.repayment.is_none());
+    let loan = collection.loan;
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.overdue, 58);
+    assert_eq!(loan.missed_installments, 1);
+
+    // the whole rest is missed the second time in a row
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.missed, 113);
+    let loan = collection.loan;
+    assert_eq!(loan.status, LoanStatus::Defaulted);
+    assert_eq!(loan.overdue, 119);
+    assert_eq!(loan.total_due, 119);
+    assert_eq!(loan.missed_installments, 2);
+
+    // collection goes on without late fees
+    fund(BORROWER, 50, &conn).await;
+    let collection = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.repayment.unwrap().amount, 50);
+    let loan = collection.loan;
+    assert_eq!(loan.status, LoanStatus::Defaulted);
+    assert_eq!(loan.repaid, 50);
+    assert_eq!(loan.overdue, 69);
+    assert_eq!(loan.total_due, 119);
+    assert_eq!(loan.missed_installments, 3);
+}
+
+#[tokio::test]
+async fn loan_closes_once_repaid() {
+    let conn = connect("loan-repaid").await;
+    let loan = active_loan(&conn).await;
+    fund(BORROWER, 10, &conn).await;
+
+    let loan = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap()
+        .loan;
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.closed_at, None);
+
+    let loan = collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .unwrap()
+        .loan;
+    assert_eq!(loan.status, LoanStatus::Repaid);
+    assert_eq!(loan.repaid, 110);
+    assert_eq!(loan.next_due_at, None);
+    assert!(loan.closed_at.is_some());
+    assert_eq!(balance(BORROWER, &conn).await, 0);
+    assert_eq!(balance(LENDER, &conn).await, 110);
+
+    // nothing is due anymore
+    assert!(collect_installment(&loan, POLICY, &conn)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn loans_count_towards_lender_limits() {
+    let conn = connect("loan-limits").await;
+    fund(LENDER, 300, &conn).await;
+    let limits = TransferLimits {
+        daily: Some(100),
+        ..Default::default()
+    };
+    let policy = TransferLimitsPolicy {
+        standard: limits,
+        verified: limits,
+        merchant: limits,
+        window: Duration::days(7),
+    };
+    // offers that couldn't be accepted right now fail early
+    assert!(matches!(
+        offer_loan(offering(150), Some(policy), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 100,
+        }))
+    ));
+
+    // the lender could pay in the meantime, so the principal is checked again on acceptance
+    let offer = offer_loan(offering(80), Some(policy), &conn).await.unwrap();
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(LENDER),
+            payee: LedgerParty::User(SHOP),
+            amount: 30,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: Some(policy),
+            approvals: None,
+            check_freezes: true,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        accept_loan(offer.id, Some(policy), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 70,
+        }))
+    ));
+    let offer = get_loan(offer.id, &conn).await.unwrap().unwrap();
+    assert_eq!(offer.status, LoanStatus::Offered);
+    assert_eq!(balance(BORROWER, &conn).await, 0);
+
+    // accepted loans are counted like payments
+    let offer = offer_loan(offering(60), Some(policy), &conn).await.unwrap();
+    accept_loan(offer.id, Some(policy), &conn)
+        .await
+        .unwrap()
+        .unwrap();
+    let usage = get_transfer_usage(LENDER, policy.window, &conn)
+        .await
+        .unwrap();
+    assert_eq!(usage.daily, 90);
+}
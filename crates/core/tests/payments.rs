@@ -0,0 +1,453 @@
+mod common;
+
+use chrono::Duration;
+use common::{balance, connect, fund};
+use economy_service_core::{
+    create_account, freeze_account, get_or_create_economy_state, make_payment, mint_money,
+    mint_payout, mint_payout_each, pay_out, pay_out_each, update_economy_state, Actor, Approval,
+    ApprovalPolicy, AuditContext, CreateAccountForm, EconomyError, FreezeAccountForm, LedgerParty,
+    MintMoneyForm, PaymentForm, PayoutItem, TransferLimits, TransferLimitsPolicy,
+    UpdateEconomyStateForm,
+};
+use economy_service_entity::{account::AccountKind, proposal::ProposalKind};
+use sea_orm::DbConn;
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+const BANKER: i32 = 3;
+const CAROL: i32 = 4;
+
+fn unlimited() -> TransferLimitsPolicy {
+    let limits = TransferLimits {
+        per_transaction: None,
+        daily: None,
+        window: None,
+    };
+    TransferLimitsPolicy {
+        standard: limits,
+        verified: limits,
+        merchant: limits,
+        window: Duration::days(7),
+    }
+}
+
+fn approvals() -> ApprovalPolicy {
+    ApprovalPolicy {
+        mint_threshold: Some(1000),
+        treasury_payment_threshold: None,
+        required: 2,
+        expiry: Duration::hours(72),
+        window: Duration::hours(24),
+    }
+}
+
+fn payment(payee: LedgerParty, amount: i32) -> PaymentForm {
+    PaymentForm {
+        payer_id: ALICE,
+        payee,
+        amount,
+        comment: None,
+    }
+}
+
+fn minting(actor: Actor, amount: i32) -> MintMoneyForm {
+    MintMoneyForm {
+        user_id: ALICE,
+        amount,
+        reason: None,
+        actor,
+        proposal_id: None,
+    }
+}
+
+fn item(payee_id: i32, amount: i32) -> PayoutItem {
+    PayoutItem {
+        payee_id,
+        amount,
+        comment: None,
+    }
+}
+
+async fn make_banker(user_id: i32, conn: &DbConn) {
+    let state = get_or_create_economy_state(user_id, conn).await.unwrap();
+    let form = UpdateEconomyStateForm {
+        banker: Some(true),
+        ..Default::default()
+    };
+    update_economy_state(state.into(), form, conn)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn make_payment_validates_payment() {
+    let conn = connect("payment-validation").await;
+    fund(ALICE, 100, &conn).await;
+
+    assert!(matches!(
+        make_payment(payment(LedgerParty::User(BOB), 0), unlimited(), &conn).await,
+        Err(EconomyError::InvalidAmount)
+    ));
+    assert!(matches!(
+        make_payment(payment(LedgerParty::User(ALICE), 10), unlimited(), &conn).await,
+        Err(EconomyError::SelfPayment)
+    ));
+    assert!(matches!(
+        make_payment(payment(LedgerParty::Account(99), 10), unlimited(), &conn).await,
+        Err(EconomyError::AccountNotFound)
+    ));
+    assert!(matches!(
+        make_payment(payment(LedgerParty::User(BOB), 101), unlimited(), &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 100 })
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 100);
+}
+
+#[tokio::test]
+async fn make_payment_pays_users_and_accounts() {
+    let conn = connect("payment-parties").await;
+    fund(ALICE, 100, &conn).await;
+    let account = create_account(
+        CreateAccountForm {
+            kind: AccountKind::Shop,
+            name: "Shop".into(),
+            owner_id: BOB,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    let record = make_payment(payment(LedgerParty::User(BOB), 30), unlimited(), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.payee_id, Some(BOB));
+
+    let record = make_payment(
+        payment(LedgerParty::Account(account.id), 20),
+        unlimited(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert_eq!(record.payee_account_id, Some(account.id));
+
+    assert_eq!(balance(ALICE, &conn).await, 50);
+    assert_eq!(balance(BOB, &conn).await, 30);
+}
+
+#[tokio::test]
+async fn mint_money_requires_banker() {
+    let conn = connect("mint-banker").await;
+
+    assert!(matches!(
+        mint_money(
+            minting(Actor::User(BOB), 10),
+            &approvals(),
+            &AuditContext::default(),
+            &conn
+        )
+        .await,
+        Err(EconomyError::MissingBankerRole)
+    ));
+
+    make_banker(BANKER, &conn).await;
+    let res = mint_money(
+        minting(Actor::User(BANKER), 10),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(res, Approval::Executed(record) if record.payee_id == Some(ALICE)));
+    assert_eq!(balance(ALICE, &conn).await, 10);
+}
+
+#[tokio::test]
+async fn mint_money_proposes_large_amounts() {
+    let conn = connect("mint-approval").await;
+    make_banker(BANKER, &conn).await;
+
+    let res = mint_money(
+        minting(Actor::User(BANKER), 1001),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        res,
+        Approval::Proposed(proposal)
+            if proposal.kind == ProposalKind::Mint && proposal.proposer_id == BANKER
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 0);
+
+    // API keys can't propose
+    let key = Actor::ApiKey {
+        id: 1,
+        created_by: BANKER,
+    };
+    assert!(matches!(
+        mint_money(
+            minting(key, 1001),
+            &approvals(),
+            &AuditContext::default(),
+            &conn
+        )
+        .await,
+        Err(EconomyError::ApprovalRequired)
+    ));
+
+    // executing the approved proposal mints right away
+    let res = mint_money(
+        MintMoneyForm {
+            proposal_id: Some(1),
+            ..minting(Actor::User(BANKER), 1001)
+        },
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(res, Approval::Executed(_)));
+    assert_eq!(balance(ALICE, &conn).await, 1001);
+}
+
+#[tokio::test]
+async fn mint_money_threshold_is_cumulative() {
+    let conn = connect("mint-cumulative").await;
+    make_banker(BANKER, &conn).await;
+    make_banker(BOB, &conn).await;
+
+    for amount in [600, -300] {
+        let res = mint_money(
+            minting(Actor::User(BANKER), amount),
+            &approvals(),
+            &AuditContext::default(),
+            &conn,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(res, Approval::Executed(record) if record.initiator_id == Some(BANKER)));
+    }
+
+    // burns count too, so 900 in total were minted by the banker,
+    // and another 100 reach the threshold without going over it
+    let res = mint_money(
+        minting(Actor::User(BANKER), 100),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(res, Approval::Executed(_)));
+
+    let res = mint_money(
+        minting(Actor::User(BANKER), 1),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(res, Approval::Proposed(_)));
+
+    // API keys count towards the banker who created them
+    let key = Actor::ApiKey {
+        id: 1,
+        created_by: BANKER,
+    };
+    assert!(matches!(
+        mint_money(
+            minting(key, 1),
+            &approvals(),
+            &AuditContext::default(),
+            &conn
+        )
+        .await,
+        Err(EconomyError::ApprovalRequired)
+    ));
+
+    // other bankers have thresholds of their own
+    let res = mint_money(
+        minting(Actor::User(BOB), 100),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(res, Approval::Executed(_)));
+    assert_eq!(balance(ALICE, &conn).await, 500);
+}
+
+#[tokio::test]
+async fn mint_money_to_frozen_account_only_burns() {
+    let conn = connect("mint-frozen").await;
+    make_banker(BANKER, &conn).await;
+    fund(ALICE, 50, &conn).await;
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: BANKER,
+        block_incoming: true,
+        expires_at: None,
+    };
+    freeze_account(ALICE, freeze, &conn).await.unwrap();
+
+    assert!(matches!(
+        mint_money(
+            minting(Actor::User(BANKER), 10),
+            &approvals(),
+            &AuditContext::default(),
+            &conn
+        )
+        .await,
+        Err(EconomyError::PayeeFrozen)
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 50);
+
+    mint_money(
+        minting(Actor::User(BANKER), -20),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert_eq!(balance(ALICE, &conn).await, 30);
+}
+
+#[tokio::test]
+async fn pay_out_pays_all_or_nothing() {
+    let conn = connect("payout-atomic").await;
+    fund(ALICE, 100, &conn).await;
+
+    assert!(matches!(
+        pay_out(ALICE, &[item(BOB, 60), item(CAROL, 60)], None, &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 40 })
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 100);
+    assert_eq!(balance(BOB, &conn).await, 0);
+
+    let records = pay_out(ALICE, &[item(BOB, 60), item(CAROL, 40)], None, &conn)
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(balance(ALICE, &conn).await, 0);
+    assert_eq!(balance(CAROL, &conn).await, 40);
+}
+
+#[tokio::test]
+async fn pay_out_each_continues_after_failures() {
+    let conn = connect("payout-best-effort").await;
+    fund(ALICE, 100, &conn).await;
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: BANKER,
+        block_incoming: true,
+        expires_at: None,
+    };
+    freeze_account(CAROL, freeze, &conn).await.unwrap();
+
+    let limits = TransferLimits {
+        per_transaction: Some(50),
+        ..Default::default()
+    };
+    let policy = TransferLimitsPolicy {
+        standard: limits,
+        ..unlimited()
+    };
+    let items = [
+        item(BOB, 30),
+        item(CAROL, 10),
+        item(BOB, 60),
+        item(BOB, 40),
+        item(BOB, 40),
+        item(BOB, 30),
+    ];
+    let records = pay_out_each(ALICE, &items, Some(policy), &conn).await;
+
+    assert!(matches!(&records[0], Ok(record) if record.amount == 30));
+    assert!(matches!(records[1], Err(EconomyError::PayeeFrozen)));
+    assert!(matches!(
+        records[2],
+        Err(EconomyError::TransferLimitExceeded(_))
+    ));
+    assert!(matches!(&records[3], Ok(record) if record.amount == 40));
+    assert!(matches!(
+        records[4],
+        Err(EconomyError::InsufficientFunds { available: 30 })
+    ));
+    assert!(matches!(&records[5], Ok(record) if record.amount == 30));
+
+    assert_eq!(balance(ALICE, &conn).await, 0);
+    assert_eq!(balance(BOB, &conn).await, 100);
+    assert_eq!(balance(CAROL, &conn).await, 0);
+}
+
+#[tokio::test]
+async fn mint_payout_threshold_is_cumulative() {
+    let conn = connect("payout-mint").await;
+    make_banker(BANKER, &conn).await;
+
+    mint_money(
+        minting(Actor::User(BANKER), 600),
+        &approvals(),
+        &AuditContext::default(),
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    // 600 were minted already, so another 401 go over the threshold
+    let items = [item(BOB, 200), item(CAROL, 201)];
+    assert!(matches!(
+        mint_payout(Actor::User(BANKER), &items, &approvals(), &conn).await,
+        Err(EconomyError::ApprovalRequired)
+    ));
+    assert_eq!(balance(BOB, &conn).await, 0);
+
+    let items = [item(BOB, 200), item(CAROL, 200)];
+    let records = mint_payout(Actor::User(BANKER), &items, &approvals(), &conn)
+        .await
+        .unwrap();
+    assert!(records
+        .iter()
+        .all(|record| record.initiator_id == Some(BANKER)));
+    assert_eq!(balance(BOB, &conn).await, 200);
+    assert_eq!(balance(CAROL, &conn).await, 200);
+}
+
+#[tokio::test]
+async fn mint_payout_skips_frozen_payees_only_when_asked() {
+    let conn = connect("payout-mint-frozen").await;
+    make_banker(BANKER, &conn).await;
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: BANKER,
+        block_incoming: true,
+        expires_at: None,
+    };
+    freeze_account(CAROL, freeze, &conn).await.unwrap();
+
+    let items = [item(BOB, 20), item(CAROL, 30), item(ALICE, 40)];
+    assert!(matches!(
+        mint_payout(Actor::User(BANKER), &items, &approvals(), &conn).await,
+        Err(EconomyError::PayeeFrozen)
+    ));
+    assert_eq!(balance(BOB, &conn).await, 0);
+
+    let records = mint_payout_each(Actor::User(BANKER), &items, &approvals(), &conn)
+        .await
+        .unwrap();
+    assert!(matches!(&records[0], Ok(record) if record.amount == 20));
+    assert!(matches!(records[1], Err(EconomyError::PayeeFrozen)));
+    assert!(matches!(&records[2], Ok(record) if record.amount == 40));
+    assert_eq!(balance(BOB, &conn).await, 20);
+    assert_eq!(balance(CAROL, &conn).await, 0);
+    assert_eq!(balance(ALICE, &conn).await, 40);
+}
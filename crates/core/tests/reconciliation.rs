@@ -0,0 +1,94 @@
+mod common;
+
+use common::{balance, fund};
+use economy_service_core::{
+    get_or_create_economy_state, get_user_transactions_after, reconcile, transfer, LedgerParty,
+    TransferForm,
+};
+use economy_service_entity::{economy_state, transaction::TransactionKind};
+use economy_service_migration::{Migrator, MigratorTrait};
+use sea_orm::{ActiveModelTrait, Database, DbConn, Set};
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+const CAROL: i32 = 3;
+
+/// Sets the balance without recording it, like balances held before the ledger
+async fn set_legacy_balance(user_id: i32, balance: i32, conn: &DbConn) {
+    let mut state: economy_state::ActiveModel = get_or_create_economy_state(user_id, conn)
+        .await
+        .unwrap()
+        .into();
+    state.balance = Set(balance);
+    state.update(conn).await.unwrap();
+}
+
+#[tokio::test]
+async fn backfill_records_legacy_balances() {
+    let db_path = std::env::temp_dir().join(format!(
+        "economy-core-backfill-{}.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&db_path);
+    let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+        .await
+        .unwrap();
+
+    // everything but the backfill is applied
+    Migrator::up(&conn, Some(Migrator::migrations().len() as u32 - 1))
+        .await
+        .unwrap();
+    set_legacy_balance(ALICE, 100, &conn).await;
+    fund(CAROL, 20, &conn).await;
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(CAROL),
+            payee: LedgerParty::User(ALICE),
+            amount: 10,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: false,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    // money taken outside of the ledger
+    fund(BOB, 20, &conn).await;
+    set_legacy_balance(BOB, 5, &conn).await;
+
+    let before = reconcile(&conn).await.unwrap();
+    assert_eq!(before.drifted_accounts.len(), 2);
+
+    Migrator::up(&conn, None).await.unwrap();
+
+    let after = reconcile(&conn).await.unwrap();
+    assert_eq!(after.drift, 0);
+    assert!(after.drifted_accounts.is_empty());
+    assert_eq!(balance(ALICE, &conn).await, 110);
+
+    let records = get_user_transactions_after(ALICE, 0, 10, &conn)
+        .await
+        .unwrap();
+    let import = records.last().unwrap();
+    assert_eq!(import.kind, TransactionKind::Import);
+    assert_eq!(import.amount, 100);
+    assert_eq!(import.payee_balance, Some(110));
+
+    let records = get_user_transactions_after(BOB, 0, 10, &conn)
+        .await
+        .unwrap();
+    let burn = records.last().unwrap();
+    assert_eq!(burn.kind, TransactionKind::Burn);
+    assert_eq!(burn.amount, 15);
+    assert_eq!(burn.payer_balance, Some(5));
+
+    // rolling back removes only the backfilled transactions
+    Migrator::down(&conn, Some(1)).await.unwrap();
+    assert_eq!(reconcile(&conn).await.unwrap().drifted_accounts.len(), 2);
+
+    let _ = std::fs::remove_file(&db_path);
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use common::{balance, connect, fund};
+use economy_service_core::{
+    freeze_account, get_reversals, get_transaction, mint, reverse, transfer, EconomyError,
+    FreezeAccountForm, LedgerParty, ReverseForm, TransferForm,
+};
+use economy_service_entity::transaction;
+use sea_orm::DbConn;
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+const BANKER: i32 = 3;
+
+/// Alice pays the amount to Bob
+async fn payment(amount: i32, conn: &DbConn) -> transaction::Model {
+    fund(ALICE, amount, conn).await;
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(ALICE),
+            payee: LedgerParty::User(BOB),
+            amount,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        conn,
+    )
+    .await
+    .unwrap()
+}
+
+fn reversal(amount: Option<i32>, force: bool) -> ReverseForm {
+    ReverseForm {
+        amount,
+        force,
+        comment: None,
+        initiator_id: Some(BOB),
+        check_freezes: !force,
+    }
+}
+
+#[tokio::test]
+async fn reverse_partially() {
+    let conn = connect("reverse-partial").await;
+    let payment = payment(100, &conn).await;
+
+    let record = reverse(&payment, reversal(Some(30), false), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.payer_id, Some(BOB));
+    assert_eq!(record.payee_id, Some(ALICE));
+    assert_eq!(record.amount, 30);
+    assert_eq!(record.reverses_id, Some(payment.id));
+
+    // the rest of the payment is reversed if no amount is given,
+    // even when reversing with the stale payment
+    let record = reverse(&payment, reversal(None, false), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.amount, 70);
+
+    assert!(matches!(
+        reverse(&payment, reversal(None, false), &conn).await,
+        Err(EconomyError::AlreadyReversed)
+    ));
+
+    let payment = get_transaction(payment.id, &conn).await.unwrap().unwrap();
+    assert_eq!(payment.reversed_amount, 100);
+    assert_eq!(get_reversals(payment.id, &conn).await.unwrap().len(), 2);
+    assert_eq!(balance(ALICE, &conn).await, 100);
+    assert_eq!(balance(BOB, &conn).await, 0);
+}
+
+#[tokio::test]
+async fn reverse_validates_amount() {
+    let conn = connect("reverse-validation").await;
+    let payment = payment(100, &conn).await;
+    reverse(&payment, reversal(Some(60), false), &conn)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        reverse(&payment, reversal(Some(0), false), &conn).await,
+        Err(EconomyError::InvalidAmount)
+    ));
+    assert!(matches!(
+        reverse(&payment, reversal(Some(41), false), &conn).await,
+        Err(EconomyError::ReversalExceedsRemaining { remaining: 40 })
+    ));
+
+    // the payee can't give back money they spent
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(BOB),
+            payee: LedgerParty::User(BANKER),
+            amount: 30,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        reverse(&payment, reversal(Some(40), false), &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 10 })
+    ));
+
+    // only payments can be reversed
+    let minted = mint(ALICE, 10, &conn).await.unwrap();
+    assert!(matches!(
+        reverse(&minted, reversal(None, false), &conn).await,
+        Err(EconomyError::NotReversible)
+    ));
+
+    let payment = get_transaction(payment.id, &conn).await.unwrap().unwrap();
+    assert_eq!(payment.reversed_amount, 60);
+}
+
+#[tokio::test]
+async fn forced_reversal_takes_what_payee_has() {
+    let conn = connect("reverse-forced").await;
+    let payment = payment(100, &conn).await;
+
+    // the payee spent most of the money
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(BOB),
+            payee: LedgerParty::User(BANKER),
+            amount: 75,
+            comment: None,
+            initiator_id: None,
+            api_key_id: None,
+            limits: None,
+            approvals: None,
+            check_freezes: true,
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    let record = reverse(&payment, reversal(None, true), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.amount, 25);
+    assert_eq!(balance(BOB, &conn).await, 0);
+    assert_eq!(balance(ALICE, &conn).await, 25);
+
+    // the rest can be reversed once they have money again, but not before
+    assert!(matches!(
+        reverse(&payment, reversal(None, true), &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 0 })
+    ));
+    fund(BOB, 500, &conn).await;
+    let record = reverse(&payment, reversal(None, true), &conn)
+        .await
+        .unwrap();
+    assert_eq!(record.amount, 75);
+    assert_eq!(balance(BOB, &conn).await, 425);
+
+    let payment = get_transaction(payment.id, &conn).await.unwrap().unwrap();
+    assert_eq!(payment.reversed_amount, 100);
+}
+
+#[tokio::test]
+async fn refunds_respect_freezes() {
+    let conn = connect("reverse-frozen").await;
+    let payment = payment(100, &conn).await;
+
+    // the payer blocks incoming money, so only a forced reversal gives it back
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: BANKER,
+        block_incoming: true,
+        expires_at: None,
+    };
+    freeze_account(ALICE, freeze, &conn).await.unwrap();
+    assert!(matches!(
+        reverse(&payment, reversal(Some(30), false), &conn).await,
+        Err(EconomyError::PayeeFrozen)
+    ));
+    assert_eq!(balance(BOB, &conn).await, 100);
+
+    reverse(&payment, reversal(Some(30), true), &conn)
+        .await
+        .unwrap();
+    assert_eq!(balance(ALICE, &conn).await, 30);
+
+    // a frozen payee can't refund either
+    let freeze = FreezeAccountForm {
+        reason: "fraud".into(),
+        actor_id: BANKER,
+        block_incoming: false,
+        expires_at: None,
+    };
+    freeze_account(BOB, freeze, &conn).await.unwrap();
+    assert!(matches!(
+        reverse(&payment, reversal(Some(30), false), &conn).await,
+        Err(EconomyError::AccountFrozen)
+    ));
+    assert_eq!(balance(BOB, &conn).await, 70);
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use chrono::Duration;
+use chrono::Utc;
+use common::{balance, connect, fund};
+use economy_service_core::{
+    freeze_account, transfer, unfreeze_account, EconomyError, FreezeAccountForm, LedgerParty,
+    TransferForm, TransferHeadroom, TransferLimitKind, TransferLimits, TransferLimitsPolicy,
+};
+
+const ALICE: i32 = 1;
+const BOB: i32 = 2;
+
+fn policy() -> TransferLimitsPolicy {
+    let limits = TransferLimits {
+        per_transaction: Some(50),
+        daily: Some(80),
+        window: None,
+    };
+    TransferLimitsPolicy {
+        standard: limits,
+        verified: limits,
+        merchant: limits,
+        window: Duration::days(7),
+    }
+}
+
+fn payment(amount: i32) -> TransferForm {
+    TransferForm {
+        payer: LedgerParty::User(ALICE),
+        payee: LedgerParty::User(BOB),
+        amount,
+        comment: None,
+        initiator_id: None,
+        api_key_id: None,
+        limits: Some(policy()),
+        approvals: None,
+        check_freezes: true,
+    }
+}
+
+#[tokio::test]
+async fn transfer_enforces_limits() {
+    let conn = connect("transfer-limits").await;
+    fund(ALICE, 1000, &conn).await;
+
+    assert!(matches!(
+        transfer(payment(60), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::PerTransaction,
+            remaining: 50,
+        }))
+    ));
+
+    transfer(payment(50), &conn).await.unwrap();
+
+    // the payment above counts towards the daily limit
+    assert!(matches!(
+        transfer(payment(40), &conn).await,
+        Err(EconomyError::TransferLimitExceeded(TransferHeadroom {
+            kind: TransferLimitKind::Daily,
+            remaining: 30,
+        }))
+    ));
+    transfer(payment(30), &conn).await.unwrap();
+
+    // rejected payments don't move money
+    assert_eq!(balance(ALICE, &conn).await, 920);
+    assert_eq!(balance(BOB, &conn).await, 80);
+
+    // and transfers without a policy aren't limited
+    transfer(
+        TransferForm {
+            limits: None,
+            approvals: None,
+            ..payment(500)
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    assert_eq!(balance(BOB, &conn).await, 580);
+}
+
+#[tokio::test]
+async fn transfer_checks_funds_before_limits() {
+    let conn = connect("transfer-funds").await;
+    fund(ALICE, 10, &conn).await;
+
+    assert!(matches!(
+        transfer(payment(20), &conn).await,
+        Err(EconomyError::InsufficientFunds { available: 10 })
+    ));
+    assert_eq!(balance(ALICE, &conn).await, 10);
+}
+
+fn freeze(block_incoming: bool) -> FreezeAccountForm {
+    FreezeAccountForm {
+        reason: String::from("Investigation"),
+        actor_id: 99,
+        block_incoming,
+        expires_at: None,
+    }
+}
+
+#[tokio::test]
+async fn transfer_checks_freezes() {
+    let conn = connect("transfer-freezes").await;
+    fund(ALICE, 100, &conn).await;
+    fund(BOB, 100, &conn).await;
+
+    freeze_account(ALICE, freeze(false), &conn).await.unwrap();
+    assert!(matches!(
+        transfer(payment(10), &conn).await,
+        Err(EconomyError::AccountFrozen)
+    ));
+
+    // a freeze that doesn't block incoming money lets the user be paid
+    transfer(
+        TransferForm {
+            payer: LedgerParty::User(BOB),
+            payee: LedgerParty::User(ALICE),
+            ..payment(10)
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    freeze_account(ALICE, freeze(true), &conn).await.unwrap();
+    assert!(matches!(
+        transfer(
+            TransferForm {
+                payer: LedgerParty::User(BOB),
+                payee: LedgerParty::User(ALICE),
+                ..payment(10)
+            },
+            &conn,
+        )
+        .await,
+        Err(EconomyError::PayeeFrozen)
+    ));
+
+    // collecting what the user owes ignores the freeze
+    transfer(
+        TransferForm {
+            check_freezes: false,
+            ..payment(10)
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(balance(ALICE, &conn).await, 100);
+    assert_eq!(balance(BOB, &conn).await, 100);
+
+    // expired freezes don't count
+    freeze_account(
+        ALICE,
+        FreezeAccountForm {
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+            ..freeze(true)
+        },
+        &conn,
+    )
+    .await
+    .unwrap();
+    transfer(payment(10), &conn).await.unwrap();
+
+    unfreeze_account(ALICE, &conn).await.unwrap();
+    transfer(payment(10), &conn).await.unwrap();
+    assert_eq!(balance(BOB, &conn).await, 120);
+}
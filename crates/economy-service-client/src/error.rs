@@ -0,0 +1,136 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+use crate::models::{AppError, ErrorCode};
+
+/// Error of a request to the economy service.
+/// Statuses documented by the service carry the error it responded with.
+#[derive(Debug)]
+pub enum Error {
+    /// Validation failed
+    BadRequest(AppError),
+
+    /// Authentication failed
+    Unauthorized(AppError),
+
+    /// Caller isn't allowed to perform the operation
+    Forbidden(AppError),
+
+    /// Target of the operation doesn't exist
+    NotFound(AppError),
+
+    /// Operation conflicts with the current state of its target
+    Conflict(AppError),
+
+    /// Body of the request has an unsupported content type
+    UnsupportedMediaType(AppError),
+
+    /// Rate limit of the caller is exceeded
+    TooManyRequests(AppError),
+
+    /// Service failed to handle the request
+    Internal(AppError),
+
+    /// Users service can't be reached
+    ServiceUnavailable(AppError),
+
+    /// Response the service isn't documented to send
+    Unexpected { status: StatusCode, body: String },
+
+    /// Request couldn't be sent or its response couldn't be read
+    Http(reqwest::Error),
+}
+impl Error {
+    /// Builds the error from a response with a failure status
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(err) => return Error::Http(err),
+        };
+
+        let error = match serde_json::from_str::<AppError>(&body) {
+            Ok(error) => error,
+            Err(_) => return Error::Unexpected { status, body },
+        };
+        match status {
+            StatusCode::BAD_REQUEST => Error::BadRequest(error),
+            StatusCode::UNAUTHORIZED => Error::Unauthorized(error),
+            StatusCode::FORBIDDEN => Error::Forbidden(error),
+            StatusCode::NOT_FOUND => Error::NotFound(error),
+            StatusCode::CONFLICT => Error::Conflict(error),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => Error::UnsupportedMediaType(error),
+            StatusCode::TOO_MANY_REQUESTS => Error::TooManyRequests(error),
+            StatusCode::INTERNAL_SERVER_ERROR => Error::Internal(error),
+            StatusCode::SERVICE_UNAVAILABLE => Error::ServiceUnavailable(error),
+            _ => Error::Unexpected { status, body },
+        }
+    }
+
+    /// Error the service responded with, if it did
+    pub fn app_error(&self) -> Option<&AppError> {
+        match self {
+            Error::BadRequest(error)
+            | Error::Unauthorized(error)
+            | Error::Forbidden(error)
+            | Error::NotFound(error)
+            | Error::Conflict(error)
+            | Error::UnsupportedMediaType(error)
+            | Error::TooManyRequests(error)
+            | Error::Internal(error)
+            | Error::ServiceUnavailable(error) => Some(error),
+            Error::Unexpected { .. } | Error::Http(_) => None,
+        }
+    }
+
+    /// Machine-readable code of the error the service responded with, if it did
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.app_error().map(|error| error.code)
+    }
+
+    /// Status the service responded with, if it did
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Unexpected { status, .. } => Some(*status),
+            Error::Http(err) => err.status(),
+            _ => self
+                .app_error()
+                .and_then(|error| StatusCode::from_u16(error.status).ok()),
+        }
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BadRequest(error)
+            | Error::Unauthorized(error)
+            | Error::Forbidden(error)
+            | Error::NotFound(error)
+            | Error::Conflict(error)
+            | Error::UnsupportedMediaType(error)
+            | Error::TooManyRequests(error)
+            | Error::Internal(error)
+            | Error::ServiceUnavailable(error) => {
+                write!(f, "{} ({:?}): {}", error.status, error.code, error.detail)
+            }
+            Error::Unexpected { status, body } => {
+                write!(f, "unexpected response {}: {}", status, body)
+            }
+            Error::Http(err) => err.fmt(f),
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
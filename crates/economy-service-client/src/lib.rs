@@ -0,0 +1,521 @@
+mod error;
+pub mod models;
+
+pub use error::Error;
+
+use futures_util::{stream, Stream, StreamExt};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::models::*;
+
+/// Version of the API the client talks to
+const API_PREFIX: &str = "/v1";
+
+#[derive(Clone, Debug)]
+pub struct EconomyServiceClient {
+    base_url: String,
+    client: reqwest::Client,
+    token: Option<String>,
+    api_key: Option<String>,
+}
+impl EconomyServiceClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        EconomyServiceClient {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            token: None,
+            api_key: None,
+        }
+    }
+
+    /// Authenticates the requests with the token of the users service
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Authenticates the requests with an API key of the economy service
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}{}", self.base_url, API_PREFIX, path));
+        if let Some(token) = &self.token {
+            request = request.header("x-token", token);
+        }
+        if let Some(key) = &self.api_key {
+            request = request.header("x-api-key", key);
+        }
+        request
+    }
+
+    /// Sends the request, turning failure statuses into errors
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(Error::from_response(response).await)
+        }
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, Error> {
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    async fn empty(&self, request: RequestBuilder) -> Result<(), Error> {
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Sends an operation that may be proposed for approval instead of executed
+    async fn approval(&self, request: RequestBuilder) -> Result<Approval, Error> {
+        let response = self.send(request).await?;
+        match response.status() {
+            StatusCode::ACCEPTED => Ok(Approval::Proposed(response.json().await?)),
+            _ => Ok(Approval::Executed),
+        }
+    }
+
+    /// Sends an operation responding with its report whether it succeeded or not
+    async fn report<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, Error> {
+        let response = request.send().await?;
+        match response.status() {
+            StatusCode::OK | StatusCode::UNPROCESSABLE_ENTITY => Ok(response.json().await?),
+            _ => Err(Error::from_response(response).await),
+        }
+    }
+
+    pub async fn get_self(&self) -> Result<EconomyState, Error> {
+        self.json(self.request(Method::GET, "/me")).await
+    }
+
+    pub async fn get_by_id(&self, user_id: i32) -> Result<EconomyStateDetails, Error> {
+        self.json(self.request(Method::GET, &format!("/users/{}", user_id)))
+            .await
+    }
+
+    /// Usernames are sent as they are, so they should only contain URL-safe characters.
+    pub async fn get_by_username(&self, username: &str) -> Result<EconomyStateDetails, Error> {
+        self.json(self.request(Method::GET, &format!("/users/by-username/{}", username)))
+            .await
+    }
+
+    pub async fn get_self_accounts(&self) -> Result<Vec<AccountMembership>, Error> {
+        self.json(self.request(Method::GET, "/me/accounts")).await
+    }
+
+    /// Streams balance changes of the caller. Without a cursor the stream starts
+    /// with the current balance, otherwise with the transactions after the cursor.
+    pub async fn get_self_events(
+        &self,
+        cursor: Option<i32>,
+    ) -> Result<impl Stream<Item = Result<BalanceEvent, Error>>, Error> {
+        let mut request = self.request(Method::GET, "/me/events");
+        if let Some(cursor) = cursor {
+            request = request.header("last-event-id", cursor.to_string());
+        }
+        let response = self.send(request).await?;
+
+        let events = stream::unfold(
+            (response.bytes_stream(), String::new()),
+            |(mut bytes, mut buffer)| async move {
+                loop {
+                    if let Some(end) = buffer.find("\n\n") {
+                        let block = buffer[..end].to_owned();
+                        buffer.drain(..end + 2);
+                        match parse_event(&block) {
+                            Some(event) => return Some((event, (bytes, buffer))),
+                            None => continue,
+                        }
+                    }
+
+                    match bytes.next().await? {
+                        Ok(chunk) => buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                        Err(err) => return Some((Err(err.into()), (bytes, buffer))),
+                    }
+                }
+            },
+        );
+        Ok(events)
+    }
+
+    pub async fn get_limits(&self, user_id: i32) -> Result<TransferLimitsInfo, Error> {
+        self.json(self.request(Method::GET, &format!("/users/{}/limits", user_id)))
+            .await
+    }
+
+    /// Adds money to the user, or proposes it for approval if the amount is large
+    pub async fn add_money(&self, user_id: i32, data: &DataAddMoney) -> Result<Approval, Error> {
+        self.approval(
+            self.request(Method::PATCH, &format!("/users/{}", user_id))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn pay(&self, payee_id: i32, data: &DataPay) -> Result<(), Error> {
+        self.empty(
+            self.request(Method::PUT, &format!("/users/{}/pay", payee_id))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn pay_by_username(&self, username: &str, data: &DataPay) -> Result<(), Error> {
+        self.empty(
+            self.request(Method::PUT, &format!("/users/by-username/{}/pay", username))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn set_limits(&self, user_id: i32, data: &DataSetLimits) -> Result<(), Error> {
+        self.empty(
+            self.request(Method::PUT, &format!("/users/{}/limits", user_id))
+                .json(data),
+        )
+        .await
+    }
+
+    /// Pays out money to many players. Atomic payouts with problems pay nothing
+    /// and report the problems, so the report is returned either way.
+    pub async fn batch_pay(&self, data: &DataBatchPay) -> Result<PayoutReport, Error> {
+        self.report(self.request(Method::POST, "/payouts").json(data))
+            .await
+    }
+
+    pub async fn freeze(&self, user_id: i32, data: &DataFreeze) -> Result<AccountFreeze, Error> {
+        self.json(
+            self.request(Method::PUT, &format!("/users/{}/freeze", user_id))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn unfreeze(&self, user_id: i32) -> Result<(), Error> {
+        self.empty(self.request(Method::DELETE, &format!("/users/{}/freeze", user_id)))
+            .await
+    }
+
+    pub async fn create_account(&self, data: &DataCreateAccount) -> Result<Account, Error> {
+        self.json(self.request(Method::POST, "/accounts").json(data))
+            .await
+    }
+
+    pub async fn get_account(&self, account_id: i32) -> Result<Account, Error> {
+        self.json(self.request(Method::GET, &format!("/accounts/{}", account_id)))
+            .await
+    }
+
+    pub async fn get_account_members(&self, account_id: i32) -> Result<Vec<AccountMember>, Error> {
+        self.json(self.request(Method::GET, &format!("/accounts/{}/members", account_id)))
+            .await
+    }
+
+    pub async fn set_account_member(
+        &self,
+        account_id: i32,
+        user_id: i32,
+        data: &DataSetAccountMember,
+    ) -> Result<AccountMember, Error> {
+        self.json(
+            self.request(
+                Method::PUT,
+                &format!("/accounts/{}/members/{}", account_id, user_id),
+            )
+            .json(data),
+        )
+        .await
+    }
+
+    pub async fn remove_account_member(&self, account_id: i32, user_id: i32) -> Result<(), Error> {
+        self.empty(self.request(
+            Method::DELETE,
+            &format!("/accounts/{}/members/{}", account_id, user_id),
+        ))
+        .await
+    }
+
+    pub async fn get_account_limits(
+        &self,
+        account_id: i32,
+    ) -> Result<Vec<AccountRoleLimit>, Error> {
+        self.json(self.request(Method::GET, &format!("/accounts/{}/limits", account_id)))
+            .await
+    }
+
+    pub async fn set_account_limit(
+        &self,
+        account_id: i32,
+        role: AccountRole,
+        data: &DataSetAccountLimit,
+    ) -> Result<AccountRoleLimit, Error> {
+        self.json(
+            self.request(
+                Method::PUT,
+                &format!("/accounts/{}/limits/{}", account_id, role.as_str()),
+            )
+            .json(data),
+        )
+        .await
+    }
+
+    pub async fn get_account_transactions(
+        &self,
+        account_id: i32,
+        query: &AccountTransactionsQuery,
+    ) -> Result<Vec<Transaction>, Error> {
+        self.json(
+            self.request(
+                Method::GET,
+                &format!("/accounts/{}/transactions", account_id),
+            )
+            .query(query),
+        )
+        .await
+    }
+
+    pub async fn pay_account(&self, account_id: i32, data: &DataPay) -> Result<(), Error> {
+        self.empty(
+            self.request(Method::PUT, &format!("/accounts/{}/pay", account_id))
+                .json(data),
+        )
+        .await
+    }
+
+    /// Sends money from the account, or proposes it for approval if the amount is large
+    pub async fn send_from_account(
+        &self,
+        account_id: i32,
+        data: &DataSendFromAccount,
+    ) -> Result<Approval, Error> {
+        self.approval(
+            self.request(Method::PUT, &format!("/accounts/{}/send", account_id))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn get_transaction(&self, transaction_id: i32) -> Result<TransactionDetails, Error> {
+        self.json(self.request(Method::GET, &format!("/transactions/{}", transaction_id)))
+            .await
+    }
+
+    pub async fn refund_payment(
+        &self,
+        transaction_id: i32,
+        data: &DataReverse,
+    ) -> Result<Transaction, Error> {
+        self.json(
+            self.request(
+                Method::POST,
+                &format!("/transactions/{}/refund", transaction_id),
+            )
+            .json(data),
+        )
+        .await
+    }
+
+    pub async fn reverse_payment(
+        &self,
+        transaction_id: i32,
+        data: &DataReverse,
+    ) -> Result<Transaction, Error> {
+        self.json(
+            self.request(
+                Method::POST,
+                &format!("/transactions/{}/reverse", transaction_id),
+            )
+            .json(data),
+        )
+        .await
+    }
+
+    pub async fn get_proposals(&self, query: &ProposalsQuery) -> Result<Vec<Proposal>, Error> {
+        self.json(self.request(Method::GET, "/proposals").query(query))
+            .await
+    }
+
+    pub async fn get_proposal(&self, proposal_id: i32) -> Result<ProposalDetails, Error> {
+        self.json(self.request(Method::GET, &format!("/proposals/{}", proposal_id)))
+            .await
+    }
+
+    pub async fn approve_proposal(&self, proposal_id: i32) -> Result<ProposalDetails, Error> {
+        self.json(self.request(Method::POST, &format!("/proposals/{}/approve", proposal_id)))
+            .await
+    }
+
+    pub async fn reject_proposal(
+        &self,
+        proposal_id: i32,
+        data: &DataRejectProposal,
+    ) -> Result<ProposalDetails, Error> {
+        self.json(
+            self.request(Method::POST, &format!("/proposals/{}/reject", proposal_id))
+                .json(data),
+        )
+        .await
+    }
+
+    pub async fn get_self_loans(&self) -> Result<Vec<Loan>, Error> {
+        self.json(self.request(Method::GET, "/me/loans")).await
+    }
+
+    pub async fn offer_loan(&self, data: &DataOfferLoan) -> Result<Loan, Error> {
+        self.json(self.request(Method::POST, "/loans").json(data))
+            .await
+    }
+
+    pub async fn get_loan(&self, loan_id: i32) -> Result<Loan, Error> {
+        self.json(self.request(Method::GET, &format!("/loans/{}", loan_id)))
+            .await
+    }
+
+    /// Accepts the loan offered to the caller, moving its principal to their balance
+    pub async fn accept_loan(&self, loan_id: i32) -> Result<Loan, Error> {
+        self.json(self.request(Method::POST, &format!("/loans/{}/accept", loan_id)))
+            .await
+    }
+
+    /// Declines the loan offered to the caller, or withdraws the loan they offered
+    pub async fn decline_loan(&self, loan_id: i32) -> Result<Loan, Error> {
+        self.json(self.request(Method::POST, &format!("/loans/{}/decline", loan_id)))
+            .await
+    }
+
+    pub async fn get_audit_log(&self, query: &AuditLogQuery) -> Result<Vec<AuditLogEntry>, Error> {
+        self.json(self.request(Method::GET, "/audit").query(query))
+            .await
+    }
+
+    /// Reconciles balances with the ledger, listing at most `limit` drifted accounts
+    pub async fn get_reconciliation(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<ReconciliationReport, Error> {
+        let mut request = self.request(Method::GET, "/reconciliation");
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        self.json(request).await
+    }
+
+    pub async fn get_balances_export(&self, format: ExportFormat) -> Result<String, Error> {
+        Ok(self
+            .send(
+                self.request(Method::GET, "/export/balances")
+                    .query(&[("format", format)]),
+            )
+            .await?
+            .text()
+            .await?)
+    }
+
+    pub async fn get_transactions_export(
+        &self,
+        query: &TransactionsExportQuery,
+    ) -> Result<String, Error> {
+        Ok(self
+            .send(
+                self.request(Method::GET, "/export/transactions")
+                    .query(query),
+            )
+            .await?
+            .text()
+            .await?)
+    }
+
+    /// Imports opening balances. Imports with problems apply nothing and report the problems,
+    /// so the report is returned either way.
+    pub async fn import_balances(
+        &self,
+        query: &ImportQuery,
+        format: ImportFormat,
+        data: impl Into<reqwest::Body>,
+    ) -> Result<ImportReport, Error> {
+        self.report(
+            self.request(Method::POST, "/import")
+                .query(query)
+                .header(reqwest::header::CONTENT_TYPE, format.content_type())
+                .body(data),
+        )
+        .await
+    }
+
+    pub async fn get_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        self.json(self.request(Method::GET, "/webhooks")).await
+    }
+
+    pub async fn create_webhook(&self, data: &DataCreateWebhook) -> Result<Webhook, Error> {
+        self.json(self.request(Method::POST, "/webhooks").json(data))
+            .await
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: i32) -> Result<(), Error> {
+        self.empty(self.request(Method::DELETE, &format!("/webhooks/{}", webhook_id)))
+            .await
+    }
+
+    pub async fn get_webhook_deliveries(
+        &self,
+        webhook_id: i32,
+        query: &PageQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        self.json(
+            self.request(Method::GET, &format!("/webhooks/{}/deliveries", webhook_id))
+                .query(query),
+        )
+        .await
+    }
+
+    pub async fn get_api_keys(&self) -> Result<Vec<ApiKey>, Error> {
+        self.json(self.request(Method::GET, "/api-keys")).await
+    }
+
+    pub async fn create_api_key(&self, data: &DataCreateApiKey) -> Result<CreatedApiKey, Error> {
+        self.json(self.request(Method::POST, "/api-keys").json(data))
+            .await
+    }
+
+    pub async fn revoke_api_key(&self, api_key_id: i32) -> Result<(), Error> {
+        self.empty(self.request(Method::DELETE, &format!("/api-keys/{}", api_key_id)))
+            .await
+    }
+}
+
+#[derive(Deserialize)]
+struct BalanceData {
+    balance: i32,
+}
+
+/// Parses a block of Server-Sent Events lines, skipping keep-alive comments and unknown events
+fn parse_event(block: &str) -> Option<Result<BalanceEvent, Error>> {
+    let mut event = "message";
+    let mut data = Vec::new();
+    for line in block.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim_start();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    let data = data.join("\n");
+
+    let parsed = match event {
+        "balance" => serde_json::from_str::<BalanceData>(&data)
+            .map(|data| BalanceEvent::Balance(data.balance)),
+        "transaction" => serde_json::from_str(&data).map(BalanceEvent::Transaction),
+        _ => return None,
+    };
+    Some(parsed.map_err(|err| Error::Unexpected {
+        status: StatusCode::OK,
+        body: format!("{}: {}", err, data),
+    }))
+}
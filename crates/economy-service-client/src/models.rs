@@ -0,0 +1,856 @@
+//! Request and response data of the economy service API.
+//!
+//! Types deriving `ToSchema` under the `utoipa` feature are shared with the service itself.
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Machine-readable error code. Codes are stable, unlike the wording of error details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Request data is invalid
+    ValidationFailed,
+    /// Amount isn't positive
+    InvalidAmount,
+    /// Username isn't a valid Minecraft username
+    InvalidUsername,
+    /// Payer and payee are the same
+    SelfPayment,
+    /// Payer doesn't have enough money
+    InsufficientFunds,
+    /// Payment exceeds a transfer or spending limit
+    TransferLimitExceeded,
+    /// Transaction isn't a payment
+    NotReversible,
+    /// Amount exceeds the unreversed part of the payment
+    ReversalExceedsRemaining,
+    /// Token is missing or invalid
+    Unauthenticated,
+    MissingAdminRole,
+    MissingBankerRole,
+    MissingAccountRole,
+    /// API key isn't granted the scope the operation needs
+    MissingScope,
+    /// Caller isn't involved in the target of the operation
+    NotAllowed,
+    /// Account of the caller is frozen
+    AccountFrozen,
+    /// Account of the payee is frozen
+    PayeeFrozen,
+    /// Operation is too large to be executed without approval
+    ApprovalRequired,
+    /// Proposer can't approve their own proposal
+    OwnProposal,
+    UserNotFound,
+    AccountNotFound,
+    MemberNotFound,
+    TransactionNotFound,
+    ProposalNotFound,
+    WebhookNotFound,
+    FreezeNotFound,
+    ApiKeyNotFound,
+    LoanNotFound,
+    /// Payment is already fully reversed
+    AlreadyReversed,
+    /// Proposal was already resolved
+    ProposalNotPending,
+    /// Proposal is already approved by the caller
+    AlreadyApproved,
+    /// Loan was already accepted or declined
+    LoanNotPending,
+    UnsupportedMediaType,
+    RateLimited,
+    /// Users service can't be reached
+    UpstreamUnavailable,
+    Internal,
+    /// Code unknown to this version of the client
+    #[serde(other)]
+    Unknown,
+}
+impl ErrorCode {
+    /// HTTP status the error is sent with
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorCode::ValidationFailed
+            | ErrorCode::InvalidAmount
+            | ErrorCode::InvalidUsername
+            | ErrorCode::SelfPayment
+            | ErrorCode::InsufficientFunds
+            | ErrorCode::TransferLimitExceeded
+            | ErrorCode::NotReversible
+            | ErrorCode::ReversalExceedsRemaining => 400,
+            ErrorCode::Unauthenticated => 401,
+            ErrorCode::MissingAdminRole
+            | ErrorCode::MissingBankerRole
+            | ErrorCode::MissingAccountRole
+            | ErrorCode::MissingScope
+            | ErrorCode::NotAllowed
+            | ErrorCode::AccountFrozen
+            | ErrorCode::PayeeFrozen
+            | ErrorCode::ApprovalRequired
+            | ErrorCode::OwnProposal => 403,
+            ErrorCode::UserNotFound
+            | ErrorCode::AccountNotFound
+            | ErrorCode::MemberNotFound
+            | ErrorCode::TransactionNotFound
+            | ErrorCode::ProposalNotFound
+            | ErrorCode::WebhookNotFound
+            | ErrorCode::ApiKeyNotFound
+            | ErrorCode::LoanNotFound
+            | ErrorCode::FreezeNotFound => 404,
+            ErrorCode::AlreadyReversed
+            | ErrorCode::ProposalNotPending
+            | ErrorCode::AlreadyApproved
+            | ErrorCode::LoanNotPending => 409,
+            ErrorCode::UnsupportedMediaType => 415,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::Internal | ErrorCode::Unknown => 500,
+            ErrorCode::UpstreamUnavailable => 503,
+        }
+    }
+}
+
+/// Service error data, sent as `application/problem+json` (RFC 7807)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AppError {
+    /// Machine-readable error code
+    pub code: ErrorCode,
+
+    /// Reason phrase of the HTTP status
+    pub title: String,
+
+    /// HTTP status code
+    pub status: u16,
+
+    /// Error detail message
+    pub detail: String,
+
+    /// Money available to the payer, for `insufficient_funds`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<i64>,
+
+    /// Amount that can still be sent or reversed, for `transfer_limit_exceeded`
+    /// and `reversal_exceeds_remaining`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<i64>,
+
+    /// Exceeded limit, `per_transaction`, `daily` or `window`, for `transfer_limit_exceeded`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+}
+impl AppError {
+    pub fn new(code: ErrorCode, detail: impl Into<String>) -> Self {
+        let status = code.status();
+        AppError {
+            code,
+            title: StatusCode::from_u16(status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+                .to_owned(),
+            status,
+            detail: detail.into(),
+            available: None,
+            remaining: None,
+            limit: None,
+        }
+    }
+
+    pub fn with_available(mut self, available: i64) -> Self {
+        self.available = Some(available);
+        self
+    }
+
+    pub fn with_remaining(mut self, remaining: i64) -> Self {
+        self.remaining = Some(remaining);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: impl Into<String>) -> Self {
+        self.limit = Some(limit.into());
+        self
+    }
+}
+
+/// Transfer limits tier of an account
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AccountTier {
+    Standard,
+    Verified,
+    Merchant,
+}
+
+/// Economy state of user
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct EconomyState {
+    /// ID of user
+    pub user_id: i32,
+
+    /// Balance of user
+    pub balance: i32,
+
+    /// Whether the user has banker permissions
+    pub banker: bool,
+
+    /// Transfer limits tier of the account
+    pub tier: AccountTier,
+
+    /// Amount the user still has to repay of the loans they borrowed
+    pub debt: i64,
+}
+
+/// Data used in pay operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DataPay {
+    /// Amount of money to pay
+    pub amount: i32,
+
+    /// Comment that will be shown to payee
+    pub comment: Option<String>,
+}
+
+/// Data used in add money operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DataAddMoney {
+    /// Amount of money to add
+    pub amount: i32,
+
+    /// Reason recorded in the audit log
+    pub reason: Option<String>,
+}
+
+/// Freeze of an account
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountFreeze {
+    pub reason: String,
+    pub actor_id: i32,
+    pub block_incoming: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Economy state of user with details visible to bankers
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EconomyStateDetails {
+    #[serde(flatten)]
+    pub state: EconomyState,
+
+    /// Active freeze of the account. Only shown to bankers.
+    pub freeze: Option<AccountFreeze>,
+}
+
+/// Transfer limits of user along with their usage
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TransferLimitsInfo {
+    pub tier: AccountTier,
+    pub per_transaction: Option<i32>,
+    pub daily: Option<i32>,
+    pub window: Option<i32>,
+    pub window_hours: i64,
+    pub daily_used: i64,
+    pub window_used: i64,
+
+    /// Amount that can still be sent, or `None` if unlimited
+    pub headroom: Option<i64>,
+}
+
+/// Data used in set limits operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataSetLimits {
+    pub tier: AccountTier,
+    pub per_transaction: Option<i32>,
+    pub daily: Option<i32>,
+    pub window: Option<i32>,
+    pub reason: Option<String>,
+}
+
+/// Data used in freeze operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataFreeze {
+    pub reason: String,
+    pub block_incoming: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    Payment,
+    Mint,
+    Burn,
+    Import,
+    Reversal,
+    Loan,
+    Repayment,
+}
+
+/// Transaction of the ledger
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Transaction {
+    pub id: i32,
+    pub kind: TransactionKind,
+    pub payer_id: Option<i32>,
+    pub payee_id: Option<i32>,
+    pub payer_account_id: Option<i32>,
+    pub payee_account_id: Option<i32>,
+    pub initiator_id: Option<i32>,
+    pub api_key_id: Option<i32>,
+    pub amount: i32,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub reverses_id: Option<i32>,
+    pub reversed_amount: i32,
+    pub loan_id: Option<i32>,
+}
+
+/// Transaction along with its reversals
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TransactionDetails {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub reversals: Vec<Transaction>,
+}
+
+/// Data used in refund and reverse operations
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DataReverse {
+    /// Amount of money to move back. The whole unreversed part of the payment if `None`.
+    pub amount: Option<i32>,
+    pub comment: Option<String>,
+}
+
+/// Event of the balance updates stream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BalanceEvent {
+    /// Current balance, sent when the stream starts
+    Balance(i32),
+
+    /// Transaction the user took part in
+    Transaction(BalanceUpdate),
+}
+
+/// Transaction the user took part in, along with their balance after it
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BalanceUpdate {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub balance: Option<i32>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutMode {
+    /// Pay all of the items or none of them
+    #[default]
+    Atomic,
+
+    /// Pay every item that can be paid and report the others
+    BestEffort,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataPayoutItem {
+    pub payee_id: i32,
+    pub amount: i32,
+    pub comment: Option<String>,
+}
+
+/// Data used in batch pay operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataBatchPay {
+    pub items: Vec<DataPayoutItem>,
+    pub mode: PayoutMode,
+
+    /// Mint the money instead of paying it from own balance. Bankers only.
+    pub mint: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PayoutResult {
+    pub index: usize,
+    pub payee_id: i32,
+    pub amount: i32,
+    pub transaction_id: Option<i32>,
+    pub error: Option<String>,
+    pub code: Option<ErrorCode>,
+}
+
+/// Outcome of a payout. Nothing is paid if an atomic payout has errors.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PayoutReport {
+    pub items: usize,
+    pub paid: usize,
+    pub total: i64,
+    pub results: Vec<PayoutResult>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    Shop,
+    Company,
+    Organization,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRole {
+    Owner,
+    Manager,
+    Treasurer,
+    Member,
+}
+impl AccountRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccountRole::Owner => "owner",
+            AccountRole::Manager => "manager",
+            AccountRole::Treasurer => "treasurer",
+            AccountRole::Member => "member",
+        }
+    }
+}
+
+/// Shared account owned by players
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Account {
+    pub id: i32,
+    pub kind: AccountKind,
+    pub name: String,
+    pub balance: i32,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Shared account along with the role of the user in it
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountMembership {
+    #[serde(flatten)]
+    pub account: Account,
+    pub role: AccountRole,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountMember {
+    pub account_id: i32,
+    pub user_id: i32,
+    pub role: AccountRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Spending limits of a role in a shared account
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountRoleLimit {
+    pub role: AccountRole,
+    pub per_transaction: Option<i32>,
+    pub daily: Option<i32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataCreateAccount {
+    pub kind: AccountKind,
+    pub name: String,
+}
+
+/// Data used in send from account operation. Exactly one payee should be given.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataSendFromAccount {
+    pub payee_id: Option<i32>,
+    pub payee_account_id: Option<i32>,
+    pub amount: i32,
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataSetAccountMember {
+    pub role: AccountRole,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataSetAccountLimit {
+    pub per_transaction: Option<i32>,
+    pub daily: Option<i32>,
+}
+
+/// Query parameters of account transactions fetch
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AccountTransactionsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalKind {
+    Mint,
+    TreasuryPayment,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Pending,
+    Approved,
+    Executed,
+    Failed,
+    Rejected,
+    Expired,
+}
+
+/// Operation awaiting approval by several users
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Proposal {
+    pub id: i32,
+    pub kind: ProposalKind,
+    pub status: ProposalStatus,
+    pub proposer_id: i32,
+    pub account_id: Option<i32>,
+    pub payload: Value,
+    pub required_approvals: i32,
+    pub rejected_by: Option<i32>,
+    pub transaction_id: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProposalApproval {
+    pub approver_id: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Proposal along with its approvals
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProposalDetails {
+    #[serde(flatten)]
+    pub proposal: Proposal,
+    pub approvals: Vec<ProposalApproval>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DataRejectProposal {
+    pub reason: Option<String>,
+}
+
+/// Query parameters of proposals fetch
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProposalsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ProposalStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoanStatus {
+    Offered,
+    Active,
+    Defaulted,
+    Repaid,
+    Declined,
+}
+
+/// Money lent by one player to another, repaid in installments from the borrower's balance
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Loan {
+    pub id: i32,
+    pub lender_id: i32,
+    pub borrower_id: i32,
+    pub status: LoanStatus,
+    pub principal: i32,
+    pub interest_rate_bps: i32,
+    pub installments: i32,
+    pub interval_hours: i32,
+    pub installment_amount: i32,
+    pub total_due: i32,
+    pub repaid: i32,
+    pub overdue: i32,
+    pub missed_installments: i32,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub next_due_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Data used in offer loan operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DataOfferLoan {
+    /// ID of the user the money is lent to
+    pub borrower_id: i32,
+
+    /// Amount of money to lend
+    pub principal: i32,
+
+    /// Interest over the whole term in basis points, e.g. 500 for 5%
+    pub interest_rate_bps: i32,
+
+    /// Amount of installments the loan is repaid in
+    pub installments: i32,
+
+    /// Hours between two installments, the first one is due this long after acceptance
+    pub interval_hours: i32,
+
+    /// Comment that will be shown to the borrower
+    pub comment: Option<String>,
+}
+
+/// Result of an operation that may require approval
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Approval {
+    /// Operation was executed right away
+    Executed,
+
+    /// Operation awaits approval
+    Proposed(Proposal),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Mint,
+    Burn,
+    SetLimits,
+    Freeze,
+    Unfreeze,
+    CreateWebhook,
+    DeleteWebhook,
+    GrantBanker,
+    RevokeBanker,
+    Import,
+    CreateProposal,
+    ApproveProposal,
+    RejectProposal,
+    ExecuteProposal,
+    Reverse,
+    MintPayout,
+    CreateApiKey,
+    RevokeApiKey,
+    SetAccountMember,
+    RemoveAccountMember,
+    SetAccountLimit,
+}
+
+/// Entry of the audit log
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditLogEntry {
+    pub id: i32,
+    pub actor_id: i32,
+    pub api_key_id: Option<i32>,
+    pub action: AuditAction,
+    pub target: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub reason: Option<String>,
+    pub request_id: Option<String>,
+    pub client_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters of audit log fetch
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AuditLogQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+/// Account whose balance doesn't match its recorded movements
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DriftedAccount {
+    pub user_id: Option<i32>,
+    pub account_id: Option<i32>,
+    pub balance: i32,
+    pub expected: i64,
+    pub drift: i64,
+}
+
+/// Comparison of balances with the money movements recorded in the ledger
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReconciliationReport {
+    pub checked_at: DateTime<Utc>,
+    pub accounts: usize,
+    pub total_balance: i64,
+    pub expected_total: i64,
+    pub drift: i64,
+    pub drifted_account_count: usize,
+    pub drifted_accounts: Vec<DriftedAccount>,
+}
+
+/// Format of exported data
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+/// Query parameters of transactions export
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TransactionsExportQuery {
+    pub format: ExportFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Format of imported data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+impl ImportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "text/csv",
+            ImportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// Query parameters of balances import
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportQuery {
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Problem that prevents an import from being applied
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImportProblem {
+    pub row: usize,
+    pub user_id: Option<i32>,
+    pub detail: String,
+}
+
+/// Outcome of an import
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImportReport {
+    pub rows: usize,
+    pub total: i64,
+    pub banker_changes: usize,
+    pub problems: Vec<ImportProblem>,
+    pub applied: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    PaymentReceived,
+    Minted,
+    Burned,
+    PaymentReversed,
+}
+
+/// Receiver of economy events
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataCreateWebhook {
+    pub url: String,
+
+    /// Secret the payloads are signed with
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+/// Key that services authenticate with instead of a player's token
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiKey {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// API key along with the key itself, which is only shown once
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataCreateApiKey {
+    pub name: String,
+
+    /// Scopes like `read:balances`, `pay:from:<account ID>` or `mint`
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// Delivery of an event to a webhook
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters of paginated fetches
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PageQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
@@ -0,0 +1,60 @@
+use clap::Subcommand;
+use economy_service_core::{get_active_freeze, get_economy_state};
+use economy_service_migration::sea_orm::{ActiveEnum, DbConn};
+
+use crate::CommandResult;
+
+#[derive(Subcommand)]
+pub(crate) enum AccountCommand {
+    /// Print economy state of the user
+    Show {
+        /// Target user ID
+        user_id: i32,
+    },
+}
+
+fn format_limit(limit: Option<i32>) -> String {
+    limit.map_or_else(|| String::from("tier default"), |limit| limit.to_string())
+}
+
+pub(crate) async fn account(command: AccountCommand, conn: &DbConn) -> CommandResult {
+    match command {
+        AccountCommand::Show { user_id } => {
+            let state = match get_economy_state(user_id, conn).await? {
+                Some(state) => state,
+                None => {
+                    println!("User {} has no economy state yet", user_id);
+                    return Ok(());
+                }
+            };
+            let freeze = get_active_freeze(user_id, conn).await?;
+
+            println!("User ID:          {}", state.user_id);
+            println!("Balance:          {}", state.balance);
+            println!("Banker:           {}", state.banker);
+            println!("Tier:             {}", state.tier.to_value());
+            println!(
+                "Per-transaction:  {}",
+                format_limit(state.limit_per_transaction)
+            );
+            println!("Daily limit:      {}", format_limit(state.limit_daily));
+            println!("Window limit:     {}", format_limit(state.limit_window));
+
+            match freeze {
+                Some(freeze) => {
+                    println!(
+                        "Frozen:           since {} by user {}: {}",
+                        freeze.created_at, freeze.actor_id, freeze.reason
+                    );
+                    println!("Incoming blocked: {}", freeze.block_incoming);
+                    if let Some(expires_at) = freeze.expires_at {
+                        println!("Freeze expires:   {}", expires_at);
+                    }
+                }
+                None => println!("Frozen:           false"),
+            }
+        }
+    }
+
+    Ok(())
+}
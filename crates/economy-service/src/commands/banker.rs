@@ -0,0 +1,78 @@
+use clap::{Args, Subcommand};
+use economy_service_core::{
+    get_or_create_economy_state, record_audit_entry, update_economy_state, AuditEntryForm,
+    UpdateEconomyStateForm,
+};
+use economy_service_entity::audit_log_entry::AuditAction;
+use economy_service_migration::sea_orm::{DbConn, TransactionTrait};
+use serde_json::json;
+
+use crate::{commands::audit_entry, CommandResult};
+
+#[derive(Subcommand)]
+pub(crate) enum BankerCommand {
+    /// Grant banker role to the user
+    Grant(BankerArgs),
+
+    /// Revoke banker role from the user
+    Revoke(BankerArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct BankerArgs {
+    /// Target user ID
+    user_id: i32,
+
+    /// ID of the user performing the operation, recorded in the audit log
+    #[arg(long)]
+    actor: i32,
+
+    /// Reason recorded in the audit log
+    #[arg(long)]
+    reason: Option<String>,
+}
+
+pub(crate) async fn banker(command: BankerCommand, conn: &DbConn) -> CommandResult {
+    let (args, banker, action) = match command {
+        BankerCommand::Grant(args) => (args, true, AuditAction::GrantBanker),
+        BankerCommand::Revoke(args) => (args, false, AuditAction::RevokeBanker),
+    };
+
+    // update and record it in the audit log atomically
+    let txn = conn.begin().await?;
+
+    let before = get_or_create_economy_state(args.user_id, &txn).await?;
+    if before.banker == banker {
+        println!(
+            "Nothing to do: banker role of user {} is already {}",
+            args.user_id, banker
+        );
+        return Ok(());
+    }
+
+    update_economy_state(
+        before.clone().into(),
+        UpdateEconomyStateForm {
+            banker: Some(banker),
+            ..Default::default()
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(json!({ "banker": before.banker })),
+            after: Some(json!({ "banker": banker })),
+            reason: args.reason,
+            ..audit_entry(args.actor, action, args.user_id)
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    println!("Banker role of user {} is now {}", args.user_id, banker);
+    Ok(())
+}
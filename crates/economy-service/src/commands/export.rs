@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use economy_service_core::{
+    export_balances, export_transactions, get_balances_export_user_ids,
+    get_transactions_export_user_ids, ExportFormat,
+};
+use economy_service_migration::sea_orm::DbConn;
+use futures::{StreamExt, TryStreamExt};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use crate::{commands::users_client, CommandResult};
+
+#[derive(Subcommand)]
+pub(crate) enum ExportCommand {
+    /// Export balances of all users, ordered by user ID
+    Balances(ExportArgs),
+
+    /// Export transactions within a time range, oldest first
+    Transactions {
+        #[command(flatten)]
+        args: ExportArgs,
+
+        /// Only transactions created at or after this time (RFC 3339)
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+
+        /// Only transactions created before this time (RFC 3339)
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Args)]
+pub(crate) struct ExportArgs {
+    /// Format of the export: `csv` or `ndjson`
+    #[arg(long, default_value = "csv")]
+    format: ExportFormat,
+
+    /// File to write the export to, standard output if omitted
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+pub(crate) async fn export(command: ExportCommand, conn: &DbConn) -> CommandResult {
+    let users_client = users_client()?;
+
+    let (output, mut export) = match command {
+        ExportCommand::Balances(args) => {
+            let user_ids = get_balances_export_user_ids(conn).await?;
+            let usernames = users_client.get_usernames(user_ids).await?;
+            (
+                args.output,
+                export_balances(conn.clone(), usernames, args.format).boxed(),
+            )
+        }
+        ExportCommand::Transactions { args, from, to } => {
+            let user_ids = get_transactions_export_user_ids(from, to, conn).await?;
+            let usernames = users_client.get_usernames(user_ids).await?;
+            (
+                args.output,
+                export_transactions(conn.clone(), usernames, args.format, from, to).boxed(),
+            )
+        }
+    };
+
+    let mut writer: BufWriter<Box<dyn Write>> = BufWriter::new(match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    });
+
+    while let Some(chunk) = export.try_next().await? {
+        writer.write_all(&chunk)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use clap::Args;
+use economy_service_core::{
+    freeze_account, get_active_freeze, record_audit_entry, unfreeze_account, AuditEntryForm,
+    FreezeAccountForm,
+};
+use economy_service_entity::audit_log_entry::AuditAction;
+use economy_service_migration::sea_orm::{DbConn, TransactionTrait};
+
+use crate::{commands::audit_entry, CommandResult};
+
+#[derive(Args)]
+pub(crate) struct FreezeArgs {
+    /// Target user ID
+    user_id: i32,
+
+    /// Reason of the freeze, shown to bankers
+    #[arg(long)]
+    reason: String,
+
+    /// ID of the user performing the operation, recorded in the audit log
+    #[arg(long)]
+    actor: i32,
+
+    /// Block incoming payments as well
+    #[arg(long)]
+    block_incoming: bool,
+
+    /// Time the freeze expires at (RFC 3339), never if omitted
+    #[arg(long)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Args)]
+pub(crate) struct UnfreezeArgs {
+    /// Target user ID
+    user_id: i32,
+
+    /// ID of the user performing the operation, recorded in the audit log
+    #[arg(long)]
+    actor: i32,
+
+    /// Reason recorded in the audit log
+    #[arg(long)]
+    reason: Option<String>,
+}
+
+pub(crate) async fn freeze(args: FreezeArgs, conn: &DbConn) -> CommandResult {
+    if args.reason.trim().is_empty() {
+        return Err("Reason should not be empty".into());
+    }
+
+    if matches!(args.expires_at, Some(expires_at) if expires_at <= Utc::now()) {
+        return Err("Expiry should be in the future".into());
+    }
+
+    // freeze and record it in the audit log atomically
+    let txn = conn.begin().await?;
+
+    let before = get_active_freeze(args.user_id, &txn).await?;
+
+    let freeze = freeze_account(
+        args.user_id,
+        FreezeAccountForm {
+            reason: args.reason.clone(),
+            actor_id: args.actor,
+            block_incoming: args.block_incoming,
+            expires_at: args.expires_at,
+        },
+        &txn,
+    )
+    .await?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: before.map(serde_json::to_value).transpose()?,
+            after: Some(serde_json::to_value(&freeze)?),
+            reason: Some(args.reason),
+            ..audit_entry(args.actor, AuditAction::Freeze, args.user_id)
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    println!("Account of user {} is frozen", args.user_id);
+    Ok(())
+}
+
+pub(crate) async fn unfreeze(args: UnfreezeArgs, conn: &DbConn) -> CommandResult {
+    // unfreeze and record it in the audit log atomically
+    let txn = conn.begin().await?;
+
+    let freeze = unfreeze_account(args.user_id, &txn)
+        .await?
+        .ok_or("Account is not frozen")?;
+
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(serde_json::to_value(freeze)?),
+            reason: args.reason,
+            ..audit_entry(args.actor, AuditAction::Unfreeze, args.user_id)
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    println!("Account of user {} is unfrozen", args.user_id);
+    Ok(())
+}
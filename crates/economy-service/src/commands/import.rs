@@ -0,0 +1,83 @@
+use clap::Args;
+use economy_service_api::import::{run_import, ImportFormat};
+use economy_service_core::AuditEntryForm;
+use economy_service_entity::audit_log_entry::AuditAction;
+use economy_service_migration::sea_orm::DbConn;
+use std::path::PathBuf;
+
+use crate::{
+    commands::{operation_audit_entry, users_client},
+    CommandResult,
+};
+
+#[derive(Args)]
+pub(crate) struct ImportArgs {
+    /// CSV or JSON file with `user_id`, `balance` and optional `banker` fields
+    file: PathBuf,
+
+    /// Format of the file: `csv` or `json`. Inferred from the file extension if omitted.
+    #[arg(long)]
+    format: Option<ImportFormat>,
+
+    /// Only validate the file and report problems, without applying it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// ID of the user performing the operation, recorded in the audit log
+    #[arg(long)]
+    actor: i32,
+
+    /// Reason recorded in the audit log
+    #[arg(long)]
+    reason: Option<String>,
+}
+
+pub(crate) async fn import(args: ImportArgs, conn: &DbConn) -> CommandResult {
+    let format = match args.format {
+        Some(format) => format,
+        None => args
+            .file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or("Cannot infer format of the file, specify it with --format")?
+            .parse()?,
+    };
+    let data = std::fs::read(&args.file)?;
+
+    let report = run_import(
+        &data,
+        format,
+        args.dry_run,
+        AuditEntryForm {
+            reason: args.reason,
+            ..operation_audit_entry(args.actor, AuditAction::Import, String::from("import"))
+        },
+        &users_client()?,
+        conn,
+    )
+    .await?;
+
+    println!("Rows:             {}", report.rows);
+    println!("Total:            {}", report.total);
+    println!("Banker changes:   {}", report.banker_changes);
+    for problem in &report.problems {
+        match problem.user_id {
+            Some(user_id) => println!("Row {} (user {}): {}", problem.row, user_id, problem.detail),
+            None => println!("Row {}: {}", problem.row, problem.detail),
+        }
+    }
+
+    if !report.problems.is_empty() {
+        return Err(format!(
+            "{} problem(s) found, nothing imported",
+            report.problems.len()
+        )
+        .into());
+    }
+    match report.applied {
+        true => println!("Import applied"),
+        false => println!("No problems found, run without --dry-run to apply"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use clap::Subcommand;
+use economy_service_migration::{sea_orm::DbConn, Migrator, MigratorTrait};
+
+use crate::CommandResult;
+
+#[derive(Subcommand)]
+pub(crate) enum MigrateCommand {
+    /// List migrations along with when they were applied
+    Status,
+
+    /// Apply pending migrations
+    Up {
+        /// Number of migrations to apply, all of them if omitted
+        steps: Option<u32>,
+    },
+
+    /// Roll back applied migrations
+    Down {
+        /// Number of migrations to roll back
+        #[arg(default_value_t = 1)]
+        steps: u32,
+    },
+}
+
+pub(crate) async fn migrate(command: MigrateCommand, conn: &DbConn) -> CommandResult {
+    if let MigrateCommand::Status = command {
+        return status(conn).await;
+    }
+
+    let applied_before = Migrator::get_applied_migrations(conn).await?.len();
+
+    match command {
+        MigrateCommand::Up { steps } => Migrator::up(conn, steps).await?,
+        MigrateCommand::Down { steps } => Migrator::down(conn, Some(steps)).await?,
+        MigrateCommand::Status => unreachable!(),
+    }
+
+    let applied = Migrator::get_applied_migrations(conn).await?.len();
+    if applied >= applied_before {
+        println!("Applied {} migration(s)", applied - applied_before);
+    } else {
+        println!("Rolled back {} migration(s)", applied_before - applied);
+    }
+
+    // migrations are applied in order, so the last applied one is the current schema version
+    match applied.checked_sub(1) {
+        Some(latest) => println!("Schema is at {}", Migrator::migrations()[latest].name()),
+        None => println!("Schema is empty"),
+    }
+
+    Ok(())
+}
+
+async fn status(conn: &DbConn) -> CommandResult {
+    let mut applied: HashMap<String, i64> = Migrator::get_migration_models(conn)
+        .await?
+        .into_iter()
+        .map(|model| (model.version, model.applied_at))
+        .collect();
+
+    let mut pending = 0;
+    for migration in Migrator::migrations() {
+        match applied.remove(migration.name()) {
+            Some(applied_at) => println!(
+                "applied  {}  {}",
+                Utc.timestamp_opt(applied_at, 0).unwrap(),
+                migration.name()
+            ),
+            None => {
+                pending += 1;
+                println!("pending  {:23}  {}", "", migration.name());
+            }
+        }
+    }
+
+    // applied by a newer version of the service, which this one can't roll back
+    let mut unknown: Vec<String> = applied.into_keys().collect();
+    unknown.sort();
+    for name in &unknown {
+        println!("unknown  {:23}  {}", "", name);
+    }
+
+    match (pending, unknown.len()) {
+        (0, 0) => println!("Schema is up to date"),
+        (pending, 0) => println!("Schema is {} migration(s) behind", pending),
+        (_, unknown) => println!(
+            "Schema has {} migration(s) unknown to this version",
+            unknown
+        ),
+    }
+
+    Ok(())
+}
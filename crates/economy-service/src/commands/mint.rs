@@ -0,0 +1,61 @@
+use clap::Args;
+use economy_service_core::{mint as mint_money, record_audit_entry, AuditEntryForm};
+use economy_service_entity::audit_log_entry::AuditAction;
+use economy_service_migration::sea_orm::{DbConn, TransactionTrait};
+use serde_json::json;
+
+use crate::{commands::audit_entry, CommandResult};
+
+#[derive(Args)]
+pub(crate) struct MintArgs {
+    /// Target user ID
+    user_id: i32,
+
+    /// Amount of money
+    #[arg(value_parser = clap::value_parser!(i32).range(1..))]
+    amount: i32,
+
+    /// Reason recorded in the audit log
+    #[arg(long)]
+    reason: String,
+
+    /// ID of the user performing the operation, recorded in the audit log
+    #[arg(long)]
+    actor: i32,
+}
+
+/// Mints the amount of money if `sign` is positive, or burns it otherwise
+pub(crate) async fn mint(args: MintArgs, sign: i32, conn: &DbConn) -> CommandResult {
+    let amount = args.amount * sign.signum();
+
+    // mint and record it in the audit log atomically; frozen accounts may still be fined,
+    // but not paid
+    let txn = conn.begin().await?;
+
+    let record = mint_money(args.user_id, amount, &txn).await?;
+    let balance = record
+        .payee_balance
+        .or(record.payer_balance)
+        .unwrap_or_default();
+
+    let action = if amount < 0 {
+        AuditAction::Burn
+    } else {
+        AuditAction::Mint
+    };
+    record_audit_entry(
+        AuditEntryForm {
+            before: Some(json!({ "balance": balance - amount })),
+            after: Some(json!({ "balance": balance })),
+            reason: Some(args.reason),
+            ..audit_entry(args.actor, action, args.user_id)
+        },
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    println!("Balance of user {} is now {}", args.user_id, balance);
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod account;
+mod banker;
+mod export;
+mod freeze;
+mod import;
+mod migrate;
+mod mint;
+mod stats;
+
+pub(crate) use account::*;
+pub(crate) use banker::*;
+pub(crate) use export::*;
+pub(crate) use freeze::*;
+pub(crate) use import::*;
+pub(crate) use migrate::*;
+pub(crate) use mint::*;
+pub(crate) use stats::*;
+
+use economy_service_core::AuditEntryForm;
+use economy_service_entity::audit_log_entry::AuditAction;
+use serde::Deserialize;
+use users_service_client::UsersServiceClient;
+
+#[derive(Debug, Deserialize)]
+struct UsersServiceConfig {
+    users_service_url: String,
+}
+
+/// Creates users service client from `USERS_SERVICE_URL`, for commands that need to look users up
+fn users_client() -> Result<UsersServiceClient, envy::Error> {
+    let config = envy::from_env::<UsersServiceConfig>()?;
+    Ok(UsersServiceClient::new(&config.users_service_url))
+}
+
+/// Starts an audit log entry of an operation performed from the command line
+fn audit_entry(actor_id: i32, action: AuditAction, user_id: i32) -> AuditEntryForm {
+    operation_audit_entry(actor_id, action, format!("user:{}", user_id))
+}
+
+/// Starts an audit log entry of an operation performed from the command line on any target
+fn operation_audit_entry(actor_id: i32, action: AuditAction, target: String) -> AuditEntryForm {
+    AuditEntryForm {
+        actor_id,
+        action,
+        target,
+        before: None,
+        after: None,
+        reason: None,
+        request_id: None,
+        client_ip: None,
+        api_key_id: None,
+    }
+}
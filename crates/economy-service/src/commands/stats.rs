@@ -0,0 +1,22 @@
+use economy_service_core::get_economy_stats;
+use economy_service_migration::sea_orm::DbConn;
+
+use crate::CommandResult;
+
+pub(crate) async fn stats(conn: &DbConn) -> CommandResult {
+    let stats = get_economy_stats(conn).await?;
+
+    println!("Accounts:         {}", stats.accounts);
+    println!("Shared accounts:  {}", stats.shared_accounts);
+    println!("Bankers:          {}", stats.bankers);
+    println!("Frozen accounts:  {}", stats.frozen_accounts);
+    println!("Money supply:     {}", stats.total_balance);
+    println!("Minted:           {}", stats.minted);
+    println!("Burned:           {}", stats.burned);
+    println!("Imported:         {}", stats.imported);
+    println!("Payments:         {}", stats.payments);
+    println!("Payments volume:  {}", stats.payments_volume);
+    println!("Reversed:         {}", stats.reversed);
+
+    Ok(())
+}
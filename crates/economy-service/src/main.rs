@@ -1,3 +1,115 @@
-fn main() {
-    economy_service_api::main();
+mod commands;
+
+use clap::{Parser, Subcommand};
+use economy_service_migration::sea_orm::{Database, DbConn};
+use serde::Deserialize;
+use std::process::ExitCode;
+
+use crate::commands::{
+    AccountCommand, BankerCommand, ExportCommand, FreezeArgs, ImportArgs, MigrateCommand, MintArgs,
+    UnfreezeArgs,
+};
+
+/// Virtual currency payments gateway
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the HTTP and gRPC servers (default)
+    Serve,
+
+    #[command(flatten)]
+    Admin(AdminCommand),
+}
+
+/// Administrative commands, run directly against the database
+#[derive(Subcommand)]
+enum AdminCommand {
+    /// Apply or roll back database migrations
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+
+    /// Inspect accounts
+    #[command(subcommand)]
+    Account(AccountCommand),
+
+    /// Grant or revoke banker role
+    #[command(subcommand)]
+    Banker(BankerCommand),
+
+    /// Add money to an account
+    Mint(MintArgs),
+
+    /// Take money from an account
+    Burn(MintArgs),
+
+    /// Freeze an account
+    Freeze(FreezeArgs),
+
+    /// Lift the freeze of an account
+    Unfreeze(UnfreezeArgs),
+
+    /// Print economy statistics
+    Stats,
+
+    /// Export balances or transactions as CSV or newline-delimited JSON
+    #[command(subcommand)]
+    Export(ExportCommand),
+
+    /// Import opening balances from CSV or JSON
+    Import(ImportArgs),
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    database_url: String,
+}
+
+/// Result of an administrative command. Errors are printed to stderr.
+pub(crate) type CommandResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+async fn connect() -> Result<DbConn, Box<dyn std::error::Error + Send + Sync>> {
+    let config = envy::from_env::<Config>()?;
+    Ok(Database::connect(&config.database_url).await?)
+}
+
+async fn run(command: Command) -> CommandResult {
+    match command {
+        Command::Serve => economy_service_api::serve().await,
+        Command::Admin(command) => run_admin(command).await,
+    }
+}
+
+async fn run_admin(command: AdminCommand) -> CommandResult {
+    let conn = connect().await?;
+    match command {
+        AdminCommand::Migrate(command) => commands::migrate(command, &conn).await,
+        AdminCommand::Account(command) => commands::account(command, &conn).await,
+        AdminCommand::Banker(command) => commands::banker(command, &conn).await,
+        AdminCommand::Mint(args) => commands::mint(args, 1, &conn).await,
+        AdminCommand::Burn(args) => commands::mint(args, -1, &conn).await,
+        AdminCommand::Freeze(args) => commands::freeze(args, &conn).await,
+        AdminCommand::Unfreeze(args) => commands::unfreeze(args, &conn).await,
+        AdminCommand::Stats => commands::stats(&conn).await,
+        AdminCommand::Export(command) => commands::export(command, &conn).await,
+        AdminCommand::Import(args) => commands::import(args, &conn).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command.unwrap_or(Command::Serve)).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
 }
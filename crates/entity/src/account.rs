@@ -0,0 +1,59 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Kind of an account owned by players
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    #[sea_orm(string_value = "shop")]
+    Shop,
+
+    #[sea_orm(string_value = "company")]
+    Company,
+
+    /// Common treasury of a faction or town
+    #[sea_orm(string_value = "organization")]
+    Organization,
+}
+
+/// Account owned by players rather than belonging to a single user, such as a shop or a treasury
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "accounts")]
+pub struct Model {
+    /// Account ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Kind of the account
+    pub kind: AccountKind,
+
+    /// Name of the account
+    pub name: String,
+
+    /// Balance of the account
+    pub balance: i32,
+
+    /// ID of the user who created the account
+    pub created_by: i32,
+
+    /// Time the account was created at
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::account_member::Entity")]
+    AccountMember,
+}
+
+impl Related<super::account_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccountMember.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Freeze of user's account
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "account_freezes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    #[serde(skip)]
+    pub user_id: i32,
+
+    /// Reason of the freeze
+    pub reason: String,
+
+    /// ID of the user who froze the account
+    pub actor_id: i32,
+
+    /// Whether the account is unable to receive money as well
+    pub block_incoming: bool,
+
+    /// Time the account was frozen at
+    pub created_at: DateTimeUtc,
+
+    /// Time the freeze expires at, if any
+    pub expires_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,66 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Role of a user in an account
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRole {
+    /// Can spend money of the account and manage its members
+    #[sea_orm(string_value = "owner")]
+    Owner,
+
+    /// Can spend money of a shop or company account
+    #[sea_orm(string_value = "manager")]
+    Manager,
+
+    /// Can spend money of an organization account
+    #[sea_orm(string_value = "treasurer")]
+    Treasurer,
+
+    /// Can see an organization account and its spending
+    #[sea_orm(string_value = "member")]
+    Member,
+}
+
+/// Membership of a user in an account
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "account_members")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip)]
+    pub id: i32,
+
+    /// ID of the account
+    pub account_id: i32,
+
+    /// ID of the member
+    pub user_id: i32,
+
+    /// Role of the member
+    pub role: AccountRole,
+
+    /// Time the user became a member at
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::account::Entity",
+        from = "Column::AccountId",
+        to = "super::account::Column::Id"
+    )]
+    Account,
+}
+
+impl Related<super::account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
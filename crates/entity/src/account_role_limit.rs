@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::account_member::AccountRole;
+
+/// Limits on money a single member with the role can spend from an account. `None` means unlimited.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "account_role_limits")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip)]
+    pub id: i32,
+
+    #[serde(skip)]
+    pub account_id: i32,
+
+    /// Limited role
+    pub role: AccountRole,
+
+    /// Maximum single payment
+    pub per_transaction: Option<i32>,
+
+    /// Maximum spent by a member per UTC day
+    pub daily: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::account::Entity",
+        from = "Column::AccountId",
+        to = "super::account::Column::Id"
+    )]
+    Account,
+}
+
+impl Related<super::account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
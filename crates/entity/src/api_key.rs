@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Scopes granted to an API key, e.g. `read:balances`, `pay:from:<account ID>` or `mint`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, FromJsonQueryResult)]
+pub struct ApiKeyScopes(pub Vec<String>);
+
+/// Key that game servers and bots authenticate with instead of a player's token
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    /// API key ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Name describing who uses the key
+    pub name: String,
+
+    /// Beginning of the key, to tell keys apart
+    pub prefix: String,
+
+    /// SHA-256 hash of the key, which itself isn't stored
+    #[serde(skip)]
+    pub key_hash: String,
+
+    /// Scopes granted to the key
+    #[schema(value_type = Vec<String>)]
+    pub scopes: ApiKeyScopes,
+
+    /// ID of the admin who created the key
+    pub created_by: i32,
+
+    /// Time the key was created at
+    pub created_at: DateTimeUtc,
+
+    /// Time after which the key is rejected
+    pub expires_at: Option<DateTimeUtc>,
+
+    /// Time the key was last used at, updated at most once a minute
+    pub last_used_at: Option<DateTimeUtc>,
+
+    /// Time the key was revoked at
+    pub revoked_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,120 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Privileged operation recorded in the audit log
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "mint")]
+    Mint,
+
+    #[sea_orm(string_value = "burn")]
+    Burn,
+
+    #[sea_orm(string_value = "set_limits")]
+    SetLimits,
+
+    #[sea_orm(string_value = "freeze")]
+    Freeze,
+
+    #[sea_orm(string_value = "unfreeze")]
+    Unfreeze,
+
+    #[sea_orm(string_value = "create_webhook")]
+    CreateWebhook,
+
+    #[sea_orm(string_value = "delete_webhook")]
+    DeleteWebhook,
+
+    #[sea_orm(string_value = "grant_banker")]
+    GrantBanker,
+
+    #[sea_orm(string_value = "revoke_banker")]
+    RevokeBanker,
+
+    #[sea_orm(string_value = "import")]
+    Import,
+
+    #[sea_orm(string_value = "create_proposal")]
+    CreateProposal,
+
+    #[sea_orm(string_value = "approve_proposal")]
+    ApproveProposal,
+
+    #[sea_orm(string_value = "reject_proposal")]
+    RejectProposal,
+
+    #[sea_orm(string_value = "execute_proposal")]
+    ExecuteProposal,
+
+    #[sea_orm(string_value = "reverse")]
+    Reverse,
+
+    #[sea_orm(string_value = "mint_payout")]
+    MintPayout,
+
+    #[sea_orm(string_value = "create_api_key")]
+    CreateApiKey,
+
+    #[sea_orm(string_value = "revoke_api_key")]
+    RevokeApiKey,
+
+    #[sea_orm(string_value = "set_account_member")]
+    SetAccountMember,
+
+    #[sea_orm(string_value = "remove_account_member")]
+    RemoveAccountMember,
+
+    #[sea_orm(string_value = "set_account_limit")]
+    SetAccountLimit,
+}
+
+/// Entry of the audit log
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    /// Entry ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user who performed the operation, or created the API key it was performed with
+    pub actor_id: i32,
+
+    /// API key the operation was performed with, if any
+    pub api_key_id: Option<i32>,
+
+    /// Performed operation
+    pub action: AuditAction,
+
+    /// Object of the operation, e.g. `user:42`
+    pub target: String,
+
+    /// State of the target before the operation
+    #[schema(value_type = Object)]
+    pub before: Option<Json>,
+
+    /// State of the target after the operation
+    #[schema(value_type = Object)]
+    pub after: Option<Json>,
+
+    /// Reason given by the actor
+    pub reason: Option<String>,
+
+    /// ID of the request the operation was performed in
+    pub request_id: Option<String>,
+
+    /// IP address of the actor
+    pub client_ip: Option<String>,
+
+    /// Time the operation was performed at
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
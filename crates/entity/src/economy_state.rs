@@ -2,6 +2,23 @@ use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+/// Transfer limits tier of an account
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountTier {
+    #[sea_orm(string_value = "standard")]
+    Standard,
+
+    #[sea_orm(string_value = "verified")]
+    Verified,
+
+    #[sea_orm(string_value = "merchant")]
+    Merchant,
+}
+
 /// Economy state of user
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
 #[sea_orm(table_name = "economy_states")]
@@ -18,6 +35,18 @@ pub struct Model {
 
     /// Whether the user has banker permissions
     pub banker: bool,
+
+    /// Transfer limits tier of the account
+    pub tier: AccountTier,
+
+    #[serde(skip)]
+    pub limit_per_transaction: Option<i32>,
+
+    #[serde(skip)]
+    pub limit_daily: Option<i32>,
+
+    #[serde(skip)]
+    pub limit_window: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
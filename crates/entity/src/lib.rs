@@ -1 +1,13 @@
+pub mod account;
+pub mod account_freeze;
+pub mod account_member;
+pub mod account_role_limit;
+pub mod api_key;
+pub mod audit_log_entry;
 pub mod economy_state;
+pub mod loan;
+pub mod proposal;
+pub mod proposal_approval;
+pub mod transaction;
+pub mod webhook;
+pub mod webhook_delivery;
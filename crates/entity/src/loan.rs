@@ -0,0 +1,96 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Status of a loan
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum LoanStatus {
+    /// Offered by the lender, waiting for the borrower to accept it
+    #[sea_orm(string_value = "offered")]
+    Offered,
+
+    /// Accepted and being repaid
+    #[sea_orm(string_value = "active")]
+    Active,
+
+    /// Too many installments in a row were missed; still being collected
+    #[sea_orm(string_value = "defaulted")]
+    Defaulted,
+
+    /// Repaid in full
+    #[sea_orm(string_value = "repaid")]
+    Repaid,
+
+    /// Declined by the borrower or withdrawn by the lender
+    #[sea_orm(string_value = "declined")]
+    Declined,
+}
+
+/// Money lent by one player to another, repaid in installments from the borrower's balance
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "loans")]
+pub struct Model {
+    /// Loan ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user who lends the money
+    pub lender_id: i32,
+
+    /// ID of the user who borrows the money
+    pub borrower_id: i32,
+
+    /// Status of the loan
+    pub status: LoanStatus,
+
+    /// Amount of money lent
+    pub principal: i32,
+
+    /// Interest over the whole term in basis points, e.g. 500 for 5%
+    pub interest_rate_bps: i32,
+
+    /// Amount of installments the loan is repaid in
+    pub installments: i32,
+
+    /// Hours between two installments
+    pub interval_hours: i32,
+
+    /// Amount collected at every installment
+    pub installment_amount: i32,
+
+    /// Principal with interest and late fees
+    pub total_due: i32,
+
+    /// Amount repaid so far
+    pub repaid: i32,
+
+    /// Amount of missed installments, with late fees, collected along with the next one
+    pub overdue: i32,
+
+    /// Amount of installments missed in a row
+    pub missed_installments: i32,
+
+    /// Comment attached to the loan
+    pub comment: Option<String>,
+
+    /// Time the loan was offered at
+    pub created_at: DateTimeUtc,
+
+    /// Time the borrower accepted the loan at
+    pub accepted_at: Option<DateTimeUtc>,
+
+    /// Time the next installment is collected at
+    pub next_due_at: Option<DateTimeUtc>,
+
+    /// Time the loan was repaid or declined at
+    pub closed_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
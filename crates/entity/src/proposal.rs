@@ -0,0 +1,111 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Operation awaiting approval
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalKind {
+    /// Mint or burn by a banker
+    #[sea_orm(string_value = "mint")]
+    Mint,
+
+    /// Payment from an organization account
+    #[sea_orm(string_value = "treasury_payment")]
+    TreasuryPayment,
+}
+
+/// Status of a proposal
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Waiting for approvals
+    #[sea_orm(string_value = "pending")]
+    Pending,
+
+    /// Approved and being executed
+    #[sea_orm(string_value = "approved")]
+    Approved,
+
+    /// Approved and executed
+    #[sea_orm(string_value = "executed")]
+    Executed,
+
+    /// Approved, but the operation failed
+    #[sea_orm(string_value = "failed")]
+    Failed,
+
+    /// Rejected by an approver or withdrawn by the proposer
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+
+    /// Not approved in time
+    #[sea_orm(string_value = "expired")]
+    Expired,
+}
+
+/// Mint or payment that requires approval of several users before it's executed
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "proposals")]
+pub struct Model {
+    /// Proposal ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Proposed operation
+    pub kind: ProposalKind,
+
+    /// Status of the proposal
+    pub status: ProposalStatus,
+
+    /// ID of the user who proposed the operation
+    pub proposer_id: i32,
+
+    /// ID of the account the money is paid from, if any
+    pub account_id: Option<i32>,
+
+    /// Parameters of the operation
+    #[schema(value_type = Object)]
+    pub payload: Json,
+
+    /// Amount of distinct approvers needed
+    pub required_approvals: i32,
+
+    /// ID of the user who rejected the proposal
+    pub rejected_by: Option<i32>,
+
+    /// ID of the transaction made on execution
+    pub transaction_id: Option<i32>,
+
+    /// Reason the execution failed
+    pub error: Option<String>,
+
+    /// Time the proposal was made at
+    pub created_at: DateTimeUtc,
+
+    /// Time the proposal expires at unless approved
+    pub expires_at: DateTimeUtc,
+
+    /// Time the proposal was executed, rejected or expired at
+    pub resolved_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::proposal_approval::Entity")]
+    ProposalApproval,
+}
+
+impl Related<super::proposal_approval::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProposalApproval.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
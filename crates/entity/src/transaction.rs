@@ -0,0 +1,98 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Kind of balance movement
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    /// Money moved from payer to payee
+    #[sea_orm(string_value = "payment")]
+    Payment,
+
+    /// Money created on payee's account by a banker
+    #[sea_orm(string_value = "mint")]
+    Mint,
+
+    /// Money removed from payer's account by a banker
+    #[sea_orm(string_value = "burn")]
+    Burn,
+
+    /// Opening balance of payee, loaded by an import
+    #[sea_orm(string_value = "import")]
+    Import,
+
+    /// Money of a payment moved back from its payee (now payer) to its payer (now payee)
+    #[sea_orm(string_value = "reversal")]
+    Reversal,
+
+    /// Principal of a loan moved from lender to borrower
+    #[sea_orm(string_value = "loan")]
+    Loan,
+
+    /// Installment of a loan moved from borrower to lender
+    #[sea_orm(string_value = "repayment")]
+    Repayment,
+}
+
+/// Recorded balance movement
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "transactions")]
+pub struct Model {
+    /// Transaction ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Kind of the transaction
+    pub kind: TransactionKind,
+
+    /// User the money was taken from, if any
+    pub payer_id: Option<i32>,
+
+    /// User the money was given to, if any
+    pub payee_id: Option<i32>,
+
+    /// Account the money was taken from, if any
+    pub payer_account_id: Option<i32>,
+
+    /// Account the money was given to, if any
+    pub payee_account_id: Option<i32>,
+
+    /// User who spent money of the payer account, if any
+    pub initiator_id: Option<i32>,
+
+    /// API key the money of the payer account was spent with, if any
+    pub api_key_id: Option<i32>,
+
+    /// Amount of money moved
+    pub amount: i32,
+
+    /// Comment attached to the transaction
+    pub comment: Option<String>,
+
+    /// Time the transaction was committed
+    pub created_at: DateTimeUtc,
+
+    /// ID of the payment this transaction reverses, if any
+    pub reverses_id: Option<i32>,
+
+    /// Amount of the payment reversed so far
+    pub reversed_amount: i32,
+
+    /// ID of the loan this transaction disburses or repays, if any
+    pub loan_id: Option<i32>,
+
+    #[serde(skip)]
+    pub payer_balance: Option<i32>,
+
+    #[serde(skip)]
+    pub payee_balance: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
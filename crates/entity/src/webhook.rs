@@ -0,0 +1,71 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Balance event that webhooks can subscribe to
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// User received a payment
+    #[sea_orm(string_value = "payment_received")]
+    PaymentReceived,
+
+    /// Money was minted on user's account
+    #[sea_orm(string_value = "minted")]
+    Minted,
+
+    /// Money was burned from user's account
+    #[sea_orm(string_value = "burned")]
+    Burned,
+
+    /// Payment was reversed, partially or fully
+    #[sea_orm(string_value = "payment_reversed")]
+    PaymentReversed,
+}
+
+/// Events a webhook is subscribed to
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, FromJsonQueryResult)]
+pub struct WebhookEvents(pub Vec<WebhookEvent>);
+
+/// Outgoing webhook subscription
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "webhooks")]
+pub struct Model {
+    /// Webhook ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// URL the events are sent to
+    pub url: String,
+
+    /// Secret the payloads are signed with
+    #[serde(skip)]
+    pub secret: String,
+
+    /// Events the webhook is subscribed to
+    #[schema(value_type = Vec<WebhookEvent>)]
+    pub events: WebhookEvents,
+
+    /// ID of the user who created the webhook
+    pub created_by: i32,
+
+    /// Time the webhook was created at
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    Deliveries,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deliveries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,83 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::webhook::WebhookEvent;
+
+/// Status of a webhook delivery
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Deserialize, Serialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for the next attempt
+    #[sea_orm(string_value = "pending")]
+    Pending,
+
+    /// Receiver accepted the payload
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+
+    /// All attempts have failed
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+/// Delivery of an event to a webhook
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    /// Delivery ID
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the receiving webhook
+    pub webhook_id: i32,
+
+    /// Delivered event
+    pub event: WebhookEvent,
+
+    /// JSON body sent to the webhook
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+
+    /// Status of the delivery
+    pub status: DeliveryStatus,
+
+    /// Amount of attempts made
+    pub attempts: i32,
+
+    /// Time of the next attempt
+    pub next_attempt_at: DateTimeUtc,
+
+    /// HTTP status code of the last attempt, if the receiver responded
+    pub last_status_code: Option<i32>,
+
+    /// Error of the last attempt
+    pub last_error: Option<String>,
+
+    /// Time the event happened at
+    pub created_at: DateTimeUtc,
+
+    /// Time the receiver accepted the payload at
+    pub delivered_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook::Entity",
+        from = "Column::WebhookId",
+        to = "super::webhook::Column::Id"
+    )]
+    Webhook,
+}
+
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,12 +1,40 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20221210_000002_create_transactions_table;
+mod m20221210_000003_add_transfer_limits;
+mod m20221215_000004_create_account_freezes_table;
+mod m20221220_000005_create_audit_log_table;
+mod m20221225_000006_create_webhooks_tables;
+mod m20221228_000007_add_transaction_balances;
+mod m20230105_000008_create_accounts_tables;
+mod m20230110_000009_create_account_role_limits_table;
+mod m20230115_000010_create_proposals_tables;
+mod m20230120_000011_add_transaction_reversals;
+mod m20230125_000012_create_api_keys_table;
+mod m20230130_000013_create_loans_table;
+mod m20230205_000014_backfill_opening_balances;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20221210_000002_create_transactions_table::Migration),
+            Box::new(m20221210_000003_add_transfer_limits::Migration),
+            Box::new(m20221215_000004_create_account_freezes_table::Migration),
+            Box::new(m20221220_000005_create_audit_log_table::Migration),
+            Box::new(m20221225_000006_create_webhooks_tables::Migration),
+            Box::new(m20221228_000007_add_transaction_balances::Migration),
+            Box::new(m20230105_000008_create_accounts_tables::Migration),
+            Box::new(m20230110_000009_create_account_role_limits_table::Migration),
+            Box::new(m20230115_000010_create_proposals_tables::Migration),
+            Box::new(m20230120_000011_add_transaction_reversals::Migration),
+            Box::new(m20230125_000012_create_api_keys_table::Migration),
+            Box::new(m20230130_000013_create_loans_table::Migration),
+            Box::new(m20230205_000014_backfill_opening_balances::Migration),
+        ]
     }
 }
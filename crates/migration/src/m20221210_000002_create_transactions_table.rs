@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(Transactions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Transactions::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Transactions::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(Transactions::PayerId).integer())
+                    .col(ColumnDef::new(Transactions::PayeeId).integer())
+                    .col(ColumnDef::new(Transactions::Amount).integer().not_null())
+                    .col(ColumnDef::new(Transactions::Comment).text())
+                    .col(
+                        ColumnDef::new(Transactions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_transactions_payer_id_created_at")
+                    .table(Transactions::Table)
+                    .col(Transactions::PayerId)
+                    .col(Transactions::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_transactions_payee_id_created_at")
+                    .table(Transactions::Table)
+                    .col(Transactions::PayeeId)
+                    .col(Transactions::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                sea_query::Table::drop()
+                    .table(Transactions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    Id,
+    Kind,
+    PayerId,
+    PayeeId,
+    Amount,
+    Comment,
+    CreatedAt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One column per statement, as not every backend supports multiple alterations at once
+        let columns = [
+            ColumnDef::new(EconomyStates::Tier)
+                .string_len(16)
+                .not_null()
+                .default("standard")
+                .to_owned(),
+            ColumnDef::new(EconomyStates::LimitPerTransaction)
+                .integer()
+                .to_owned(),
+            ColumnDef::new(EconomyStates::LimitDaily)
+                .integer()
+                .to_owned(),
+            ColumnDef::new(EconomyStates::LimitWindow)
+                .integer()
+                .to_owned(),
+        ];
+
+        for mut column in columns {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(EconomyStates::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            EconomyStates::Tier,
+            EconomyStates::LimitPerTransaction,
+            EconomyStates::LimitDaily,
+            EconomyStates::LimitWindow,
+        ];
+
+        for column in columns {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(EconomyStates::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EconomyStates {
+    Table,
+    Tier,
+    LimitPerTransaction,
+    LimitDaily,
+    LimitWindow,
+}
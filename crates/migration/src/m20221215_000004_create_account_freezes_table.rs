@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(AccountFreezes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AccountFreezes::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountFreezes::UserId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(AccountFreezes::Reason).text().not_null())
+                    .col(ColumnDef::new(AccountFreezes::ActorId).integer().not_null())
+                    .col(
+                        ColumnDef::new(AccountFreezes::BlockIncoming)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(AccountFreezes::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccountFreezes::ExpiresAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                sea_query::Table::drop()
+                    .table(AccountFreezes::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AccountFreezes {
+    Table,
+    Id,
+    UserId,
+    Reason,
+    ActorId,
+    BlockIncoming,
+    CreatedAt,
+    ExpiresAt,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(AuditLog::ActorId).integer().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string_len(32).not_null())
+                    .col(ColumnDef::new(AuditLog::Target).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Before).json())
+                    .col(ColumnDef::new(AuditLog::After).json())
+                    .col(ColumnDef::new(AuditLog::Reason).text())
+                    .col(ColumnDef::new(AuditLog::RequestId).string())
+                    .col(ColumnDef::new(AuditLog::ClientIp).string())
+                    .col(
+                        ColumnDef::new(AuditLog::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, column) in [
+            ("idx_audit_log_actor_id", AuditLog::ActorId),
+            ("idx_audit_log_target", AuditLog::Target),
+            ("idx_audit_log_created_at", AuditLog::CreatedAt),
+        ] {
+            manager
+                .create_index(
+                    sea_query::Index::create()
+                        .name(name)
+                        .table(AuditLog::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Keep the log append-only at the database level as well
+        if manager.get_database_backend() == DbBackend::Postgres {
+            for sql in [
+                "CREATE RULE audit_log_no_update AS ON UPDATE TO audit_log DO INSTEAD NOTHING",
+                "CREATE RULE audit_log_no_delete AS ON DELETE TO audit_log DO INSTEAD NOTHING",
+            ] {
+                manager
+                    .get_connection()
+                    .execute(Statement::from_string(DbBackend::Postgres, sql.to_owned()))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(sea_query::Table::drop().table(AuditLog::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    ActorId,
+    Action,
+    Target,
+    Before,
+    After,
+    Reason,
+    RequestId,
+    ClientIp,
+    CreatedAt,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Transactions::PayerBalance, Transactions::PayeeBalance] {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(Transactions::Table)
+                        .add_column(ColumnDef::new(column).integer())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Transactions::PayerBalance, Transactions::PayeeBalance] {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(Transactions::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    PayerBalance,
+    PayeeBalance,
+}
@@ -0,0 +1,212 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(Accounts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Accounts::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Accounts::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(Accounts::Name).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(Accounts::Balance)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Accounts::CreatedBy).integer().not_null())
+                    .col(
+                        ColumnDef::new(Accounts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(AccountMembers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AccountMembers::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountMembers::AccountId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccountMembers::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(AccountMembers::Role)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountMembers::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_account_members_account_id")
+                            .from(AccountMembers::Table, AccountMembers::AccountId)
+                            .to(Accounts::Table, Accounts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_account_members_account_id_user_id")
+                    .table(AccountMembers::Table)
+                    .col(AccountMembers::AccountId)
+                    .col(AccountMembers::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_account_members_user_id")
+                    .table(AccountMembers::Table)
+                    .col(AccountMembers::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        for column in [
+            Transactions::PayerAccountId,
+            Transactions::PayeeAccountId,
+            Transactions::InitiatorId,
+        ] {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(Transactions::Table)
+                        .add_column(ColumnDef::new(column).integer())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for (name, column) in [
+            (
+                "idx_transactions_payer_account_id",
+                Transactions::PayerAccountId,
+            ),
+            (
+                "idx_transactions_payee_account_id",
+                Transactions::PayeeAccountId,
+            ),
+        ] {
+            manager
+                .create_index(
+                    sea_query::Index::create()
+                        .name(name)
+                        .table(Transactions::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [
+            "idx_transactions_payer_account_id",
+            "idx_transactions_payee_account_id",
+        ] {
+            manager
+                .drop_index(
+                    sea_query::Index::drop()
+                        .name(name)
+                        .table(Transactions::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for column in [
+            Transactions::PayerAccountId,
+            Transactions::PayeeAccountId,
+            Transactions::InitiatorId,
+        ] {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(Transactions::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .drop_table(
+                sea_query::Table::drop()
+                    .table(AccountMembers::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(sea_query::Table::drop().table(Accounts::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Accounts {
+    Table,
+    Id,
+    Kind,
+    Name,
+    Balance,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum AccountMembers {
+    Table,
+    Id,
+    AccountId,
+    UserId,
+    Role,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    PayerAccountId,
+    PayeeAccountId,
+    InitiatorId,
+}
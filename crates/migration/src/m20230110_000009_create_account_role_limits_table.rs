@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(AccountRoleLimits::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AccountRoleLimits::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountRoleLimits::AccountId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AccountRoleLimits::Role)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccountRoleLimits::PerTransaction).integer())
+                    .col(ColumnDef::new(AccountRoleLimits::Daily).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_account_role_limits_account_id")
+                            .from(AccountRoleLimits::Table, AccountRoleLimits::AccountId)
+                            .to(Accounts::Table, Accounts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_account_role_limits_account_id_role")
+                    .table(AccountRoleLimits::Table)
+                    .col(AccountRoleLimits::AccountId)
+                    .col(AccountRoleLimits::Role)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                sea_query::Table::drop()
+                    .table(AccountRoleLimits::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Accounts {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum AccountRoleLimits {
+    Table,
+    Id,
+    AccountId,
+    Role,
+    PerTransaction,
+    Daily,
+}
@@ -0,0 +1,155 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(Proposals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Proposals::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Proposals::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(Proposals::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(Proposals::ProposerId).integer().not_null())
+                    .col(ColumnDef::new(Proposals::AccountId).integer())
+                    .col(ColumnDef::new(Proposals::Payload).json().not_null())
+                    .col(
+                        ColumnDef::new(Proposals::RequiredApprovals)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Proposals::RejectedBy).integer())
+                    .col(ColumnDef::new(Proposals::TransactionId).integer())
+                    .col(ColumnDef::new(Proposals::Error).text())
+                    .col(
+                        ColumnDef::new(Proposals::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Proposals::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Proposals::ResolvedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_proposals_status_expires_at")
+                    .table(Proposals::Table)
+                    .col(Proposals::Status)
+                    .col(Proposals::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(ProposalApprovals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProposalApprovals::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(
+                        ColumnDef::new(ProposalApprovals::ProposalId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProposalApprovals::ApproverId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProposalApprovals::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_proposal_approvals_proposal_id")
+                            .from(ProposalApprovals::Table, ProposalApprovals::ProposalId)
+                            .to(Proposals::Table, Proposals::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_proposal_approvals_proposal_id_approver_id")
+                    .table(ProposalApprovals::Table)
+                    .col(ProposalApprovals::ProposalId)
+                    .col(ProposalApprovals::ApproverId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                sea_query::Table::drop()
+                    .table(ProposalApprovals::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(sea_query::Table::drop().table(Proposals::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Proposals {
+    Table,
+    Id,
+    Kind,
+    Status,
+    ProposerId,
+    AccountId,
+    Payload,
+    RequiredApprovals,
+    RejectedBy,
+    TransactionId,
+    Error,
+    CreatedAt,
+    ExpiresAt,
+    ResolvedAt,
+}
+
+#[derive(Iden)]
+enum ProposalApprovals {
+    Table,
+    Id,
+    ProposalId,
+    ApproverId,
+    CreatedAt,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .add_column(ColumnDef::new(Transactions::ReversesId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .add_column(
+                        ColumnDef::new(Transactions::ReversedAmount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_transactions_reverses_id")
+                    .table(Transactions::Table)
+                    .col(Transactions::ReversesId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                sea_query::Index::drop()
+                    .name("idx_transactions_reverses_id")
+                    .table(Transactions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for column in [Transactions::ReversesId, Transactions::ReversedAmount] {
+            manager
+                .alter_table(
+                    sea_query::Table::alter()
+                        .table(Transactions::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    ReversesId,
+    ReversedAmount,
+}
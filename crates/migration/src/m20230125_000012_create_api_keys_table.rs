@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(ApiKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiKeys::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(ApiKeys::Name).string().not_null())
+                    .col(ColumnDef::new(ApiKeys::Prefix).string_len(16).not_null())
+                    .col(ColumnDef::new(ApiKeys::KeyHash).string_len(64).not_null())
+                    .col(ColumnDef::new(ApiKeys::Scopes).json().not_null())
+                    .col(ColumnDef::new(ApiKeys::CreatedBy).integer().not_null())
+                    .col(
+                        ColumnDef::new(ApiKeys::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApiKeys::ExpiresAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ApiKeys::LastUsedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ApiKeys::RevokedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_api_keys_key_hash")
+                    .table(ApiKeys::Table)
+                    .col(ApiKeys::KeyHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // operations performed with a key are attributed to it
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .add_column(ColumnDef::new(Transactions::ApiKeyId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(AuditLog::Table)
+                    .add_column(ColumnDef::new(AuditLog::ApiKeyId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(AuditLog::Table)
+                    .drop_column(AuditLog::ApiKeyId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .drop_column(Transactions::ApiKeyId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(sea_query::Table::drop().table(ApiKeys::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ApiKeys {
+    Table,
+    Id,
+    Name,
+    Prefix,
+    KeyHash,
+    Scopes,
+    CreatedBy,
+    CreatedAt,
+    ExpiresAt,
+    LastUsedAt,
+    RevokedAt,
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    ApiKeyId,
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    ApiKeyId,
+}
@@ -0,0 +1,143 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                sea_query::Table::create()
+                    .table(Loans::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Loans::Id)
+                            .integer()
+                            .primary_key()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Loans::LenderId).integer().not_null())
+                    .col(ColumnDef::new(Loans::BorrowerId).integer().not_null())
+                    .col(ColumnDef::new(Loans::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(Loans::Principal).integer().not_null())
+                    .col(ColumnDef::new(Loans::InterestRateBps).integer().not_null())
+                    .col(ColumnDef::new(Loans::Installments).integer().not_null())
+                    .col(ColumnDef::new(Loans::IntervalHours).integer().not_null())
+                    .col(
+                        ColumnDef::new(Loans::InstallmentAmount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Loans::TotalDue).integer().not_null())
+                    .col(ColumnDef::new(Loans::Repaid).integer().not_null())
+                    .col(ColumnDef::new(Loans::Overdue).integer().not_null())
+                    .col(
+                        ColumnDef::new(Loans::MissedInstallments)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Loans::Comment).text())
+                    .col(
+                        ColumnDef::new(Loans::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Loans::AcceptedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Loans::NextDueAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Loans::ClosedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_loans_status_next_due_at")
+                    .table(Loans::Table)
+                    .col(Loans::Status)
+                    .col(Loans::NextDueAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_loans_borrower_id")
+                    .table(Loans::Table)
+                    .col(Loans::BorrowerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                sea_query::Index::create()
+                    .name("idx_loans_lender_id")
+                    .table(Loans::Table)
+                    .col(Loans::LenderId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // disbursements and repayments are linked to their loan
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .add_column(ColumnDef::new(Transactions::LoanId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                sea_query::Table::alter()
+                    .table(Transactions::Table)
+                    .drop_column(Transactions::LoanId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(sea_query::Table::drop().table(Loans::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Loans {
+    Table,
+    Id,
+    LenderId,
+    BorrowerId,
+    Status,
+    Principal,
+    InterestRateBps,
+    Installments,
+    IntervalHours,
+    InstallmentAmount,
+    TotalDue,
+    Repaid,
+    Overdue,
+    MissedInstallments,
+    Comment,
+    CreatedAt,
+    AcceptedAt,
+    NextDueAt,
+    ClosedAt,
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    LoanId,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DbBackend, Statement},
+};
+
+/// Comment of the transactions recording balances held before the ledger was introduced
+const OPENING_BALANCE: &str = "Opening balance before the ledger";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Records the part of every balance the ledger doesn't account for, which users held
+    /// before transactions were recorded, as an import (or a burn if the balance is lower).
+    /// Reconciliation would report such balances as drifted otherwise.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        let now = match backend {
+            DbBackend::Sqlite => "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+            _ => "CURRENT_TIMESTAMP",
+        };
+
+        // part of every balance the ledger of the user doesn't account for
+        let drifts = "SELECT s.user_id, s.balance, s.balance - \
+                (SELECT COALESCE(SUM(t.amount), 0) FROM transactions t WHERE t.payee_id = s.user_id) + \
+                (SELECT COALESCE(SUM(t.amount), 0) FROM transactions t WHERE t.payer_id = s.user_id) \
+                AS drift \
+            FROM economy_states s";
+
+        // money above what the ledger says is imported, money below it is burned
+        let movements = [
+            (
+                "import",
+                "payee_id",
+                "payee_balance",
+                "d.drift",
+                "d.drift > 0",
+            ),
+            (
+                "burn",
+                "payer_id",
+                "payer_balance",
+                "-d.drift",
+                "d.drift < 0",
+            ),
+        ];
+        for (kind, party, balance, amount, condition) in movements {
+            let sql = format!(
+                "INSERT INTO transactions (kind, {party}, amount, comment, created_at, {balance}) \
+                 SELECT '{kind}', d.user_id, {amount}, '{comment}', {now}, d.balance \
+                 FROM ({drifts}) d WHERE {condition}",
+                kind = kind,
+                party = party,
+                balance = balance,
+                amount = amount,
+                comment = OPENING_BALANCE,
+                now = now,
+                drifts = drifts,
+                condition = condition,
+            );
+            manager
+                .get_connection()
+                .execute(Statement::from_string(backend, sql))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                sea_query::Query::delete()
+                    .from_table(Transactions::Table)
+                    .and_where(Expr::col(Transactions::Comment).eq(OPENING_BALANCE))
+                    .and_where(Expr::col(Transactions::Kind).is_in(["import", "burn"]))
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Transactions {
+    Table,
+    Kind,
+    Comment,
+}
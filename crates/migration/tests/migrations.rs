@@ -0,0 +1,65 @@
+use economy_service_migration::{
+    sea_orm::{ConnectionTrait, Database, DbConn, Statement},
+    Migrator, MigratorTrait,
+};
+
+/// Definitions of tables and indexes, besides internal ones and the table tracking migrations
+async fn schema(conn: &DbConn) -> Vec<(String, String)> {
+    let rows = conn
+        .query_all(Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT name, sql FROM sqlite_master WHERE sql IS NOT NULL \
+             AND name NOT LIKE 'sqlite_%' AND tbl_name <> 'seaql_migrations' ORDER BY name"
+                .to_owned(),
+        ))
+        .await
+        .unwrap();
+    rows.iter()
+        .map(|row| {
+            (
+                row.try_get("", "name").unwrap(),
+                row.try_get("", "sql").unwrap(),
+            )
+        })
+        .collect()
+}
+
+async fn applied(conn: &DbConn) -> usize {
+    Migrator::get_applied_migrations(conn).await.unwrap().len()
+}
+
+#[tokio::test]
+async fn migrations_roll_back() {
+    let db_path =
+        std::env::temp_dir().join(format!("economy-migrations-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&db_path);
+    let conn = Database::connect(format!("sqlite://{}?mode=rwc", db_path.display()))
+        .await
+        .unwrap();
+
+    // schema before each migration is applied
+    let mut schemas = Vec::new();
+    for _ in Migrator::migrations() {
+        schemas.push(schema(&conn).await);
+        Migrator::up(&conn, Some(1)).await.unwrap();
+    }
+    assert!(Migrator::get_pending_migrations(&conn)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // rolling a migration back restores the schema it was applied to
+    for (index, before) in schemas.iter().enumerate().rev() {
+        let name = Migrator::migrations()[index].name().to_owned();
+        Migrator::down(&conn, Some(1)).await.unwrap();
+        assert_eq!(applied(&conn).await, index, "rolling back {}", name);
+        assert_eq!(&schema(&conn).await, before, "rolling back {}", name);
+    }
+    assert!(schema(&conn).await.is_empty());
+
+    // and the schema can be migrated again
+    Migrator::up(&conn, None).await.unwrap();
+    assert_eq!(applied(&conn).await, schemas.len());
+
+    let _ = std::fs::remove_file(&db_path);
+}
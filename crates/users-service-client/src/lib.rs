@@ -1,5 +1,10 @@
+use futures::{stream, StreamExt, TryStreamExt};
 use reqwest::StatusCode;
 use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Amount of concurrent lookups of [`UsersServiceClient::get_usernames`]
+const LOOKUP_CONCURRENCY: usize = 16;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct User {
@@ -63,6 +68,41 @@ impl UsersServiceClient {
         .await
     }
 
+    /// Looks up usernames of the users, leaving out the ones that don't exist.
+    /// Fails if any of the lookups fails.
+    pub async fn get_usernames(
+        &self,
+        ids: impl IntoIterator<Item = i32>,
+    ) -> Result<HashMap<i32, String>, reqwest::Error> {
+        stream::iter(ids)
+            .map(|id| async move {
+                match self.get_user(id).await? {
+                    GetUserResponse::Ok(user) => Ok(Some((id, user.username))),
+                    _ => Ok(None),
+                }
+            })
+            .buffer_unordered(LOOKUP_CONCURRENCY)
+            .try_filter_map(|entry| async move { Ok(entry) })
+            .try_collect()
+            .await
+    }
+
+    /// Looks up the user by their username, which the users service stores in lowercase.
+    /// Usernames are sent as they are, so they should be lowercased and only contain
+    /// URL-safe characters.
+    pub async fn get_user_by_username(
+        &self,
+        username: &str,
+    ) -> Result<GetUserResponse, reqwest::Error> {
+        GetUserResponse::from_http_response(
+            self.client
+                .get(format!("{}/by-username/{}", self.base_url, username))
+                .send()
+                .await?,
+        )
+        .await
+    }
+
     pub async fn get_self(
         &self,
         token: impl Into<String>,